@@ -48,6 +48,43 @@ pub enum BlackBoxFunc {
     /// Compute a recursive aggregation object when verifying a proof inside another circuit.
     /// This outputted aggregation object will then be either checked in a top-level verifier or aggregated upon again.
     RecursiveAggregation,
+    /// Applies the Poseidon2 permutation to a state of [`FieldElement`][acir_field::FieldElement]s.
+    ///
+    /// The parameters of the permutation, such as the number of rounds, depend on the field and the width of the state.
+    Poseidon2Permutation,
+    /// Verifies an EdDSA signature over the Baby JubJub curve, using the Poseidon hash as in circomlib.
+    ///
+    /// Baby JubJub is defined over the BN254 scalar field, so this is only supported by ACIR circuits over BN254.
+    EddsaVerify,
+    /// Adds two points on the embedded curve on which [`FieldElement`][acir_field::FieldElement] is defined.
+    ///
+    /// The point at infinity is represented by the coordinates the backend uses for it, e.g. `(0, 0)` on Grumpkin.
+    EmbeddedCurveAdd,
+    /// Multiplies a point on the embedded curve on which [`FieldElement`][acir_field::FieldElement] is defined by a scalar.
+    VariableBaseScalarMul,
+    /// Adds two big integers modulo a third, all of which are given as big-endian bytes.
+    ///
+    /// This allows arithmetic modulo a prime, or an RSA modulus, which differs from the field modulus.
+    /// The result has as many bytes as the modulus.
+    BigIntAdd,
+    /// Multiplies two big integers modulo a third, all of which are given as big-endian bytes.
+    ///
+    /// The result has as many bytes as the modulus.
+    BigIntMul,
+    /// Reduces a big integer modulo another, both of which are given as big-endian bytes.
+    ///
+    /// The result has as many bytes as the modulus.
+    BigIntMod,
+    /// Calculates the SHA512 hash of the inputs.
+    SHA512,
+    /// Calculates the Blake3 hash of the inputs.
+    Blake3,
+    /// Encrypts the inputs using AES-128 in CBC mode, padding them to a whole number of blocks following PKCS#7.
+    AES128CbcEncrypt,
+    /// Encrypts the inputs using AES-128 in CTR mode, where the IV is the initial counter block.
+    AES128CtrEncrypt,
+    /// Recovers the public key which produced an ECDSA signature over the secp256k1 curve from its recovery id.
+    EcdsaSecp256k1Recover,
 }
 
 impl std::fmt::Display for BlackBoxFunc {
@@ -57,14 +94,19 @@ impl std::fmt::Display for BlackBoxFunc {
 }
 
 impl BlackBoxFunc {
-    pub fn name(&self) -> &'static str {
+    pub const fn name(&self) -> &'static str {
         match self {
             BlackBoxFunc::SHA256 => "sha256",
             BlackBoxFunc::SchnorrVerify => "schnorr_verify",
             BlackBoxFunc::Blake2s => "blake2s",
+            BlackBoxFunc::SHA512 => "sha512",
+            BlackBoxFunc::Blake3 => "blake3",
+            BlackBoxFunc::AES128CbcEncrypt => "aes128_cbc_encrypt",
+            BlackBoxFunc::AES128CtrEncrypt => "aes128_ctr_encrypt",
             BlackBoxFunc::Pedersen => "pedersen",
             BlackBoxFunc::HashToField128Security => "hash_to_field_128_security",
             BlackBoxFunc::EcdsaSecp256k1 => "ecdsa_secp256k1",
+            BlackBoxFunc::EcdsaSecp256k1Recover => "ecdsa_secp256k1_recover",
             BlackBoxFunc::FixedBaseScalarMul => "fixed_base_scalar_mul",
             BlackBoxFunc::AND => "and",
             BlackBoxFunc::XOR => "xor",
@@ -72,6 +114,13 @@ impl BlackBoxFunc {
             BlackBoxFunc::Keccak256 => "keccak256",
             BlackBoxFunc::RecursiveAggregation => "recursive_aggregation",
             BlackBoxFunc::EcdsaSecp256r1 => "ecdsa_secp256r1",
+            BlackBoxFunc::Poseidon2Permutation => "poseidon2_permutation",
+            BlackBoxFunc::EddsaVerify => "eddsa_verify",
+            BlackBoxFunc::EmbeddedCurveAdd => "embedded_curve_add",
+            BlackBoxFunc::VariableBaseScalarMul => "variable_base_scalar_mul",
+            BlackBoxFunc::BigIntAdd => "bigint_add",
+            BlackBoxFunc::BigIntMul => "bigint_mul",
+            BlackBoxFunc::BigIntMod => "bigint_mod",
         }
     }
     pub fn lookup(op_name: &str) -> Option<BlackBoxFunc> {
@@ -79,9 +128,14 @@ impl BlackBoxFunc {
             "sha256" => Some(BlackBoxFunc::SHA256),
             "schnorr_verify" => Some(BlackBoxFunc::SchnorrVerify),
             "blake2s" => Some(BlackBoxFunc::Blake2s),
+            "sha512" => Some(BlackBoxFunc::SHA512),
+            "blake3" => Some(BlackBoxFunc::Blake3),
+            "aes128_cbc_encrypt" => Some(BlackBoxFunc::AES128CbcEncrypt),
+            "aes128_ctr_encrypt" => Some(BlackBoxFunc::AES128CtrEncrypt),
             "pedersen" => Some(BlackBoxFunc::Pedersen),
             "hash_to_field_128_security" => Some(BlackBoxFunc::HashToField128Security),
             "ecdsa_secp256k1" => Some(BlackBoxFunc::EcdsaSecp256k1),
+            "ecdsa_secp256k1_recover" => Some(BlackBoxFunc::EcdsaSecp256k1Recover),
             "ecdsa_secp256r1" => Some(BlackBoxFunc::EcdsaSecp256r1),
             "fixed_base_scalar_mul" => Some(BlackBoxFunc::FixedBaseScalarMul),
             "and" => Some(BlackBoxFunc::AND),
@@ -89,6 +143,13 @@ impl BlackBoxFunc {
             "range" => Some(BlackBoxFunc::RANGE),
             "keccak256" => Some(BlackBoxFunc::Keccak256),
             "recursive_aggregation" => Some(BlackBoxFunc::RecursiveAggregation),
+            "poseidon2_permutation" => Some(BlackBoxFunc::Poseidon2Permutation),
+            "eddsa_verify" => Some(BlackBoxFunc::EddsaVerify),
+            "embedded_curve_add" => Some(BlackBoxFunc::EmbeddedCurveAdd),
+            "variable_base_scalar_mul" => Some(BlackBoxFunc::VariableBaseScalarMul),
+            "bigint_add" => Some(BlackBoxFunc::BigIntAdd),
+            "bigint_mul" => Some(BlackBoxFunc::BigIntMul),
+            "bigint_mod" => Some(BlackBoxFunc::BigIntMod),
             _ => None,
         }
     }
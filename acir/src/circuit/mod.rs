@@ -3,7 +3,8 @@ pub mod brillig;
 pub mod directives;
 pub mod opcodes;
 
-use crate::native_types::Witness;
+use crate::native_types::{Witness, WitnessMap};
+use acir_field::FieldElement;
 pub use opcodes::Opcode;
 use thiserror::Error;
 
@@ -199,6 +200,33 @@ impl PublicInputs {
     pub fn contains(&self, index: usize) -> bool {
         self.0.contains(&Witness(index as u32))
     }
+
+    /// Returns the value assigned to each public input in `witness_map`, ordered by witness index.
+    pub fn values(&self, witness_map: &WitnessMap) -> Result<Vec<FieldElement>, PublicInputsError> {
+        self.0.iter().map(|witness| public_input_value(witness_map, *witness)).collect()
+    }
+
+    /// Returns a [`WitnessMap`] containing only the public inputs from `witness_map`.
+    pub fn witness_map(&self, witness_map: &WitnessMap) -> Result<WitnessMap, PublicInputsError> {
+        let mut public_witness = WitnessMap::new();
+        for witness in &self.0 {
+            public_witness.insert(*witness, public_input_value(witness_map, *witness)?);
+        }
+        Ok(public_witness)
+    }
+}
+
+fn public_input_value(
+    witness_map: &WitnessMap,
+    witness: Witness,
+) -> Result<FieldElement, PublicInputsError> {
+    witness_map.get(&witness).copied().ok_or(PublicInputsError::MissingWitness(witness))
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicInputsError {
+    #[error("Failed to extract witness {} from witness map. Witness not found.", .0.witness_index())]
+    MissingWitness(Witness),
 }
 
 #[cfg(test)]
@@ -207,9 +235,9 @@ mod tests {
 
     use super::{
         opcodes::{BlackBoxFuncCall, FunctionInput},
-        Circuit, Opcode, PublicInputs,
+        Circuit, Opcode, PublicInputs, PublicInputsError,
     };
-    use crate::native_types::Witness;
+    use crate::native_types::{Witness, WitnessMap};
     use acir_field::FieldElement;
 
     fn and_opcode() -> Opcode {
@@ -271,4 +299,45 @@ mod tests {
         let deserialized = serde_json::from_str(&json).unwrap();
         assert_eq!(circuit, deserialized);
     }
+
+    #[test]
+    fn extracts_public_inputs() {
+        let circuit = Circuit {
+            public_parameters: PublicInputs(BTreeSet::from([Witness(3), Witness(1)])),
+            return_values: PublicInputs(BTreeSet::from([Witness(2)])),
+            ..Circuit::default()
+        };
+        let witness_map = WitnessMap::from(std::collections::BTreeMap::from([
+            (Witness(1), FieldElement::from(10u128)),
+            (Witness(2), FieldElement::from(20u128)),
+            (Witness(3), FieldElement::from(30u128)),
+            (Witness(4), FieldElement::from(40u128)),
+        ]));
+
+        assert_eq!(
+            circuit.public_parameters.values(&witness_map),
+            Ok(vec![FieldElement::from(10u128), FieldElement::from(30u128)])
+        );
+        assert_eq!(
+            circuit.return_values.values(&witness_map),
+            Ok(vec![FieldElement::from(20u128)])
+        );
+
+        let public_witness = circuit.public_inputs().witness_map(&witness_map).unwrap();
+        assert_eq!(
+            public_witness.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Witness(1), FieldElement::from(10u128)),
+                (Witness(2), FieldElement::from(20u128)),
+                (Witness(3), FieldElement::from(30u128)),
+            ]
+        );
+
+        let partial_witness =
+            WitnessMap::from(std::collections::BTreeMap::from([(Witness(1), FieldElement::one())]));
+        assert_eq!(
+            circuit.public_inputs().values(&partial_witness),
+            Err(PublicInputsError::MissingWitness(Witness(2)))
+        );
+    }
 }
@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 
 // Note: Some functions will not use all of the witness
 // So we need to supply how many bits of the witness is needed
+//
+// Hash functions read `ceil(num_bits / 8)` bytes of their message from each input, in little-endian order,
+// and fail if the value of an input does not fit in `num_bits` bits.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FunctionInput {
     pub witness: Witness,
@@ -111,6 +114,93 @@ pub enum BlackBoxFuncCall {
         /// will be the input aggregation object of the next recursive aggregation.
         output_aggregation_object: Vec<Witness>,
     },
+    /// Applies the Poseidon2 permutation to `inputs`, which hold the `len` elements of the state,
+    /// and writes the permuted state to `outputs`.
+    Poseidon2Permutation {
+        inputs: Vec<FunctionInput>,
+        outputs: Vec<Witness>,
+        len: u32,
+    },
+    /// Verifies an EdDSA signature `(signature_r8, signature_s)` over the Baby JubJub curve,
+    /// writing whether the signature is valid for the public key and `message` to `output`.
+    EddsaVerify {
+        public_key_x: FunctionInput,
+        public_key_y: FunctionInput,
+        signature_s: FunctionInput,
+        signature_r8_x: FunctionInput,
+        signature_r8_y: FunctionInput,
+        message: FunctionInput,
+        output: Witness,
+    },
+    EmbeddedCurveAdd {
+        input1_x: FunctionInput,
+        input1_y: FunctionInput,
+        input2_x: FunctionInput,
+        input2_y: FunctionInput,
+        outputs: (Witness, Witness),
+    },
+    VariableBaseScalarMul {
+        point_x: FunctionInput,
+        point_y: FunctionInput,
+        scalar: FunctionInput,
+        outputs: (Witness, Witness),
+    },
+    BigIntAdd {
+        lhs: Vec<FunctionInput>,
+        rhs: Vec<FunctionInput>,
+        modulus: Vec<FunctionInput>,
+        /// The bytes of the result, of which there must be as many as there are bytes of the modulus.
+        outputs: Vec<Witness>,
+    },
+    BigIntMul {
+        lhs: Vec<FunctionInput>,
+        rhs: Vec<FunctionInput>,
+        modulus: Vec<FunctionInput>,
+        /// The bytes of the result, of which there must be as many as there are bytes of the modulus.
+        outputs: Vec<Witness>,
+    },
+    BigIntMod {
+        input: Vec<FunctionInput>,
+        modulus: Vec<FunctionInput>,
+        /// The bytes of the result, of which there must be as many as there are bytes of the modulus.
+        outputs: Vec<Witness>,
+    },
+    SHA512 {
+        inputs: Vec<FunctionInput>,
+        outputs: Vec<Witness>,
+    },
+    Blake3 {
+        inputs: Vec<FunctionInput>,
+        outputs: Vec<Witness>,
+    },
+    /// Encrypts `inputs` using AES-128 in CBC mode with the 16 byte `iv` and `key`.
+    ///
+    /// The inputs are padded following PKCS#7, so there is one output per input rounded up to the next whole block.
+    AES128CbcEncrypt {
+        inputs: Vec<FunctionInput>,
+        iv: Vec<FunctionInput>,
+        key: Vec<FunctionInput>,
+        outputs: Vec<Witness>,
+    },
+    /// Encrypts `inputs` using AES-128 in CTR mode with the 16 byte initial counter block `iv` and `key`,
+    /// producing one output per input.
+    AES128CtrEncrypt {
+        inputs: Vec<FunctionInput>,
+        iv: Vec<FunctionInput>,
+        key: Vec<FunctionInput>,
+        outputs: Vec<Witness>,
+    },
+    /// Recovers the public key which produced `signature` over the 32 byte `hashed_message`, writing the 32 byte
+    /// big-endian coordinates of the key to `public_key_x` and `public_key_y`.
+    ///
+    /// The `recovery_id` is the parity of the y coordinate of the signature's nonce point.
+    EcdsaSecp256k1Recover {
+        hashed_message: Vec<FunctionInput>,
+        signature: Vec<FunctionInput>,
+        recovery_id: FunctionInput,
+        public_key_x: Vec<Witness>,
+        public_key_y: Vec<Witness>,
+    },
 }
 
 impl BlackBoxFuncCall {
@@ -130,6 +220,20 @@ impl BlackBoxFuncCall {
             BlackBoxFunc::RANGE => BlackBoxFuncCall::RANGE { input: FunctionInput::dummy() },
             BlackBoxFunc::SHA256 => BlackBoxFuncCall::SHA256 { inputs: vec![], outputs: vec![] },
             BlackBoxFunc::Blake2s => BlackBoxFuncCall::Blake2s { inputs: vec![], outputs: vec![] },
+            BlackBoxFunc::SHA512 => BlackBoxFuncCall::SHA512 { inputs: vec![], outputs: vec![] },
+            BlackBoxFunc::Blake3 => BlackBoxFuncCall::Blake3 { inputs: vec![], outputs: vec![] },
+            BlackBoxFunc::AES128CbcEncrypt => BlackBoxFuncCall::AES128CbcEncrypt {
+                inputs: vec![],
+                iv: vec![],
+                key: vec![],
+                outputs: vec![],
+            },
+            BlackBoxFunc::AES128CtrEncrypt => BlackBoxFuncCall::AES128CtrEncrypt {
+                inputs: vec![],
+                iv: vec![],
+                key: vec![],
+                outputs: vec![],
+            },
             BlackBoxFunc::SchnorrVerify => BlackBoxFuncCall::SchnorrVerify {
                 public_key_x: FunctionInput::dummy(),
                 public_key_y: FunctionInput::dummy(),
@@ -152,6 +256,13 @@ impl BlackBoxFuncCall {
                 hashed_message: vec![],
                 output: Witness(0),
             },
+            BlackBoxFunc::EcdsaSecp256k1Recover => BlackBoxFuncCall::EcdsaSecp256k1Recover {
+                hashed_message: vec![],
+                signature: vec![],
+                recovery_id: FunctionInput::dummy(),
+                public_key_x: vec![],
+                public_key_y: vec![],
+            },
             BlackBoxFunc::EcdsaSecp256r1 => BlackBoxFuncCall::EcdsaSecp256r1 {
                 public_key_x: vec![],
                 public_key_y: vec![],
@@ -163,6 +274,34 @@ impl BlackBoxFuncCall {
                 input: FunctionInput::dummy(),
                 outputs: (Witness(0), Witness(0)),
             },
+            BlackBoxFunc::EmbeddedCurveAdd => BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1_x: FunctionInput::dummy(),
+                input1_y: FunctionInput::dummy(),
+                input2_x: FunctionInput::dummy(),
+                input2_y: FunctionInput::dummy(),
+                outputs: (Witness(0), Witness(0)),
+            },
+            BlackBoxFunc::VariableBaseScalarMul => BlackBoxFuncCall::VariableBaseScalarMul {
+                point_x: FunctionInput::dummy(),
+                point_y: FunctionInput::dummy(),
+                scalar: FunctionInput::dummy(),
+                outputs: (Witness(0), Witness(0)),
+            },
+            BlackBoxFunc::BigIntAdd => BlackBoxFuncCall::BigIntAdd {
+                lhs: vec![],
+                rhs: vec![],
+                modulus: vec![],
+                outputs: vec![],
+            },
+            BlackBoxFunc::BigIntMul => BlackBoxFuncCall::BigIntMul {
+                lhs: vec![],
+                rhs: vec![],
+                modulus: vec![],
+                outputs: vec![],
+            },
+            BlackBoxFunc::BigIntMod => {
+                BlackBoxFuncCall::BigIntMod { input: vec![], modulus: vec![], outputs: vec![] }
+            }
             BlackBoxFunc::Keccak256 => {
                 BlackBoxFuncCall::Keccak256 { inputs: vec![], outputs: vec![] }
             }
@@ -174,6 +313,18 @@ impl BlackBoxFuncCall {
                 input_aggregation_object: None,
                 output_aggregation_object: vec![],
             },
+            BlackBoxFunc::Poseidon2Permutation => {
+                BlackBoxFuncCall::Poseidon2Permutation { inputs: vec![], outputs: vec![], len: 0 }
+            }
+            BlackBoxFunc::EddsaVerify => BlackBoxFuncCall::EddsaVerify {
+                public_key_x: FunctionInput::dummy(),
+                public_key_y: FunctionInput::dummy(),
+                signature_s: FunctionInput::dummy(),
+                signature_r8_x: FunctionInput::dummy(),
+                signature_r8_y: FunctionInput::dummy(),
+                message: FunctionInput::dummy(),
+                output: Witness(0),
+            },
         }
     }
 
@@ -184,15 +335,27 @@ impl BlackBoxFuncCall {
             BlackBoxFuncCall::RANGE { .. } => BlackBoxFunc::RANGE,
             BlackBoxFuncCall::SHA256 { .. } => BlackBoxFunc::SHA256,
             BlackBoxFuncCall::Blake2s { .. } => BlackBoxFunc::Blake2s,
+            BlackBoxFuncCall::SHA512 { .. } => BlackBoxFunc::SHA512,
+            BlackBoxFuncCall::Blake3 { .. } => BlackBoxFunc::Blake3,
+            BlackBoxFuncCall::AES128CbcEncrypt { .. } => BlackBoxFunc::AES128CbcEncrypt,
+            BlackBoxFuncCall::AES128CtrEncrypt { .. } => BlackBoxFunc::AES128CtrEncrypt,
             BlackBoxFuncCall::SchnorrVerify { .. } => BlackBoxFunc::SchnorrVerify,
             BlackBoxFuncCall::Pedersen { .. } => BlackBoxFunc::Pedersen,
             BlackBoxFuncCall::HashToField128Security { .. } => BlackBoxFunc::HashToField128Security,
             BlackBoxFuncCall::EcdsaSecp256k1 { .. } => BlackBoxFunc::EcdsaSecp256k1,
+            BlackBoxFuncCall::EcdsaSecp256k1Recover { .. } => BlackBoxFunc::EcdsaSecp256k1Recover,
             BlackBoxFuncCall::EcdsaSecp256r1 { .. } => BlackBoxFunc::EcdsaSecp256r1,
             BlackBoxFuncCall::FixedBaseScalarMul { .. } => BlackBoxFunc::FixedBaseScalarMul,
+            BlackBoxFuncCall::EmbeddedCurveAdd { .. } => BlackBoxFunc::EmbeddedCurveAdd,
+            BlackBoxFuncCall::VariableBaseScalarMul { .. } => BlackBoxFunc::VariableBaseScalarMul,
+            BlackBoxFuncCall::BigIntAdd { .. } => BlackBoxFunc::BigIntAdd,
+            BlackBoxFuncCall::BigIntMul { .. } => BlackBoxFunc::BigIntMul,
+            BlackBoxFuncCall::BigIntMod { .. } => BlackBoxFunc::BigIntMod,
             BlackBoxFuncCall::Keccak256 { .. } => BlackBoxFunc::Keccak256,
             BlackBoxFuncCall::Keccak256VariableLength { .. } => BlackBoxFunc::Keccak256,
             BlackBoxFuncCall::RecursiveAggregation { .. } => BlackBoxFunc::RecursiveAggregation,
+            BlackBoxFuncCall::Poseidon2Permutation { .. } => BlackBoxFunc::Poseidon2Permutation,
+            BlackBoxFuncCall::EddsaVerify { .. } => BlackBoxFunc::EddsaVerify,
         }
     }
 
@@ -204,14 +367,47 @@ impl BlackBoxFuncCall {
         match self {
             BlackBoxFuncCall::SHA256 { inputs, .. }
             | BlackBoxFuncCall::Blake2s { inputs, .. }
+            | BlackBoxFuncCall::SHA512 { inputs, .. }
+            | BlackBoxFuncCall::Blake3 { inputs, .. }
             | BlackBoxFuncCall::Keccak256 { inputs, .. }
             | BlackBoxFuncCall::Pedersen { inputs, .. }
-            | BlackBoxFuncCall::HashToField128Security { inputs, .. } => inputs.to_vec(),
+            | BlackBoxFuncCall::HashToField128Security { inputs, .. }
+            | BlackBoxFuncCall::Poseidon2Permutation { inputs, .. } => inputs.to_vec(),
             BlackBoxFuncCall::AND { lhs, rhs, .. } | BlackBoxFuncCall::XOR { lhs, rhs, .. } => {
                 vec![*lhs, *rhs]
             }
             BlackBoxFuncCall::FixedBaseScalarMul { input, .. }
             | BlackBoxFuncCall::RANGE { input } => vec![*input],
+            BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1_x, input1_y, input2_x, input2_y, ..
+            } => {
+                vec![*input1_x, *input1_y, *input2_x, *input2_y]
+            }
+            BlackBoxFuncCall::VariableBaseScalarMul { point_x, point_y, scalar, .. } => {
+                vec![*point_x, *point_y, *scalar]
+            }
+            BlackBoxFuncCall::BigIntAdd { lhs, rhs, modulus, .. }
+            | BlackBoxFuncCall::BigIntMul { lhs, rhs, modulus, .. } => {
+                let mut inputs = Vec::with_capacity(lhs.len() + rhs.len() + modulus.len());
+                inputs.extend(lhs.iter().copied());
+                inputs.extend(rhs.iter().copied());
+                inputs.extend(modulus.iter().copied());
+                inputs
+            }
+            BlackBoxFuncCall::AES128CbcEncrypt { inputs, iv, key, .. }
+            | BlackBoxFuncCall::AES128CtrEncrypt { inputs, iv, key, .. } => {
+                let mut all_inputs = Vec::with_capacity(inputs.len() + iv.len() + key.len());
+                all_inputs.extend(inputs.iter().copied());
+                all_inputs.extend(iv.iter().copied());
+                all_inputs.extend(key.iter().copied());
+                all_inputs
+            }
+            BlackBoxFuncCall::BigIntMod { input, modulus, .. } => {
+                let mut inputs = Vec::with_capacity(input.len() + modulus.len());
+                inputs.extend(input.iter().copied());
+                inputs.extend(modulus.iter().copied());
+                inputs
+            }
             BlackBoxFuncCall::SchnorrVerify {
                 public_key_x,
                 public_key_y,
@@ -226,6 +422,22 @@ impl BlackBoxFuncCall {
                 inputs.extend(message.iter().copied());
                 inputs
             }
+            BlackBoxFuncCall::EddsaVerify {
+                public_key_x,
+                public_key_y,
+                signature_s,
+                signature_r8_x,
+                signature_r8_y,
+                message,
+                ..
+            } => vec![
+                *public_key_x,
+                *public_key_y,
+                *signature_s,
+                *signature_r8_x,
+                *signature_r8_y,
+                *message,
+            ],
             BlackBoxFuncCall::EcdsaSecp256k1 {
                 public_key_x,
                 public_key_y,
@@ -264,6 +476,18 @@ impl BlackBoxFuncCall {
                 inputs.extend(hashed_message.iter().copied());
                 inputs
             }
+            BlackBoxFuncCall::EcdsaSecp256k1Recover {
+                hashed_message,
+                signature,
+                recovery_id,
+                ..
+            } => {
+                let mut inputs = Vec::with_capacity(hashed_message.len() + signature.len() + 1);
+                inputs.extend(hashed_message.iter().copied());
+                inputs.extend(signature.iter().copied());
+                inputs.push(*recovery_id);
+                inputs
+            }
             BlackBoxFuncCall::Keccak256VariableLength { inputs, var_message_size, .. } => {
                 let mut inputs = inputs.clone();
                 inputs.push(*var_message_size);
@@ -293,7 +517,15 @@ impl BlackBoxFuncCall {
         match self {
             BlackBoxFuncCall::SHA256 { outputs, .. }
             | BlackBoxFuncCall::Blake2s { outputs, .. }
+            | BlackBoxFuncCall::SHA512 { outputs, .. }
+            | BlackBoxFuncCall::Blake3 { outputs, .. }
+            | BlackBoxFuncCall::AES128CbcEncrypt { outputs, .. }
+            | BlackBoxFuncCall::AES128CtrEncrypt { outputs, .. }
             | BlackBoxFuncCall::Keccak256 { outputs, .. }
+            | BlackBoxFuncCall::Poseidon2Permutation { outputs, .. }
+            | BlackBoxFuncCall::BigIntAdd { outputs, .. }
+            | BlackBoxFuncCall::BigIntMul { outputs, .. }
+            | BlackBoxFuncCall::BigIntMod { outputs, .. }
             | BlackBoxFuncCall::RecursiveAggregation {
                 output_aggregation_object: outputs, ..
             } => outputs.to_vec(),
@@ -301,12 +533,146 @@ impl BlackBoxFuncCall {
             | BlackBoxFuncCall::XOR { output, .. }
             | BlackBoxFuncCall::HashToField128Security { output, .. }
             | BlackBoxFuncCall::SchnorrVerify { output, .. }
+            | BlackBoxFuncCall::EddsaVerify { output, .. }
             | BlackBoxFuncCall::EcdsaSecp256k1 { output, .. }
             | BlackBoxFuncCall::EcdsaSecp256r1 { output, .. } => vec![*output],
             BlackBoxFuncCall::FixedBaseScalarMul { outputs, .. }
+            | BlackBoxFuncCall::EmbeddedCurveAdd { outputs, .. }
+            | BlackBoxFuncCall::VariableBaseScalarMul { outputs, .. }
             | BlackBoxFuncCall::Pedersen { outputs, .. } => vec![outputs.0, outputs.1],
             BlackBoxFuncCall::RANGE { .. } => vec![],
             BlackBoxFuncCall::Keccak256VariableLength { outputs, .. } => outputs.to_vec(),
+            BlackBoxFuncCall::EcdsaSecp256k1Recover { public_key_x, public_key_y, .. } => {
+                public_key_x.iter().chain(public_key_y).copied().collect()
+            }
+        }
+    }
+
+    /// Returns the inputs of the call grouped into operands, in the order in which they are declared.
+    ///
+    /// Optional operands, such as the input aggregation object of a [`BlackBoxFuncCall::RecursiveAggregation`]
+    /// call or the message size of a [`BlackBoxFuncCall::Keccak256VariableLength`] call, are empty when absent.
+    pub fn get_input_operands(&self) -> Vec<Vec<FunctionInput>> {
+        match self {
+            BlackBoxFuncCall::AND { lhs, rhs, .. } | BlackBoxFuncCall::XOR { lhs, rhs, .. } => {
+                vec![vec![*lhs], vec![*rhs]]
+            }
+            BlackBoxFuncCall::RANGE { input }
+            | BlackBoxFuncCall::FixedBaseScalarMul { input, .. } => {
+                vec![vec![*input]]
+            }
+            BlackBoxFuncCall::SHA256 { inputs, .. }
+            | BlackBoxFuncCall::Blake2s { inputs, .. }
+            | BlackBoxFuncCall::SHA512 { inputs, .. }
+            | BlackBoxFuncCall::Blake3 { inputs, .. }
+            | BlackBoxFuncCall::Pedersen { inputs, .. }
+            | BlackBoxFuncCall::HashToField128Security { inputs, .. }
+            | BlackBoxFuncCall::Poseidon2Permutation { inputs, .. } => vec![inputs.clone()],
+            BlackBoxFuncCall::Keccak256 { inputs, .. } => vec![inputs.clone(), vec![]],
+            BlackBoxFuncCall::Keccak256VariableLength { inputs, var_message_size, .. } => {
+                vec![inputs.clone(), vec![*var_message_size]]
+            }
+            BlackBoxFuncCall::SchnorrVerify {
+                public_key_x,
+                public_key_y,
+                signature,
+                message,
+                ..
+            } => vec![vec![*public_key_x], vec![*public_key_y], signature.clone(), message.clone()],
+            BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x,
+                public_key_y,
+                signature,
+                hashed_message,
+                ..
+            }
+            | BlackBoxFuncCall::EcdsaSecp256r1 {
+                public_key_x,
+                public_key_y,
+                signature,
+                hashed_message,
+                ..
+            } => vec![
+                public_key_x.clone(),
+                public_key_y.clone(),
+                signature.clone(),
+                hashed_message.clone(),
+            ],
+            BlackBoxFuncCall::EcdsaSecp256k1Recover {
+                hashed_message,
+                signature,
+                recovery_id,
+                ..
+            } => vec![hashed_message.clone(), signature.clone(), vec![*recovery_id]],
+            BlackBoxFuncCall::EmbeddedCurveAdd {
+                input1_x, input1_y, input2_x, input2_y, ..
+            } => vec![vec![*input1_x], vec![*input1_y], vec![*input2_x], vec![*input2_y]],
+            BlackBoxFuncCall::VariableBaseScalarMul { point_x, point_y, scalar, .. } => {
+                vec![vec![*point_x], vec![*point_y], vec![*scalar]]
+            }
+            BlackBoxFuncCall::BigIntAdd { lhs, rhs, modulus, .. }
+            | BlackBoxFuncCall::BigIntMul { lhs, rhs, modulus, .. } => {
+                vec![lhs.clone(), rhs.clone(), modulus.clone()]
+            }
+            BlackBoxFuncCall::BigIntMod { input, modulus, .. } => {
+                vec![input.clone(), modulus.clone()]
+            }
+            BlackBoxFuncCall::AES128CbcEncrypt { inputs, iv, key, .. }
+            | BlackBoxFuncCall::AES128CtrEncrypt { inputs, iv, key, .. } => {
+                vec![inputs.clone(), iv.clone(), key.clone()]
+            }
+            BlackBoxFuncCall::EddsaVerify {
+                public_key_x,
+                public_key_y,
+                signature_s,
+                signature_r8_x,
+                signature_r8_y,
+                message,
+                ..
+            } => vec![
+                vec![*public_key_x],
+                vec![*public_key_y],
+                vec![*signature_s],
+                vec![*signature_r8_x],
+                vec![*signature_r8_y],
+                vec![*message],
+            ],
+            BlackBoxFuncCall::RecursiveAggregation {
+                verification_key,
+                proof,
+                public_inputs,
+                key_hash,
+                input_aggregation_object,
+                ..
+            } => vec![
+                verification_key.clone(),
+                proof.clone(),
+                public_inputs.clone(),
+                vec![*key_hash],
+                input_aggregation_object.clone().unwrap_or_default(),
+            ],
+        }
+    }
+
+    /// Returns the constant parameters of the call, such as the domain separator of a [`BlackBoxFuncCall::Pedersen`] call.
+    ///
+    /// These follow the [input operands][BlackBoxFuncCall::get_input_operands] as single element operands when the call is solved.
+    pub fn get_parameters(&self) -> Vec<u32> {
+        match self {
+            BlackBoxFuncCall::Pedersen { domain_separator, .. } => vec![*domain_separator],
+            BlackBoxFuncCall::Poseidon2Permutation { len, .. } => vec![*len],
+            _ => vec![],
+        }
+    }
+
+    /// Returns the outputs of the call grouped into operands, in the order in which they are declared.
+    pub fn get_output_operands(&self) -> Vec<Vec<Witness>> {
+        match self {
+            BlackBoxFuncCall::RANGE { .. } => vec![],
+            BlackBoxFuncCall::EcdsaSecp256k1Recover { public_key_x, public_key_y, .. } => {
+                vec![public_key_x.clone(), public_key_y.clone()]
+            }
+            _ => vec![self.get_outputs_vec()],
         }
     }
 }
@@ -396,6 +762,7 @@ impl std::fmt::Display for BlackBoxFuncCall {
             BlackBoxFuncCall::Pedersen { domain_separator, .. } => {
                 write!(f, " domain_separator: {domain_separator}")
             }
+            BlackBoxFuncCall::Poseidon2Permutation { len, .. } => write!(f, " len: {len}"),
             _ => write!(f, ""),
         }
     }
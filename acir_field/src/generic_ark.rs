@@ -254,6 +254,11 @@ impl<F: PrimeField> FieldElement<F> {
         self.0
     }
 
+    /// Wraps a field element of the underlying arkworks field, which is already reduced.
+    pub fn from_repr(field: F) -> Self {
+        FieldElement(field)
+    }
+
     pub fn to_hex(self) -> String {
         let mut bytes = Vec::new();
         self.0.serialize_uncompressed(&mut bytes).unwrap();
@@ -276,6 +281,22 @@ impl<F: PrimeField> FieldElement<F> {
         bytes
     }
 
+    /// Writes the `bytes.len()` least significant bytes of the field element into `bytes` in little-endian order,
+    /// without allocating.
+    ///
+    /// Returns `false` if any of the more significant bytes which were dropped are non-zero.
+    pub fn fill_le_bytes(&self, bytes: &mut [u8]) -> bool {
+        let repr = self.0.into_bigint();
+        let limbs: &[u64] = repr.as_ref();
+        let le_byte =
+            |index: usize| limbs.get(index / 8).map_or(0, |limb| (limb >> (8 * (index % 8))) as u8);
+
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = le_byte(index);
+        }
+        (bytes.len()..limbs.len() * 8).all(|index| le_byte(index) == 0)
+    }
+
     /// Converts bytes into a FieldElement and applies a
     /// reduction if needed.
     pub fn from_be_bytes_reduce(bytes: &[u8]) -> FieldElement<F> {
@@ -441,6 +462,23 @@ mod tests {
         let max_num_bits_bn254 = crate::generic_ark::FieldElement::<ark_bn254::Fr>::max_num_bits();
         assert_eq!(max_num_bits_bn254, 254)
     }
+
+    #[test]
+    fn fill_le_bytes_matches_to_be_bytes() {
+        let field = -crate::generic_ark::FieldElement::<ark_bn254::Fr>::from(2i128);
+        let mut bytes = [0u8; 32];
+        assert!(field.fill_le_bytes(&mut bytes));
+        bytes.reverse();
+        assert_eq!(bytes.to_vec(), field.to_be_bytes());
+
+        let field = crate::generic_ark::FieldElement::<ark_bn254::Fr>::from(0x0102_0304u128);
+        let mut bytes = [0u8; 3];
+        assert!(!field.fill_le_bytes(&mut bytes));
+        assert_eq!(bytes, [4, 3, 2]);
+        let mut bytes = [0xff; 6];
+        assert!(field.fill_le_bytes(&mut bytes));
+        assert_eq!(bytes, [4, 3, 2, 1, 0, 0]);
+    }
 }
 
 fn mask_vector_le(bytes: &mut [u8], num_bits: usize) {
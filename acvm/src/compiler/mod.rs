@@ -6,6 +6,7 @@ use acir::{
     native_types::{Expression, Witness},
     BlackBoxFunc, FieldElement,
 };
+use blackbox_solver::BlackBoxRegistry;
 use indexmap::IndexMap;
 use thiserror::Error;
 
@@ -16,7 +17,7 @@ mod optimizers;
 mod transformers;
 
 use optimizers::{GeneralOptimizer, RangeOptimizer};
-use transformers::{CSatTransformer, FallbackTransformer, R1CSTransformer};
+use transformers::{register_fallbacks, CSatTransformer, FallbackTransformer, R1CSTransformer};
 
 #[derive(PartialEq, Eq, Debug, Error)]
 pub enum CompileError {
@@ -56,6 +57,17 @@ impl AcirTransformationMap {
                 }
             })
     }
+
+    /// Returns the location in the original circuit of the opcode at `new_location` in the transformed circuit.
+    pub fn old_location(&self, new_location: OpcodeLocation) -> OpcodeLocation {
+        match new_location {
+            OpcodeLocation::Acir(index) => OpcodeLocation::Acir(self.acir_opcode_positions[index]),
+            OpcodeLocation::Brillig { acir_index, brillig_index } => OpcodeLocation::Brillig {
+                acir_index: self.acir_opcode_positions[acir_index],
+                brillig_index,
+            },
+        }
+    }
 }
 
 fn transform_assert_messages(
@@ -71,11 +83,33 @@ fn transform_assert_messages(
         .collect()
 }
 
+/// Returns the definitions of the standard black box functions along with the fallbacks which the ACVM provides.
+///
+/// This is the registry which [`compile`] uses. Backends which register definitions of their own should extend it and
+/// pass it to both [`compile_with_registry`] and [`ACVM::set_black_box_registry`][crate::pwg::ACVM::set_black_box_registry],
+/// so that the circuit is solved with the same definitions which it was compiled with.
+pub fn black_box_registry() -> BlackBoxRegistry {
+    let mut registry = BlackBoxRegistry::new();
+    register_fallbacks(&mut registry);
+    registry
+}
+
 /// Applies [`ProofSystemCompiler`][crate::ProofSystemCompiler] specific optimizations to a [`Circuit`].
 pub fn compile(
     acir: Circuit,
     np_language: Language,
     is_opcode_supported: impl Fn(&Opcode) -> bool,
+) -> Result<(Circuit, AcirTransformationMap), CompileError> {
+    compile_with_registry(acir, np_language, is_opcode_supported, &black_box_registry())
+}
+
+/// Applies [`ProofSystemCompiler`][crate::ProofSystemCompiler] specific optimizations to a [`Circuit`],
+/// replacing unsupported black box functions with the fallbacks defined in `registry`.
+pub fn compile_with_registry(
+    acir: Circuit,
+    np_language: Language,
+    is_opcode_supported: impl Fn(&Opcode) -> bool,
+    registry: &BlackBoxRegistry,
 ) -> Result<(Circuit, AcirTransformationMap), CompileError> {
     // Instantiate the optimizer.
     // Currently the optimizer and reducer are one in the same
@@ -87,7 +121,7 @@ pub fn compile(
 
     // Fallback transformer pass
     let (acir, acir_opcode_positions) =
-        FallbackTransformer::transform(acir, is_opcode_supported, acir_opcode_positions)?;
+        FallbackTransformer::transform(acir, is_opcode_supported, registry, acir_opcode_positions)?;
 
     // General optimizer pass
     let mut opcodes: Vec<Opcode> = Vec::new();
@@ -165,43 +199,13 @@ pub fn compile(
                 }
             }
             Opcode::BlackBoxFuncCall(func) => {
-                match func {
-                    acir::circuit::opcodes::BlackBoxFuncCall::AND { output, .. }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::XOR { output, .. } => {
-                        transformer.mark_solvable(*output)
-                    }
-                    acir::circuit::opcodes::BlackBoxFuncCall::RANGE { .. } => (),
-                    acir::circuit::opcodes::BlackBoxFuncCall::SHA256 { outputs, .. }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::Keccak256 { outputs, .. }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::Keccak256VariableLength {
-                        outputs,
-                        ..
-                    }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::RecursiveAggregation {
-                        output_aggregation_object: outputs,
-                        ..
-                    }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::Blake2s { outputs, .. } => {
-                        for witness in outputs {
-                            transformer.mark_solvable(*witness);
-                        }
-                    }
-                    acir::circuit::opcodes::BlackBoxFuncCall::FixedBaseScalarMul {
-                        outputs,
-                        ..
-                    }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::Pedersen { outputs, .. } => {
-                        transformer.mark_solvable(outputs.0);
-                        transformer.mark_solvable(outputs.1)
-                    }
-                    acir::circuit::opcodes::BlackBoxFuncCall::HashToField128Security {
-                        output,
-                        ..
-                    }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::EcdsaSecp256k1 { output, .. }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::EcdsaSecp256r1 { output, .. }
-                    | acir::circuit::opcodes::BlackBoxFuncCall::SchnorrVerify { output, .. } => {
-                        transformer.mark_solvable(*output)
+                // The outputs of black box functions which the ACVM can solve natively are solvable.
+                let is_solvable = registry
+                    .get(func.name())
+                    .map_or(false, |definition| definition.native.is_some());
+                if is_solvable {
+                    for witness in func.get_outputs_vec() {
+                        transformer.mark_solvable(witness);
                     }
                 }
 
@@ -1,8 +1,13 @@
 use super::super::CompileError;
 use acir::{
-    circuit::{opcodes::BlackBoxFuncCall, Circuit, Opcode},
-    native_types::Expression,
+    circuit::{
+        opcodes::{BlackBoxFuncCall, BlockId, FunctionInput},
+        Circuit, Opcode,
+    },
+    native_types::{Expression, Witness},
+    BlackBoxFunc,
 };
+use blackbox_solver::{BlackBoxRegistry, FallbackContext, FallbackGenerator};
 
 /// The initial transformer to act on a [`Circuit`]. This replaces any unsupported opcodes with
 /// fallback implementations consisting of well supported opcodes.
@@ -13,11 +18,27 @@ impl FallbackTransformer {
     pub(crate) fn transform(
         acir: Circuit,
         is_supported: impl Fn(&Opcode) -> bool,
+        registry: &BlackBoxRegistry,
         opcode_positions: Vec<usize>,
     ) -> Result<(Circuit, Vec<usize>), CompileError> {
         let mut acir_supported_opcodes = Vec::with_capacity(acir.opcodes.len());
         let mut new_opcode_positions = Vec::with_capacity(opcode_positions.len());
         let mut witness_idx = acir.current_witness_index + 1;
+        // Fallbacks may look up tables in memory blocks following those already used by the circuit,
+        // provided that the backend supports memory opcodes.
+        let supports_memory =
+            is_supported(&Opcode::MemoryInit { block_id: BlockId::default(), init: Vec::new() });
+        let mut next_block_id = acir
+            .opcodes
+            .iter()
+            .filter_map(|opcode| match opcode {
+                Opcode::MemoryInit { block_id, .. } | Opcode::MemoryOp { block_id, .. } => {
+                    Some(block_id.0 + 1)
+                }
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
 
         for (idx, opcode) in acir.opcodes.into_iter().enumerate() {
             match &opcode {
@@ -48,9 +69,19 @@ impl FallbackTransformer {
                         // If we get here then we know that this black box function is not supported
                         // so we need to replace it with a version of the opcode which only uses arithmetic
                         // expressions
+                        let context = FallbackContext {
+                            num_witness: witness_idx,
+                            lookup_block: supports_memory.then_some(BlockId(next_block_id)),
+                        };
                         let (updated_witness_index, opcodes_fallback) =
-                            Self::opcode_fallback(bb_func_call, witness_idx)?;
+                            Self::opcode_fallback(bb_func_call, registry, context)?;
                         witness_idx = updated_witness_index;
+                        if opcodes_fallback
+                            .iter()
+                            .any(|opcode| matches!(opcode, Opcode::MemoryInit { .. }))
+                        {
+                            next_block_id += 1;
+                        }
                         new_opcode_positions
                             .extend(vec![opcode_positions[idx]; opcodes_fallback.len()]);
                         acir_supported_opcodes.extend(opcodes_fallback);
@@ -65,106 +96,344 @@ impl FallbackTransformer {
         ))
     }
 
+    /// Returns the fallback for `gc` given by its definition in `registry`.
     fn opcode_fallback(
         gc: &BlackBoxFuncCall,
-        current_witness_idx: u32,
+        registry: &BlackBoxRegistry,
+        context: FallbackContext,
     ) -> Result<(u32, Vec<Opcode>), CompileError> {
-        let (updated_witness_index, opcodes_fallback) = match gc {
-            BlackBoxFuncCall::AND { lhs, rhs, output } => {
-                assert_eq!(
-                    lhs.num_bits, rhs.num_bits,
-                    "number of bits specified for each input must be the same"
-                );
-                stdlib::blackbox_fallbacks::and(
-                    Expression::from(lhs.witness),
-                    Expression::from(rhs.witness),
-                    *output,
-                    lhs.num_bits,
-                    current_witness_idx,
-                )
-            }
-            BlackBoxFuncCall::XOR { lhs, rhs, output } => {
-                assert_eq!(
-                    lhs.num_bits, rhs.num_bits,
-                    "number of bits specified for each input must be the same"
-                );
-                stdlib::blackbox_fallbacks::xor(
-                    Expression::from(lhs.witness),
-                    Expression::from(rhs.witness),
-                    *output,
-                    lhs.num_bits,
-                    current_witness_idx,
-                )
-            }
-            BlackBoxFuncCall::RANGE { input } => {
-                // Note there are no outputs because range produces no outputs
-                stdlib::blackbox_fallbacks::range(
-                    Expression::from(input.witness),
-                    input.num_bits,
-                    current_witness_idx,
-                )
-            }
-            #[cfg(feature = "unstable-fallbacks")]
-            BlackBoxFuncCall::SHA256 { inputs, outputs } => {
-                let mut sha256_inputs = Vec::new();
-                for input in inputs.iter() {
-                    let witness_index = Expression::from(input.witness);
-                    let num_bits = input.num_bits;
-                    sha256_inputs.push((witness_index, num_bits));
-                }
-                stdlib::blackbox_fallbacks::sha256(
-                    sha256_inputs,
-                    outputs.to_vec(),
-                    current_witness_idx,
-                )
-            }
-            #[cfg(feature = "unstable-fallbacks")]
-            BlackBoxFuncCall::Blake2s { inputs, outputs } => {
-                let mut blake2s_input = Vec::new();
-                for input in inputs.iter() {
-                    let witness_index = Expression::from(input.witness);
-                    let num_bits = input.num_bits;
-                    blake2s_input.push((witness_index, num_bits));
-                }
-                stdlib::blackbox_fallbacks::blake2s(
-                    blake2s_input,
-                    outputs.to_vec(),
-                    current_witness_idx,
-                )
-            }
-            #[cfg(feature = "unstable-fallbacks")]
-            BlackBoxFuncCall::HashToField128Security { inputs, output } => {
-                let mut blake2s_input = Vec::new();
-                for input in inputs.iter() {
-                    let witness_index = Expression::from(input.witness);
-                    let num_bits = input.num_bits;
-                    blake2s_input.push((witness_index, num_bits));
-                }
-                stdlib::blackbox_fallbacks::hash_to_field(
-                    blake2s_input,
-                    *output,
-                    current_witness_idx,
-                )
-            }
-            #[cfg(feature = "unstable-fallbacks")]
-            BlackBoxFuncCall::Keccak256 { inputs, outputs } => {
-                let mut keccak_input = Vec::new();
-                for input in inputs.iter() {
-                    let witness_index = Expression::from(input.witness);
-                    let num_bits = input.num_bits;
-                    keccak_input.push((witness_index, num_bits));
-                }
-                stdlib::blackbox_fallbacks::keccak256(
-                    keccak_input,
-                    outputs.to_vec(),
-                    current_witness_idx,
-                )
-            }
-            _ => {
-                return Err(CompileError::UnsupportedBlackBox(gc.get_black_box_func()));
-            }
-        };
+        let func = gc.get_black_box_func();
+        registry
+            .get(func.name())
+            .and_then(|definition| definition.fallback)
+            .and_then(|fallback| {
+                fallback(&gc.get_input_operands(), &gc.get_output_operands(), context)
+            })
+            .ok_or(CompileError::UnsupportedBlackBox(func))
+    }
+}
+
+/// The fallbacks which the ACVM provides for the standard black box functions.
+const FALLBACKS: &[(BlackBoxFunc, FallbackGenerator)] = &[
+    (BlackBoxFunc::AND, and),
+    (BlackBoxFunc::XOR, xor),
+    (BlackBoxFunc::RANGE, range),
+    #[cfg(feature = "unstable-fallbacks")]
+    (BlackBoxFunc::SHA256, sha256),
+    #[cfg(feature = "unstable-fallbacks")]
+    (BlackBoxFunc::Blake2s, blake2s),
+    #[cfg(feature = "unstable-fallbacks")]
+    (BlackBoxFunc::SHA512, sha512),
+    #[cfg(feature = "unstable-fallbacks")]
+    (BlackBoxFunc::Blake3, blake3),
+    #[cfg(feature = "unstable-fallbacks")]
+    (BlackBoxFunc::HashToField128Security, hash_to_field),
+    #[cfg(feature = "unstable-fallbacks")]
+    (BlackBoxFunc::Keccak256, keccak256),
+    #[cfg(feature = "bn254")]
+    (BlackBoxFunc::EddsaVerify, eddsa_verify),
+    #[cfg(feature = "bn254")]
+    (BlackBoxFunc::EmbeddedCurveAdd, embedded_curve_add),
+    #[cfg(feature = "bn254")]
+    (BlackBoxFunc::VariableBaseScalarMul, variable_base_scalar_mul),
+    (BlackBoxFunc::AES128CbcEncrypt, aes128_cbc_encrypt),
+    (BlackBoxFunc::AES128CtrEncrypt, aes128_ctr_encrypt),
+    (BlackBoxFunc::BigIntAdd, bigint_add),
+    (BlackBoxFunc::BigIntMul, bigint_mul),
+    (BlackBoxFunc::BigIntMod, bigint_mod),
+    #[cfg(feature = "bn254")]
+    (BlackBoxFunc::Poseidon2Permutation, poseidon2_permutation),
+];
 
-        Ok((updated_witness_index, opcodes_fallback))
+/// Adds the ACVM's fallbacks to the definitions of the standard black box functions in `registry`.
+pub(crate) fn register_fallbacks(registry: &mut BlackBoxRegistry) {
+    for &(func, fallback) in FALLBACKS {
+        let definition =
+            *registry.get(func.name()).expect("every black box function has a definition");
+        registry.register(blackbox_solver::BlackBoxDefinition {
+            fallback: Some(fallback),
+            ..definition
+        });
     }
 }
+
+fn expression(input: &FunctionInput) -> Expression {
+    Expression::from(input.witness)
+}
+
+fn expressions(operand: &[FunctionInput]) -> Vec<Expression> {
+    operand.iter().map(expression).collect()
+}
+
+fn witnesses(operand: &[FunctionInput]) -> Vec<Witness> {
+    operand.iter().map(|input| input.witness).collect()
+}
+
+/// Pairs each input of a hash with its bit size.
+#[cfg(feature = "unstable-fallbacks")]
+fn hash_inputs(operand: &[FunctionInput]) -> Vec<(Expression, u32)> {
+    operand.iter().map(|input| (expression(input), input.num_bits)).collect()
+}
+
+fn and(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    let (lhs, rhs) = (inputs[0][0], inputs[1][0]);
+    // The fallback decomposes both inputs into the same number of bits.
+    if lhs.num_bits != rhs.num_bits {
+        return None;
+    }
+    Some(stdlib::blackbox_fallbacks::and(
+        expression(&lhs),
+        expression(&rhs),
+        outputs[0][0],
+        lhs.num_bits,
+        context.num_witness,
+    ))
+}
+
+fn xor(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    let (lhs, rhs) = (inputs[0][0], inputs[1][0]);
+    // The fallback decomposes both inputs into the same number of bits.
+    if lhs.num_bits != rhs.num_bits {
+        return None;
+    }
+    Some(stdlib::blackbox_fallbacks::xor(
+        expression(&lhs),
+        expression(&rhs),
+        outputs[0][0],
+        lhs.num_bits,
+        context.num_witness,
+    ))
+}
+
+fn range(
+    inputs: &[Vec<FunctionInput>],
+    _outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    // Note there are no outputs because range produces no outputs
+    let input = inputs[0][0];
+    Some(stdlib::blackbox_fallbacks::range(expression(&input), input.num_bits, context.num_witness))
+}
+
+#[cfg(feature = "unstable-fallbacks")]
+fn sha256(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::sha256(
+        hash_inputs(&inputs[0]),
+        outputs[0].clone(),
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "unstable-fallbacks")]
+fn blake2s(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::blake2s(
+        hash_inputs(&inputs[0]),
+        outputs[0].clone(),
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "unstable-fallbacks")]
+fn sha512(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::sha512(
+        hash_inputs(&inputs[0]),
+        outputs[0].clone(),
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "unstable-fallbacks")]
+fn blake3(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::blake3(
+        hash_inputs(&inputs[0]),
+        outputs[0].clone(),
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "unstable-fallbacks")]
+fn hash_to_field(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::hash_to_field(
+        hash_inputs(&inputs[0]),
+        outputs[0][0],
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "unstable-fallbacks")]
+fn keccak256(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    // The fallback hashes the whole message, so cannot replace calls with a variable message size.
+    if !inputs[1].is_empty() {
+        return None;
+    }
+    Some(stdlib::blackbox_fallbacks::keccak256(
+        hash_inputs(&inputs[0]),
+        outputs[0].clone(),
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "bn254")]
+fn eddsa_verify(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::eddsa_verify(
+        expression(&inputs[0][0]),
+        expression(&inputs[1][0]),
+        expression(&inputs[2][0]),
+        expression(&inputs[3][0]),
+        expression(&inputs[4][0]),
+        expression(&inputs[5][0]),
+        outputs[0][0],
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "bn254")]
+fn embedded_curve_add(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::embedded_curve_add(
+        expression(&inputs[0][0]),
+        expression(&inputs[1][0]),
+        expression(&inputs[2][0]),
+        expression(&inputs[3][0]),
+        (outputs[0][0], outputs[0][1]),
+        context.num_witness,
+    ))
+}
+
+#[cfg(feature = "bn254")]
+fn variable_base_scalar_mul(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::variable_base_scalar_mul(
+        expression(&inputs[0][0]),
+        expression(&inputs[1][0]),
+        expression(&inputs[2][0]),
+        (outputs[0][0], outputs[0][1]),
+        context.num_witness,
+    ))
+}
+
+fn aes128_cbc_encrypt(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::aes128_cbc_encrypt(
+        witnesses(&inputs[0]),
+        witnesses(&inputs[1]),
+        witnesses(&inputs[2]),
+        outputs[0].clone(),
+        context.lookup_block,
+        context.num_witness,
+    ))
+}
+
+fn aes128_ctr_encrypt(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    Some(stdlib::blackbox_fallbacks::aes128_ctr_encrypt(
+        witnesses(&inputs[0]),
+        witnesses(&inputs[1]),
+        witnesses(&inputs[2]),
+        outputs[0].clone(),
+        context.lookup_block,
+        context.num_witness,
+    ))
+}
+
+fn bigint_add(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    stdlib::blackbox_fallbacks::bigint_add(
+        expressions(&inputs[0]),
+        expressions(&inputs[1]),
+        expressions(&inputs[2]),
+        outputs[0].clone(),
+        context.num_witness,
+    )
+}
+
+fn bigint_mul(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    stdlib::blackbox_fallbacks::bigint_mul(
+        expressions(&inputs[0]),
+        expressions(&inputs[1]),
+        expressions(&inputs[2]),
+        outputs[0].clone(),
+        context.num_witness,
+    )
+}
+
+fn bigint_mod(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    stdlib::blackbox_fallbacks::bigint_mod(
+        expressions(&inputs[0]),
+        expressions(&inputs[1]),
+        outputs[0].clone(),
+        context.num_witness,
+    )
+}
+
+#[cfg(feature = "bn254")]
+fn poseidon2_permutation(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)> {
+    stdlib::blackbox_fallbacks::poseidon2_permutation(
+        expressions(&inputs[0]),
+        outputs[0].clone(),
+        context.num_witness,
+    )
+}
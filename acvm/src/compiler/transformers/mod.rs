@@ -3,5 +3,5 @@ mod fallback;
 mod r1cs;
 
 pub(crate) use csat::CSatTransformer;
-pub(crate) use fallback::FallbackTransformer;
+pub(crate) use fallback::{register_fallbacks, FallbackTransformer};
 pub(crate) use r1cs::R1CSTransformer;
@@ -3,7 +3,10 @@ use acir::{
     FieldElement,
 };
 
-use super::{insert_value, ErrorLocation, OpcodeNotSolvable, OpcodeResolutionError};
+use super::{
+    assigned_witness_values, insert_value, ErrorLocation, OpcodeNotSolvable, OpcodeResolutionError,
+    UnsatisfiedConstraintInfo,
+};
 
 /// An Arithmetic solver will take a Circuit's arithmetic opcodes with witness assignments
 /// and create the other witness variables
@@ -28,6 +31,7 @@ impl ArithmeticSolver {
         initial_witness: &mut WitnessMap,
         opcode: &Expression,
     ) -> Result<(), OpcodeResolutionError> {
+        let original_opcode = opcode;
         let opcode = &ArithmeticSolver::evaluate(opcode, initial_witness);
         // Evaluate multiplication term
         let mul_result = ArithmeticSolver::solve_mul_term(opcode, initial_witness);
@@ -36,9 +40,7 @@ impl ArithmeticSolver {
 
         match (mul_result, opcode_status) {
             (MulTerm::TooManyUnknowns, _) | (_, OpcodeStatus::OpcodeUnsolvable) => {
-                Err(OpcodeResolutionError::OpcodeNotSolvable(
-                    OpcodeNotSolvable::ExpressionHasTooManyUnknowns(opcode.clone()),
-                ))
+                Err(OpcodeNotSolvable::ExpressionHasTooManyUnknowns(opcode.clone()).into())
             }
             (MulTerm::OneUnknown(q, w1), OpcodeStatus::OpcodeSolvable(a, (b, w2))) => {
                 if w1 == w2 {
@@ -46,9 +48,11 @@ impl ArithmeticSolver {
                     let total_sum = a + opcode.q_c;
                     if (q + b).is_zero() {
                         if !total_sum.is_zero() {
-                            Err(OpcodeResolutionError::UnsatisfiedConstrain {
-                                opcode_location: ErrorLocation::Unresolved,
-                            })
+                            Err(Self::unsatisfied_constraint(
+                                original_opcode,
+                                initial_witness,
+                                total_sum,
+                            ))
                         } else {
                             Ok(())
                         }
@@ -60,9 +64,7 @@ impl ArithmeticSolver {
                     }
                 } else {
                     // TODO: can we be more specific with this error?
-                    Err(OpcodeResolutionError::OpcodeNotSolvable(
-                        OpcodeNotSolvable::ExpressionHasTooManyUnknowns(opcode.clone()),
-                    ))
+                    Err(OpcodeNotSolvable::ExpressionHasTooManyUnknowns(opcode.clone()).into())
                 }
             }
             (
@@ -76,9 +78,11 @@ impl ArithmeticSolver {
                 let total_sum = sum + opcode.q_c;
                 if partial_prod.is_zero() {
                     if !total_sum.is_zero() {
-                        Err(OpcodeResolutionError::UnsatisfiedConstrain {
-                            opcode_location: ErrorLocation::Unresolved,
-                        })
+                        Err(Self::unsatisfied_constraint(
+                            original_opcode,
+                            initial_witness,
+                            total_sum,
+                        ))
                     } else {
                         Ok(())
                     }
@@ -92,10 +96,9 @@ impl ArithmeticSolver {
             (MulTerm::Solved(a), OpcodeStatus::OpcodeSatisfied(b)) => {
                 // All the variables in the MulTerm are solved and the Fan-in is also solved
                 // There is nothing to solve
-                if !(a + b + opcode.q_c).is_zero() {
-                    Err(OpcodeResolutionError::UnsatisfiedConstrain {
-                        opcode_location: ErrorLocation::Unresolved,
-                    })
+                let total_sum = a + b + opcode.q_c;
+                if !total_sum.is_zero() {
+                    Err(Self::unsatisfied_constraint(original_opcode, initial_witness, total_sum))
                 } else {
                     Ok(())
                 }
@@ -110,9 +113,11 @@ impl ArithmeticSolver {
                 let total_sum = total_prod + partial_sum + opcode.q_c;
                 if coeff.is_zero() {
                     if !total_sum.is_zero() {
-                        Err(OpcodeResolutionError::UnsatisfiedConstrain {
-                            opcode_location: ErrorLocation::Unresolved,
-                        })
+                        Err(Self::unsatisfied_constraint(
+                            original_opcode,
+                            initial_witness,
+                            total_sum,
+                        ))
                     } else {
                         Ok(())
                     }
@@ -126,6 +131,22 @@ impl ArithmeticSolver {
         }
     }
 
+    /// Constructs an error for an `opcode` which evaluated to the non-zero value `residual`.
+    fn unsatisfied_constraint(
+        opcode: &Expression,
+        initial_witness: &WitnessMap,
+        residual: FieldElement,
+    ) -> OpcodeResolutionError {
+        OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Unresolved,
+            info: Some(Box::new(UnsatisfiedConstraintInfo::Expression {
+                expression: opcode.clone(),
+                witness_values: assigned_witness_values(opcode, initial_witness),
+                residual,
+            })),
+        }
+    }
+
     /// Returns the evaluation of the multiplication term in the arithmetic opcode
     /// If the witness values are not known, then the function returns a None
     /// XXX: Do we need to account for the case where 5xy + 6x = 0 ? We do not know y, but it can be solved given x . But I believe x can be solved with another opcode
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use acir::{
+    circuit::opcodes::{BlackBoxFuncCall, FunctionInput},
+    native_types::{Witness, WitnessMap},
+    FieldElement,
+};
+use blackbox_solver::{
+    BlackBoxDefinition, BlackBoxInput, BlackBoxInputs, BlackBoxRegistry, BlackBoxResolutionError,
+};
+
+use super::{insert_value, OpcodeNotSolvable, OpcodeResolutionError, UnsatisfiedConstraintInfo};
+use crate::BlackBoxFunctionSolver;
+
+/// Check if all of the inputs to the function have assignments
+///
+/// Returns the first missing assignment if any are missing
+fn first_missing_assignment(
+    witness_assignments: &WitnessMap,
+    inputs: &[FunctionInput],
+) -> Option<Witness> {
+    inputs.iter().find_map(|input| {
+        if witness_assignments.contains_key(&input.witness) {
+            None
+        } else {
+            Some(input.witness)
+        }
+    })
+}
+
+/// A black box function call whose definition and operands have been looked up ahead of execution.
+pub(crate) struct PreparedBlackBoxCall<'a> {
+    definition: &'a BlackBoxDefinition,
+    input_operands: Vec<Vec<FunctionInput>>,
+    parameters: Vec<u32>,
+    output_operands: Vec<Vec<Witness>>,
+}
+
+impl<'a> PreparedBlackBoxCall<'a> {
+    /// Prepares `bb_func` to be solved using its definition in `registry`, returning `None` if it has no definition.
+    pub(crate) fn new(registry: &'a BlackBoxRegistry, bb_func: &BlackBoxFuncCall) -> Option<Self> {
+        Some(PreparedBlackBoxCall {
+            definition: registry.get(bb_func.name())?,
+            input_operands: bb_func.get_input_operands(),
+            parameters: bb_func.get_parameters(),
+            output_operands: bb_func.get_output_operands(),
+        })
+    }
+}
+
+pub(crate) fn solve(
+    backend: &impl BlackBoxFunctionSolver,
+    registry: &BlackBoxRegistry,
+    initial_witness: &mut WitnessMap,
+    bb_func: &BlackBoxFuncCall,
+) -> Result<(), OpcodeResolutionError> {
+    match PreparedBlackBoxCall::new(registry, bb_func) {
+        Some(prepared) => solve_prepared(backend, &prepared, initial_witness, bb_func),
+        None => Err(BlackBoxResolutionError::Unsupported(bb_func.get_black_box_func()).into()),
+    }
+}
+
+/// Solves `bb_func`, which has already been [prepared][PreparedBlackBoxCall] as `prepared`.
+pub(crate) fn solve_prepared(
+    backend: &impl BlackBoxFunctionSolver,
+    prepared: &PreparedBlackBoxCall,
+    initial_witness: &mut WitnessMap,
+    bb_func: &BlackBoxFuncCall,
+) -> Result<(), OpcodeResolutionError> {
+    if let Some(unassigned_witness) = prepared
+        .input_operands
+        .iter()
+        .find_map(|operand| first_missing_assignment(initial_witness, operand))
+    {
+        return Err(OpcodeNotSolvable::MissingAssignment(unassigned_witness.0).into());
+    }
+
+    solve_black_box_function(backend, prepared, initial_witness)
+        .map_err(|error| add_black_box_context(error, backend, prepared, initial_witness, bb_func))
+}
+
+/// Attaches the values of the inputs and outputs of `bb_func` to `error`.
+fn add_black_box_context(
+    error: OpcodeResolutionError,
+    backend: &impl BlackBoxFunctionSolver,
+    prepared: &PreparedBlackBoxCall,
+    initial_witness: &WitnessMap,
+    bb_func: &BlackBoxFuncCall,
+) -> OpcodeResolutionError {
+    // All inputs are known to be assigned at this point.
+    let inputs: Vec<(Witness, FieldElement)> = bb_func
+        .get_inputs_vec()
+        .iter()
+        .map(|input| (input.witness, initial_witness[&input.witness]))
+        .collect();
+
+    match error {
+        OpcodeResolutionError::BlackBoxFunctionFailed { opcode_location, func, reason, .. } => {
+            OpcodeResolutionError::BlackBoxFunctionFailed { opcode_location, func, reason, inputs }
+        }
+        OpcodeResolutionError::UnsatisfiedConstrain { opcode_location, .. } => {
+            let outputs = bb_func.get_outputs_vec();
+            let expected_outputs = outputs
+                .iter()
+                .filter_map(|witness| initial_witness.get(witness).map(|value| (*witness, *value)))
+                .collect();
+
+            // Recompute the outputs from the inputs alone so they can be compared against those already assigned.
+            let mut input_witness =
+                WitnessMap::from(inputs.iter().copied().collect::<BTreeMap<_, _>>());
+            let _ = solve_black_box_function(backend, prepared, &mut input_witness);
+            let computed_outputs = outputs
+                .iter()
+                .filter_map(|witness| input_witness.get(witness).map(|value| (*witness, *value)))
+                .collect();
+
+            OpcodeResolutionError::UnsatisfiedConstrain {
+                opcode_location,
+                info: Some(Box::new(UnsatisfiedConstraintInfo::BlackBox {
+                    func: bb_func.get_black_box_func(),
+                    inputs,
+                    expected_outputs,
+                    computed_outputs,
+                })),
+            }
+        }
+        error => error,
+    }
+}
+
+/// The input operands of a prepared call followed by its constant parameters, whose values are read from the
+/// witness map as the native implementation requests them.
+///
+/// Every input must already be assigned in the witness map.
+struct WitnessInputs<'a> {
+    prepared: &'a PreparedBlackBoxCall<'a>,
+    witness_map: &'a WitnessMap,
+}
+
+impl BlackBoxInputs for WitnessInputs<'_> {
+    fn num_operands(&self) -> usize {
+        self.prepared.input_operands.len() + self.prepared.parameters.len()
+    }
+
+    fn operand_len(&self, operand: usize) -> usize {
+        self.prepared.input_operands.get(operand).map_or(1, Vec::len)
+    }
+
+    fn get(&self, operand: usize, index: usize) -> BlackBoxInput {
+        match self.prepared.input_operands.get(operand) {
+            Some(inputs) => {
+                let input = inputs[index];
+                BlackBoxInput { value: self.witness_map[&input.witness], num_bits: input.num_bits }
+            }
+            None => {
+                let parameter =
+                    self.prepared.parameters[operand - self.prepared.input_operands.len()];
+                BlackBoxInput { value: FieldElement::from(parameter as u128), num_bits: 32 }
+            }
+        }
+    }
+}
+
+fn solve_black_box_function(
+    backend: &impl BlackBoxFunctionSolver,
+    prepared: &PreparedBlackBoxCall,
+    initial_witness: &mut WitnessMap,
+) -> Result<(), OpcodeResolutionError> {
+    let inputs = WitnessInputs { prepared, witness_map: initial_witness };
+    let output_sizes: Vec<usize> = prepared.output_operands.iter().map(Vec::len).collect();
+    let outputs = prepared.definition.solve(backend, &inputs, &output_sizes)?;
+
+    for (witness, value) in
+        prepared.output_operands.iter().flatten().zip(outputs.into_iter().flatten())
+    {
+        insert_value(witness, value, initial_witness)?;
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use acir::{
-    brillig::{RegisterIndex, Value},
+    brillig::{ForeignCallParam, ForeignCallResult, RegisterIndex, Value},
     circuit::{
         brillig::{Brillig, BrilligInputs, BrilligOutputs},
         OpcodeLocation,
@@ -7,20 +7,38 @@ use acir::{
     native_types::WitnessMap,
     FieldElement,
 };
-use blackbox_solver::BlackBoxFunctionSolver;
-use brillig_vm::{Registers, VMStatus, VM};
+use blackbox_solver::{BlackBoxFunctionSolver, BlackBoxRegistry};
+use brillig_vm::{BrilligProfile, BrilligTrace, Registers, VMLimits, VMStatus, VM};
+use serde::{Deserialize, Serialize};
 
-use crate::{pwg::OpcodeNotSolvable, OpcodeResolutionError};
+use crate::{
+    pwg::{ErrorLocation, OpcodeNotSolvable},
+    OpcodeResolutionError,
+};
 
 use super::{get_value, insert_value};
 
+/// Configuration of the Brillig VM used to solve a Brillig opcode.
+#[derive(Default)]
+pub(super) struct BrilligOptions<'a> {
+    pub(super) limits: VMLimits,
+    /// Describes how black box functions are solved, or `None` to use the [standard registry][BlackBoxRegistry::standard].
+    pub(super) black_box_registry: Option<&'a BlackBoxRegistry>,
+    /// Receives the instructions executed by the VM if profiling is enabled.
+    pub(super) profile: Option<&'a mut BrilligProfile>,
+    /// Receives the registers and memory modified by the VM if tracing is enabled.
+    pub(super) trace: Option<&'a mut BrilligTrace>,
+}
+
 pub(super) struct BrilligSolver;
 
 impl BrilligSolver {
     pub(super) fn solve<B: BlackBoxFunctionSolver>(
         initial_witness: &mut WitnessMap,
         brillig: &Brillig,
+        foreign_call_results: &[ForeignCallResult],
         bb_solver: &B,
+        options: BrilligOptions<'_>,
         acir_index: usize,
     ) -> Result<Option<ForeignCallWaitInfo>, OpcodeResolutionError> {
         // If the predicate is `None`, then we simply return the value 1
@@ -48,9 +66,9 @@ impl BrilligSolver {
                 BrilligInputs::Single(expr) => match get_value(expr, initial_witness) {
                     Ok(value) => input_register_values.push(value.into()),
                     Err(_) => {
-                        return Err(OpcodeResolutionError::OpcodeNotSolvable(
-                            OpcodeNotSolvable::ExpressionHasTooManyUnknowns(expr.clone()),
-                        ))
+                        return Err(
+                            OpcodeNotSolvable::ExpressionHasTooManyUnknowns(expr.clone()).into()
+                        )
                     }
                 },
                 BrilligInputs::Array(expr_arr) => {
@@ -60,9 +78,10 @@ impl BrilligSolver {
                         match get_value(expr, initial_witness) {
                             Ok(value) => input_memory.push(value.into()),
                             Err(_) => {
-                                return Err(OpcodeResolutionError::OpcodeNotSolvable(
-                                    OpcodeNotSolvable::ExpressionHasTooManyUnknowns(expr.clone()),
-                                ))
+                                return Err(OpcodeNotSolvable::ExpressionHasTooManyUnknowns(
+                                    expr.clone(),
+                                )
+                                .into())
                             }
                         }
                     }
@@ -76,17 +95,39 @@ impl BrilligSolver {
         // Instantiate a Brillig VM given the solved input registers and memory
         // along with the Brillig bytecode, and any present foreign call results.
         let input_registers = Registers::load(input_register_values);
+        let foreign_call_results =
+            brillig.foreign_call_results.iter().chain(foreign_call_results).cloned().collect();
         let mut vm = VM::new(
             input_registers,
             input_memory,
             brillig.bytecode.clone(),
-            brillig.foreign_call_results.clone(),
+            foreign_call_results,
             bb_solver,
-        );
+        )
+        .with_limits(options.limits);
+        if let Some(registry) = options.black_box_registry {
+            vm = vm.with_black_box_registry(registry);
+        }
+        if options.profile.is_some() {
+            vm = vm.with_profiling();
+        }
+        if options.trace.is_some() {
+            vm = vm.with_tracing();
+        }
 
         // Run the Brillig VM on these inputs, bytecode, etc!
         let vm_status = vm.process_opcodes();
 
+        // The VM is re-executed from the start once a foreign call is resolved so only the final execution is recorded.
+        if !matches!(vm_status, VMStatus::ForeignCallWait { .. }) {
+            if let Some(profile) = options.profile {
+                *profile = vm.take_profile().expect("profiling is enabled");
+            }
+            if let Some(trace) = options.trace {
+                *trace = vm.trace().expect("tracing is enabled").clone();
+            }
+        }
+
         // Check the status of the Brillig VM.
         // It may be finished, in-progress, failed, or may be waiting for results of a foreign call.
         // Return the "resolution" to the caller who may choose to make subsequent calls
@@ -113,6 +154,7 @@ impl BrilligSolver {
             VMStatus::InProgress => unreachable!("Brillig VM has not completed execution"),
             VMStatus::Failure { message, call_stack } => {
                 Err(OpcodeResolutionError::BrilligFunctionFailed {
+                    opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(acir_index)),
                     message,
                     call_stack: call_stack
                         .iter()
@@ -123,6 +165,17 @@ impl BrilligSolver {
                         .collect(),
                 })
             }
+            VMStatus::LimitExceeded { limit, call_stack } => {
+                let brillig_index =
+                    *call_stack.last().expect("Brillig error call stacks cannot be empty");
+                Err(OpcodeResolutionError::ResourceLimitExceeded {
+                    opcode_location: ErrorLocation::Resolved(OpcodeLocation::Brillig {
+                        acir_index,
+                        brillig_index,
+                    }),
+                    limit: limit.into(),
+                })
+            }
             VMStatus::ForeignCallWait { function, inputs } => {
                 Ok(Some(ForeignCallWaitInfo { function, inputs }))
             }
@@ -154,10 +207,10 @@ impl BrilligSolver {
 /// where the result of the foreign call has not yet been provided.
 ///
 /// The caller must resolve this opcode externally based upon the information in the request.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ForeignCallWaitInfo {
     /// An identifier interpreted by the caller process
     pub function: String,
     /// Resolved inputs to a foreign call computed in the previous steps of a Brillig VM process
-    pub inputs: Vec<Vec<Value>>,
+    pub inputs: Vec<ForeignCallParam>,
 }
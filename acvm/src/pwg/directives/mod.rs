@@ -67,6 +67,7 @@ pub(super) fn solve_directives(
             if b.len() < decomposed_integer.len() {
                 return Err(OpcodeResolutionError::UnsatisfiedConstrain {
                     opcode_location: ErrorLocation::Unresolved,
+                    info: None,
                 });
             }
 
@@ -0,0 +1,206 @@
+use std::collections::{BTreeSet, HashSet};
+
+use acir::{
+    circuit::{
+        brillig::{BrilligInputs, BrilligOutputs},
+        directives::{Directive, QuotientDirective},
+        opcodes::BlockId,
+        Opcode, OpcodeLocation,
+    },
+    native_types::{Witness, WitnessMap},
+};
+
+use super::{expression_witnesses, OpcodeNotSolvable, OpcodeResolutionError};
+
+/// A failure encountered by an [`ACVM`][super::ACVM] which has been set to [report all failures][super::ACVM::report_all_failures].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpcodeFailure {
+    /// The location of the failing opcode.
+    ///
+    /// For Brillig failures this points to the Brillig opcode which failed rather than the enclosing ACIR opcode.
+    pub opcode_location: OpcodeLocation,
+    /// The assertion message attached to `opcode_location` in the circuit, if one exists.
+    pub assert_message: Option<String>,
+    /// The error which was returned when attempting to solve the opcode.
+    pub error: OpcodeResolutionError,
+}
+
+/// Records the outcome of executing a circuit in diagnostic mode.
+///
+/// Rather than halting on the first unsatisfied constraint, the ACVM records the failure and continues execution.
+/// Any witnesses which the failing opcode did not assign are marked as tainted. Opcodes which later cannot be solved
+/// because of a tainted witness are recorded separately as they are a consequence of an earlier failure.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FailureReport {
+    assert_messages: Vec<(OpcodeLocation, String)>,
+
+    failures: Vec<OpcodeFailure>,
+    tainted_opcodes: Vec<OpcodeLocation>,
+
+    tainted_witnesses: BTreeSet<Witness>,
+    tainted_blocks: HashSet<BlockId>,
+}
+
+impl FailureReport {
+    pub(super) fn new(assert_messages: Vec<(OpcodeLocation, String)>) -> Self {
+        FailureReport { assert_messages, ..FailureReport::default() }
+    }
+
+    /// Returns every failure encountered during execution in the order in which they occurred.
+    pub fn failures(&self) -> &[OpcodeFailure] {
+        &self.failures
+    }
+
+    /// Returns the locations of the opcodes which were skipped as they depend on the output of a failed opcode.
+    pub fn tainted_opcodes(&self) -> &[OpcodeLocation] {
+        &self.tainted_opcodes
+    }
+
+    /// Returns whether `witness` was left unassigned due to a failure.
+    pub fn is_witness_tainted(&self, witness: &Witness) -> bool {
+        self.tainted_witnesses.contains(witness)
+    }
+
+    /// Returns `true` if no failures have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns whether `opcode` operates on a memory block whose contents are no longer known.
+    pub(super) fn reads_tainted_memory(&self, opcode: &Opcode) -> bool {
+        match opcode {
+            Opcode::MemoryOp { block_id, .. } => self.tainted_blocks.contains(block_id),
+            _ => false,
+        }
+    }
+
+    /// Returns whether `error` was caused by a witness which has been tainted by a previous failure.
+    pub(super) fn is_caused_by_taint(&self, error: &OpcodeResolutionError) -> bool {
+        match error {
+            OpcodeResolutionError::OpcodeNotSolvable {
+                reason: OpcodeNotSolvable::MissingAssignment(witness_index),
+                ..
+            } => self.tainted_witnesses.contains(&Witness(*witness_index)),
+            OpcodeResolutionError::OpcodeNotSolvable {
+                reason: OpcodeNotSolvable::ExpressionHasTooManyUnknowns(expr),
+                ..
+            } => {
+                expression_witnesses(expr).any(|witness| self.tainted_witnesses.contains(&witness))
+            }
+            _ => false,
+        }
+    }
+
+    /// Records a failure of `opcode` and taints any of its witnesses which are still unassigned.
+    pub(super) fn record_failure(
+        &mut self,
+        opcode: &Opcode,
+        opcode_location: OpcodeLocation,
+        error: OpcodeResolutionError,
+        witness_map: &WitnessMap,
+    ) {
+        let assert_message = self
+            .assert_messages
+            .iter()
+            .find(|(location, _)| *location == opcode_location)
+            .map(|(_, message)| message.clone());
+        self.failures.push(OpcodeFailure { opcode_location, assert_message, error });
+        self.taint_unassigned_witnesses(opcode, witness_map);
+    }
+
+    /// Records that `opcode` could not be solved due to a previous failure.
+    ///
+    /// All of its unassigned witnesses are tainted and, if it writes to memory, so is the memory block.
+    pub(super) fn record_tainted(
+        &mut self,
+        opcode: &Opcode,
+        acir_index: usize,
+        witness_map: &WitnessMap,
+    ) {
+        self.tainted_opcodes.push(OpcodeLocation::Acir(acir_index));
+        self.taint_unassigned_witnesses(opcode, witness_map);
+        match opcode {
+            Opcode::MemoryInit { block_id, .. } => {
+                self.tainted_blocks.insert(*block_id);
+            }
+            Opcode::MemoryOp { block_id, op, .. } if !op.operation.is_zero() => {
+                self.tainted_blocks.insert(*block_id);
+            }
+            _ => (),
+        }
+    }
+
+    fn taint_unassigned_witnesses(&mut self, opcode: &Opcode, witness_map: &WitnessMap) {
+        let unassigned = opcode_witnesses(opcode)
+            .into_iter()
+            .filter(|witness| !witness_map.contains_key(witness));
+        self.tainted_witnesses.extend(unassigned);
+    }
+}
+
+/// Returns whether the ACVM can safely continue past `error` when reporting all failures.
+///
+/// Errors which indicate that the circuit cannot be executed at all, such as unsupported black box functions,
+/// or an opcode which is unsolvable for reasons other than a previous failure, still halt execution.
+pub(super) fn is_recoverable(error: &OpcodeResolutionError) -> bool {
+    matches!(
+        error,
+        OpcodeResolutionError::UnsatisfiedConstrain { .. }
+            | OpcodeResolutionError::IndexOutOfBounds { .. }
+            | OpcodeResolutionError::BlackBoxFunctionFailed { .. }
+            | OpcodeResolutionError::BrilligFunctionFailed { .. }
+    )
+}
+
+/// Returns all witnesses which are read or written by `opcode`.
+pub(super) fn opcode_witnesses(opcode: &Opcode) -> BTreeSet<Witness> {
+    let mut witnesses = BTreeSet::new();
+    match opcode {
+        Opcode::Arithmetic(expr) => witnesses.extend(expression_witnesses(expr)),
+        Opcode::BlackBoxFuncCall(bb_func) => {
+            witnesses.extend(bb_func.get_inputs_vec().iter().map(|input| input.witness));
+            witnesses.extend(bb_func.get_outputs_vec());
+        }
+        Opcode::Directive(Directive::Quotient(QuotientDirective { a, b, q, r, predicate })) => {
+            witnesses.extend(expression_witnesses(a));
+            witnesses.extend(expression_witnesses(b));
+            witnesses.extend(predicate.iter().flat_map(expression_witnesses));
+            witnesses.extend([*q, *r]);
+        }
+        Opcode::Directive(Directive::ToLeRadix { a, b, .. }) => {
+            witnesses.extend(expression_witnesses(a));
+            witnesses.extend(b.iter().copied());
+        }
+        Opcode::Directive(Directive::PermutationSort { inputs, bits, .. }) => {
+            witnesses.extend(inputs.iter().flatten().flat_map(expression_witnesses));
+            witnesses.extend(bits.iter().copied());
+        }
+        Opcode::Brillig(brillig) => {
+            for input in &brillig.inputs {
+                match input {
+                    BrilligInputs::Single(expr) => witnesses.extend(expression_witnesses(expr)),
+                    BrilligInputs::Array(exprs) => {
+                        witnesses.extend(exprs.iter().flat_map(expression_witnesses))
+                    }
+                }
+            }
+            for output in &brillig.outputs {
+                match output {
+                    BrilligOutputs::Simple(witness) => {
+                        witnesses.insert(*witness);
+                    }
+                    BrilligOutputs::Array(witness_arr) => witnesses.extend(witness_arr.iter()),
+                }
+            }
+            witnesses.extend(brillig.predicate.iter().flat_map(expression_witnesses));
+        }
+        Opcode::MemoryOp { op, predicate, .. } => {
+            witnesses.extend(expression_witnesses(&op.operation));
+            witnesses.extend(expression_witnesses(&op.index));
+            witnesses.extend(expression_witnesses(&op.value));
+            witnesses.extend(predicate.iter().flat_map(expression_witnesses));
+        }
+        Opcode::MemoryInit { init, .. } => witnesses.extend(init.iter()),
+    }
+    witnesses
+}
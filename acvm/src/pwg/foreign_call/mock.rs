@@ -0,0 +1,289 @@
+use acir::brillig::{ForeignCallParam, ForeignCallResult};
+use thiserror::Error;
+
+use super::{ForeignCallError, ForeignCallExecutor};
+use crate::pwg::ForeignCallWaitInfo;
+
+type InputMatcher<'a> = Box<dyn Fn(&[ForeignCallParam]) -> bool + 'a>;
+type ComputedResponse<'a> = Box<dyn FnMut(&[ForeignCallParam]) -> ForeignCallResult + 'a>;
+
+enum MockResponse<'a> {
+    Fixed(ForeignCallResult),
+    Computed(ComputedResponse<'a>),
+}
+
+/// An expected foreign call which is registered with [`MockOracles`].
+///
+/// A mock matches any call to its function unless restricted with [`MockCall::with_inputs`] or [`MockCall::matching`].
+/// It is expected to be called at least once, or exactly as many times as given with [`MockCall::times`].
+pub struct MockCall<'a> {
+    function: String,
+    matcher: Option<InputMatcher<'a>>,
+    response: MockResponse<'a>,
+    expected_calls: Option<usize>,
+    calls: usize,
+}
+
+impl<'a> MockCall<'a> {
+    /// Creates a mock for calls to `function` which returns no outputs.
+    pub fn new(function: impl Into<String>) -> Self {
+        MockCall {
+            function: function.into(),
+            matcher: None,
+            response: MockResponse::Fixed(ForeignCallResult { values: Vec::new() }),
+            expected_calls: None,
+            calls: 0,
+        }
+    }
+
+    /// Only match calls whose inputs are exactly `inputs`.
+    pub fn with_inputs(self, inputs: Vec<ForeignCallParam>) -> Self {
+        self.matching(move |call_inputs| call_inputs == inputs.as_slice())
+    }
+
+    /// Only match calls whose inputs satisfy `predicate`.
+    pub fn matching(mut self, predicate: impl Fn(&[ForeignCallParam]) -> bool + 'a) -> Self {
+        self.matcher = Some(Box::new(predicate));
+        self
+    }
+
+    /// Responds to each matching call with `result`.
+    pub fn returns(mut self, result: impl Into<ForeignCallResult>) -> Self {
+        self.response = MockResponse::Fixed(result.into());
+        self
+    }
+
+    /// Responds to each matching call by passing its inputs to `compute`.
+    pub fn returns_with(
+        mut self,
+        compute: impl FnMut(&[ForeignCallParam]) -> ForeignCallResult + 'a,
+    ) -> Self {
+        self.response = MockResponse::Computed(Box::new(compute));
+        self
+    }
+
+    /// Expects this mock to be called exactly `count` times.
+    pub fn times(mut self, count: usize) -> Self {
+        self.expected_calls = Some(count);
+        self
+    }
+
+    fn matches(&self, foreign_call: &ForeignCallWaitInfo) -> bool {
+        self.function == foreign_call.function
+            && self.matcher.as_ref().map_or(true, |matcher| matcher(&foreign_call.inputs))
+    }
+
+    /// Returns whether this mock has been called as many times as it is allowed to be.
+    fn is_saturated(&self) -> bool {
+        self.expected_calls.map_or(false, |expected| self.calls >= expected)
+    }
+
+    /// Returns whether this mock has been called as many times as is expected.
+    fn is_satisfied(&self) -> bool {
+        match self.expected_calls {
+            Some(expected) => self.calls == expected,
+            None => self.calls > 0,
+        }
+    }
+
+    fn respond(&mut self, inputs: &[ForeignCallParam]) -> ForeignCallResult {
+        self.calls += 1;
+        match &mut self.response {
+            MockResponse::Fixed(result) => result.clone(),
+            MockResponse::Computed(compute) => compute(inputs),
+        }
+    }
+}
+
+/// An expectation registered with [`MockOracles`] which was not met.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum MockExpectationError {
+    #[error(
+        "Expected `{function}` to be called {expected} times but it was called {actual} times"
+    )]
+    CallCount { function: String, expected: usize, actual: usize },
+    #[error("Expected `{function}` to be called at least once but it was never called")]
+    NeverCalled { function: String },
+}
+
+/// A [`ForeignCallExecutor`] which resolves foreign calls using a set of [mocked calls][MockCall].
+///
+/// Each call is resolved by the first registered mock which matches it and has not yet been called its expected
+/// number of times. Calls to functions which have no registered mocks are declined, while calls to mocked functions
+/// which no mock matches result in an error. Once execution is complete, [`MockOracles::verify`] checks
+/// that every mock was called the expected number of times.
+#[derive(Default)]
+pub struct MockOracles<'a> {
+    mocks: Vec<MockCall<'a>>,
+    in_order: bool,
+    /// Index of the mock which was most recently called when enforcing ordering.
+    cursor: usize,
+}
+
+impl<'a> MockOracles<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `mock`. When calls are [expected in order][MockOracles::in_order], mocks must be called in the order in which they are registered.
+    pub fn mock(mut self, mock: MockCall<'a>) -> Self {
+        self.mocks.push(mock);
+        self
+    }
+
+    /// Requires that calls are made in the order in which their mocks were registered.
+    ///
+    /// A call may only move on to a later mock once every mock before it has been called its expected number of times.
+    pub fn in_order(mut self) -> Self {
+        self.in_order = true;
+        self
+    }
+
+    /// Checks that every registered mock was called the expected number of times.
+    ///
+    /// Returns an error for each mock whose expectation was not met. Mocks without an explicit
+    /// [call count][MockCall::times] are expected to have been called at least once.
+    pub fn verify(&self) -> Result<(), Vec<MockExpectationError>> {
+        let errors: Vec<_> = self
+            .mocks
+            .iter()
+            .filter(|mock| !mock.is_satisfied())
+            .map(|mock| match mock.expected_calls {
+                Some(expected) => MockExpectationError::CallCount {
+                    function: mock.function.clone(),
+                    expected,
+                    actual: mock.calls,
+                },
+                None => MockExpectationError::NeverCalled { function: mock.function.clone() },
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Finds the index of the mock which should resolve `foreign_call`.
+    fn find_mock(&self, foreign_call: &ForeignCallWaitInfo) -> Result<usize, String> {
+        let start = if self.in_order { self.cursor } else { 0 };
+        for (index, mock) in self.mocks.iter().enumerate().skip(start) {
+            if mock.matches(foreign_call) && !mock.is_saturated() {
+                return Ok(index);
+            }
+            if self.in_order && !mock.is_satisfied() {
+                return Err(format!(
+                    "call to `{}` was made out of order, expected a call to `{}`",
+                    foreign_call.function, mock.function
+                ));
+            }
+        }
+
+        if self.mocks.iter().any(|mock| mock.matches(foreign_call)) {
+            Err("call was made more times than expected".to_string())
+        } else {
+            Err(format!("no mock matches inputs {:?}", foreign_call.inputs))
+        }
+    }
+}
+
+impl ForeignCallExecutor for MockOracles<'_> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        if !self.mocks.iter().any(|mock| mock.function == foreign_call.function) {
+            return Ok(None);
+        }
+
+        let index = self.find_mock(foreign_call).map_err(|reason| ForeignCallError::Failed {
+            function: foreign_call.function.clone(),
+            reason,
+        })?;
+        self.cursor = index;
+        Ok(Some(self.mocks[index].respond(&foreign_call.inputs)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::brillig::Value;
+
+    use super::{MockCall, MockExpectationError, MockOracles};
+    use crate::pwg::{
+        foreign_call::{ForeignCallError, ForeignCallExecutor},
+        ForeignCallWaitInfo,
+    };
+
+    fn foreign_call(function: &str, input: u128) -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo {
+            function: function.to_string(),
+            inputs: vec![Value::from(input).into()],
+        }
+    }
+
+    #[test]
+    fn matches_calls_by_inputs() {
+        let mut oracles = MockOracles::new()
+            .mock(
+                MockCall::new("get")
+                    .with_inputs(vec![Value::from(1u128).into()])
+                    .returns(Value::from(10u128)),
+            )
+            .mock(
+                MockCall::new("get")
+                    .returns_with(|inputs| Value::from(inputs[0][0].to_usize() * 100).into()),
+            );
+
+        assert_eq!(oracles.execute(&foreign_call("get", 1)), Ok(Some(Value::from(10u128).into())));
+        assert_eq!(oracles.execute(&foreign_call("get", 2)), Ok(Some(Value::from(200u128).into())));
+        assert_eq!(oracles.execute(&foreign_call("other", 1)), Ok(None));
+        assert_eq!(oracles.verify(), Ok(()));
+    }
+
+    #[test]
+    fn reports_unmet_call_counts() {
+        let mut oracles = MockOracles::new()
+            .mock(MockCall::new("once").times(1))
+            .mock(MockCall::new("never_called").times(2))
+            .mock(MockCall::new("unbounded"));
+
+        assert!(oracles.execute(&foreign_call("once", 0)).unwrap().is_some());
+        assert!(matches!(
+            oracles.execute(&foreign_call("once", 0)),
+            Err(ForeignCallError::Failed { .. })
+        ));
+        assert_eq!(
+            oracles.verify(),
+            Err(vec![
+                MockExpectationError::CallCount {
+                    function: "never_called".to_string(),
+                    expected: 2,
+                    actual: 0,
+                },
+                MockExpectationError::NeverCalled { function: "unbounded".to_string() },
+            ])
+        );
+    }
+
+    #[test]
+    fn enforces_call_order() {
+        let mut oracles = MockOracles::new()
+            .in_order()
+            .mock(MockCall::new("first").times(1))
+            .mock(MockCall::new("second").times(1));
+
+        assert_eq!(
+            oracles.execute(&foreign_call("second", 0)),
+            Err(ForeignCallError::Failed {
+                function: "second".to_string(),
+                reason: "call to `second` was made out of order, expected a call to `first`"
+                    .to_string(),
+            })
+        );
+        assert!(oracles.execute(&foreign_call("first", 0)).unwrap().is_some());
+        assert!(oracles.execute(&foreign_call("second", 0)).unwrap().is_some());
+        assert_eq!(oracles.verify(), Ok(()));
+    }
+}
@@ -0,0 +1,302 @@
+use std::{collections::HashMap, future::Future, io::Write, pin::Pin};
+
+use acir::brillig::{ForeignCallParam, ForeignCallResult, Value};
+use thiserror::Error;
+
+use super::ForeignCallWaitInfo;
+
+// Mocked foreign calls for testing
+mod mock;
+// Recording and replaying foreign call results
+mod transcript;
+
+pub use mock::{MockCall, MockExpectationError, MockOracles};
+pub use transcript::{
+    ForeignCallTranscript, RecordingExecutor, ReplayExecutor, TranscriptEntry, TranscriptError,
+    TRANSCRIPT_VERSION,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ForeignCallError {
+    #[error("No handler was found for foreign call `{0}`")]
+    Unhandled(String),
+    #[error("Foreign call `{function}` failed: {reason}")]
+    Failed { function: String, reason: String },
+    #[error(
+        "Foreign call {index} does not match transcript, expected {expected:?} but found {found:?}"
+    )]
+    TranscriptMismatch {
+        index: usize,
+        expected: Box<ForeignCallWaitInfo>,
+        found: Box<ForeignCallWaitInfo>,
+    },
+    #[error("Transcript has no recorded result for foreign call {0:?}")]
+    TranscriptExhausted(Box<ForeignCallWaitInfo>),
+}
+
+/// Resolves [foreign calls][ForeignCallWaitInfo] made by Brillig processes on behalf of [`ACVM::solve_with_executor`][super::ACVM::solve_with_executor].
+pub trait ForeignCallExecutor {
+    /// Attempts to resolve `foreign_call`.
+    ///
+    /// Returns `Ok(None)` if this executor does not handle the requested function,
+    /// allowing the call to be passed on to another executor.
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError>;
+}
+
+impl<E: ForeignCallExecutor + ?Sized> ForeignCallExecutor for &mut E {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        (**self).execute(foreign_call)
+    }
+}
+
+impl<E: ForeignCallExecutor + ?Sized> ForeignCallExecutor for Box<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        (**self).execute(foreign_call)
+    }
+}
+
+/// The future returned by [`AsyncForeignCallExecutor::execute`].
+///
+/// This must be `Send` so that execution may be spawned onto multi-threaded runtimes.
+#[cfg(not(target_arch = "wasm32"))]
+pub type ForeignCallFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<ForeignCallResult>, ForeignCallError>> + Send + 'a>>;
+
+/// The future returned by [`AsyncForeignCallExecutor::execute`].
+///
+/// JavaScript promises cannot be sent between threads so this is not required to be `Send` on wasm targets.
+#[cfg(target_arch = "wasm32")]
+pub type ForeignCallFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<ForeignCallResult>, ForeignCallError>> + 'a>>;
+
+/// Resolves [foreign calls][ForeignCallWaitInfo] asynchronously on behalf of [`ACVM::solve_async`][super::ACVM::solve_async].
+///
+/// This allows foreign calls to be resolved using I/O, such as queries to a database or an RPC service,
+/// without blocking the thread on which the circuit is being executed. The returned future may be driven by any runtime.
+pub trait AsyncForeignCallExecutor {
+    /// Attempts to resolve `foreign_call`.
+    ///
+    /// The future resolves to `Ok(None)` if this executor does not handle the requested function.
+    fn execute<'a>(&'a mut self, foreign_call: &'a ForeignCallWaitInfo) -> ForeignCallFuture<'a>;
+}
+
+impl<E: AsyncForeignCallExecutor + ?Sized> AsyncForeignCallExecutor for &mut E {
+    fn execute<'a>(&'a mut self, foreign_call: &'a ForeignCallWaitInfo) -> ForeignCallFuture<'a> {
+        (**self).execute(foreign_call)
+    }
+}
+
+impl<E: AsyncForeignCallExecutor + ?Sized> AsyncForeignCallExecutor for Box<E> {
+    fn execute<'a>(&'a mut self, foreign_call: &'a ForeignCallWaitInfo) -> ForeignCallFuture<'a> {
+        (**self).execute(foreign_call)
+    }
+}
+
+/// Handles calls to a print function by writing the values of each input to `writer`.
+///
+/// Each input is written as a hex encoded field element, with array inputs enclosed in square brackets.
+/// All of the inputs to a single call are written on one line.
+pub struct PrintExecutor<W: Write> {
+    function: String,
+    writer: W,
+}
+
+impl PrintExecutor<std::io::Stdout> {
+    /// Creates a [`PrintExecutor`] which handles calls to `print` by writing to stdout.
+    pub fn stdout() -> Self {
+        PrintExecutor::new("print", std::io::stdout())
+    }
+}
+
+impl<W: Write> PrintExecutor<W> {
+    /// Creates a [`PrintExecutor`] which handles calls to `function` by writing to `writer`.
+    pub fn new(function: impl Into<String>, writer: W) -> Self {
+        PrintExecutor { function: function.into(), writer }
+    }
+
+    /// Consumes the executor, returning the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ForeignCallExecutor for PrintExecutor<W> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        if foreign_call.function != self.function {
+            return Ok(None);
+        }
+
+        let line: Vec<String> = foreign_call.inputs.iter().map(format_input).collect();
+        writeln!(self.writer, "{}", line.join(" ")).map_err(|err| ForeignCallError::Failed {
+            function: foreign_call.function.clone(),
+            reason: err.to_string(),
+        })?;
+
+        Ok(Some(ForeignCallResult { values: Vec::new() }))
+    }
+}
+
+fn format_input(input: &ForeignCallParam) -> String {
+    let format_value = |value: &Value| format!("0x{}", value.to_field().to_hex());
+    match input {
+        ForeignCallParam::Single(value) => format_value(value),
+        ForeignCallParam::Array(values) => {
+            let values: Vec<String> = values.iter().map(format_value).collect();
+            format!("[{}]", values.join(", "))
+        }
+    }
+}
+
+type ForeignCallFn<'a> =
+    Box<dyn FnMut(&[ForeignCallParam]) -> Result<ForeignCallResult, String> + 'a>;
+
+/// Dispatches foreign calls to closures registered against the name of the called function.
+///
+/// Calls to functions which have no registered closure are declined.
+#[derive(Default)]
+pub struct DispatchExecutor<'a> {
+    handlers: HashMap<String, ForeignCallFn<'a>>,
+}
+
+impl<'a> DispatchExecutor<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` to resolve calls to `function`, replacing any existing handler for `function`.
+    ///
+    /// The handler receives the inputs to the call and returns either its result or a message describing why it failed.
+    pub fn register(
+        mut self,
+        function: impl Into<String>,
+        handler: impl FnMut(&[ForeignCallParam]) -> Result<ForeignCallResult, String> + 'a,
+    ) -> Self {
+        self.handlers.insert(function.into(), Box::new(handler));
+        self
+    }
+}
+
+impl ForeignCallExecutor for DispatchExecutor<'_> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        let Some(handler) = self.handlers.get_mut(&foreign_call.function) else {
+            return Ok(None);
+        };
+        handler(&foreign_call.inputs).map(Some).map_err(|reason| ForeignCallError::Failed {
+            function: foreign_call.function.clone(),
+            reason,
+        })
+    }
+}
+
+/// Passes each foreign call to a sequence of executors in turn until one of them handles it.
+///
+/// The chain declines any call which none of its executors handle.
+#[derive(Default)]
+pub struct ExecutorChain<'a> {
+    executors: Vec<Box<dyn ForeignCallExecutor + 'a>>,
+}
+
+impl<'a> ExecutorChain<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `executor` to the end of the chain.
+    pub fn with(mut self, executor: impl ForeignCallExecutor + 'a) -> Self {
+        self.executors.push(Box::new(executor));
+        self
+    }
+}
+
+impl ForeignCallExecutor for ExecutorChain<'_> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        for executor in &mut self.executors {
+            if let Some(result) = executor.execute(foreign_call)? {
+                return Ok(Some(result));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::{
+        brillig::{ForeignCallParam, ForeignCallResult, Value},
+        FieldElement,
+    };
+
+    use super::{
+        DispatchExecutor, ExecutorChain, ForeignCallError, ForeignCallExecutor, PrintExecutor,
+    };
+    use crate::pwg::ForeignCallWaitInfo;
+
+    fn foreign_call(function: &str, inputs: Vec<ForeignCallParam>) -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo { function: function.to_string(), inputs }
+    }
+
+    #[test]
+    fn print_executor_writes_decoded_values() {
+        let mut executor = PrintExecutor::new("print", Vec::new());
+        let call = foreign_call(
+            "print",
+            vec![
+                Value::from(10u128).into(),
+                vec![Value::from(1u128), Value::from(2u128)].into(),
+                vec![Value::from(3u128)].into(),
+            ],
+        );
+
+        assert_eq!(executor.execute(&call), Ok(Some(ForeignCallResult { values: Vec::new() })));
+        assert_eq!(executor.execute(&foreign_call("other", vec![])), Ok(None));
+
+        let output = String::from_utf8(executor.into_writer()).unwrap();
+        let hex = |value: u128| FieldElement::from(value).to_hex();
+        // Arrays are printed in brackets, even when they hold a single value.
+        assert_eq!(output, format!("0x{} [0x{}, 0x{}] [0x{}]\n", hex(10), hex(1), hex(2), hex(3)));
+    }
+
+    #[test]
+    fn chain_falls_through_to_next_executor() {
+        let mut chain = ExecutorChain::new()
+            .with(DispatchExecutor::new().register("double", |inputs| {
+                Ok(Value::from(inputs[0][0].to_field() * FieldElement::from(2u128)).into())
+            }))
+            .with(
+                DispatchExecutor::new()
+                    .register("double", |_| Err("shadowed".to_string()))
+                    .register("fail", |_| Err("oracle unavailable".to_string())),
+            );
+
+        assert_eq!(
+            chain.execute(&foreign_call("double", vec![Value::from(3u128).into()])),
+            Ok(Some(Value::from(6u128).into()))
+        );
+        assert_eq!(
+            chain.execute(&foreign_call("fail", vec![])),
+            Err(ForeignCallError::Failed {
+                function: "fail".to_string(),
+                reason: "oracle unavailable".to_string()
+            })
+        );
+        assert_eq!(chain.execute(&foreign_call("unknown", vec![])), Ok(None));
+    }
+}
@@ -0,0 +1,226 @@
+use std::io::{Read, Write};
+
+use acir::brillig::ForeignCallResult;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{ForeignCallError, ForeignCallExecutor};
+use crate::pwg::ForeignCallWaitInfo;
+
+/// The version of the transcript file format written by [`ForeignCallTranscript::write`].
+///
+/// This must be incremented whenever the serialized layout of a transcript changes.
+pub const TRANSCRIPT_VERSION: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum TranscriptError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize transcript: {0}")]
+    Serialization(#[from] bincode::Error),
+    #[error("Unsupported transcript version {found}, expected version {TRANSCRIPT_VERSION}")]
+    UnsupportedVersion { found: u32 },
+}
+
+/// A foreign call made during execution along with the result which was returned to the Brillig VM.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub call: ForeignCallWaitInfo,
+    pub result: ForeignCallResult,
+}
+
+/// An ordered record of every foreign call resolved while executing a circuit.
+///
+/// A transcript can be written to disk and later used by a [`ReplayExecutor`] to re-execute the circuit
+/// without access to the systems which originally resolved its foreign calls.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignCallTranscript {
+    entries: Vec<TranscriptEntry>,
+}
+
+impl ForeignCallTranscript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded foreign calls in the order in which they were made.
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    pub fn push(&mut self, call: ForeignCallWaitInfo, result: ForeignCallResult) {
+        self.entries.push(TranscriptEntry { call, result });
+    }
+
+    /// Serializes the transcript to `writer`, prefixed with the [transcript version][TRANSCRIPT_VERSION].
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), TranscriptError> {
+        writer.write_all(&TRANSCRIPT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(writer, self)?;
+        Ok(())
+    }
+
+    /// Deserializes a transcript from `reader`.
+    ///
+    /// Returns an error if the transcript was written using a different [transcript version][TRANSCRIPT_VERSION].
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, TranscriptError> {
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != TRANSCRIPT_VERSION {
+            return Err(TranscriptError::UnsupportedVersion { found: version });
+        }
+        // Field elements can only be deserialized from borrowed data so the transcript must be read in full.
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Ok(bincode::deserialize(&buf)?)
+    }
+}
+
+/// Wraps a [`ForeignCallExecutor`], recording each foreign call which it resolves into a [`ForeignCallTranscript`].
+pub struct RecordingExecutor<E> {
+    executor: E,
+    transcript: ForeignCallTranscript,
+}
+
+impl<E: ForeignCallExecutor> RecordingExecutor<E> {
+    pub fn new(executor: E) -> Self {
+        RecordingExecutor { executor, transcript: ForeignCallTranscript::new() }
+    }
+
+    /// Returns the foreign calls which have been recorded so far.
+    pub fn transcript(&self) -> &ForeignCallTranscript {
+        &self.transcript
+    }
+
+    /// Consumes the recorder, returning the recorded transcript.
+    pub fn into_transcript(self) -> ForeignCallTranscript {
+        self.transcript
+    }
+}
+
+impl<E: ForeignCallExecutor> ForeignCallExecutor for RecordingExecutor<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        let result = self.executor.execute(foreign_call)?;
+        if let Some(result) = &result {
+            self.transcript.push(foreign_call.clone(), result.clone());
+        }
+        Ok(result)
+    }
+}
+
+/// Resolves foreign calls by serving the results stored in a [`ForeignCallTranscript`] in order.
+///
+/// Every call must exactly match the next call in the transcript, otherwise a [`ForeignCallError::TranscriptMismatch`]
+/// is returned. Calls made after the transcript has been exhausted result in [`ForeignCallError::TranscriptExhausted`].
+pub struct ReplayExecutor {
+    transcript: ForeignCallTranscript,
+    position: usize,
+}
+
+impl ReplayExecutor {
+    pub fn new(transcript: ForeignCallTranscript) -> Self {
+        ReplayExecutor { transcript, position: 0 }
+    }
+
+    /// Returns the number of recorded foreign calls which have not yet been replayed.
+    pub fn remaining(&self) -> usize {
+        self.transcript.entries.len() - self.position
+    }
+}
+
+impl ForeignCallExecutor for ReplayExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<Option<ForeignCallResult>, ForeignCallError> {
+        let Some(entry) = self.transcript.entries.get(self.position) else {
+            return Err(ForeignCallError::TranscriptExhausted(Box::new(foreign_call.clone())));
+        };
+        if entry.call != *foreign_call {
+            return Err(ForeignCallError::TranscriptMismatch {
+                index: self.position,
+                expected: Box::new(entry.call.clone()),
+                found: Box::new(foreign_call.clone()),
+            });
+        }
+
+        self.position += 1;
+        Ok(Some(entry.result.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acir::brillig::Value;
+
+    use super::{
+        ForeignCallTranscript, RecordingExecutor, ReplayExecutor, TranscriptError,
+        TRANSCRIPT_VERSION,
+    };
+    use crate::pwg::{
+        foreign_call::{DispatchExecutor, ForeignCallError, ForeignCallExecutor},
+        ForeignCallWaitInfo,
+    };
+
+    fn foreign_call(function: &str, input: u128) -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo {
+            function: function.to_string(),
+            inputs: vec![Value::from(input).into()],
+        }
+    }
+
+    #[test]
+    fn replays_recorded_transcript() {
+        let mut recorder = RecordingExecutor::new(
+            DispatchExecutor::new()
+                .register("double", |inputs| Ok(Value::from(inputs[0][0].to_usize() * 2).into())),
+        );
+        recorder.execute(&foreign_call("double", 3)).unwrap();
+        recorder.execute(&foreign_call("double", 4)).unwrap();
+        // Declined calls are not recorded.
+        assert_eq!(recorder.execute(&foreign_call("unknown", 0)), Ok(None));
+
+        let mut bytes = Vec::new();
+        recorder.into_transcript().write(&mut bytes).unwrap();
+        let transcript = ForeignCallTranscript::read(bytes.as_slice()).unwrap();
+        assert_eq!(transcript.entries().len(), 2);
+
+        let mut replayer = ReplayExecutor::new(transcript);
+        assert_eq!(
+            replayer.execute(&foreign_call("double", 3)),
+            Ok(Some(Value::from(6u128).into()))
+        );
+        assert_eq!(
+            replayer.execute(&foreign_call("double", 5)),
+            Err(ForeignCallError::TranscriptMismatch {
+                index: 1,
+                expected: Box::new(foreign_call("double", 4)),
+                found: Box::new(foreign_call("double", 5)),
+            })
+        );
+        assert_eq!(
+            replayer.execute(&foreign_call("double", 4)),
+            Ok(Some(Value::from(8u128).into()))
+        );
+        assert_eq!(replayer.remaining(), 0);
+        assert_eq!(
+            replayer.execute(&foreign_call("double", 4)),
+            Err(ForeignCallError::TranscriptExhausted(Box::new(foreign_call("double", 4))))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let mut bytes = Vec::new();
+        ForeignCallTranscript::new().write(&mut bytes).unwrap();
+        bytes[..4].copy_from_slice(&(TRANSCRIPT_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            ForeignCallTranscript::read(bytes.as_slice()),
+            Err(TranscriptError::UnsupportedVersion { found }) if found == TRANSCRIPT_VERSION + 1
+        ));
+    }
+}
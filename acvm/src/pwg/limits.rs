@@ -0,0 +1,61 @@
+use std::time::Instant;
+
+use brillig_vm::{LimitExceeded, VMLimits};
+use thiserror::Error;
+
+/// Bounds on the resources which may be consumed while executing a circuit.
+///
+/// All limits are unbounded by default.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct ResourceLimits {
+    /// The maximum number of opcodes which may be processed by each Brillig call.
+    pub max_brillig_steps: Option<u64>,
+    /// The maximum number of opcodes which may be solved by the ACVM.
+    pub max_opcodes: Option<usize>,
+    /// The maximum number of values which the memory of each Brillig call may hold.
+    pub max_brillig_memory: Option<usize>,
+    /// The maximum depth of nested calls within each Brillig call.
+    pub max_brillig_call_depth: Option<usize>,
+    /// The time by which execution must have completed.
+    ///
+    /// This is not supported on targets without a system clock such as `wasm32-unknown-unknown`.
+    pub deadline: Option<Instant>,
+}
+
+impl ResourceLimits {
+    /// Returns the limits which apply to each Brillig call.
+    pub(super) fn brillig_limits(&self) -> VMLimits {
+        VMLimits {
+            max_steps: self.max_brillig_steps,
+            max_memory_size: self.max_brillig_memory,
+            max_call_depth: self.max_brillig_call_depth,
+            deadline: self.deadline,
+        }
+    }
+}
+
+/// The [resource limit][ResourceLimits] which caused execution to halt.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Error)]
+pub enum ResourceLimitError {
+    #[error("Brillig call exceeded the maximum of {0} steps")]
+    BrilligSteps(u64),
+    #[error("Brillig call exceeded the maximum memory size of {0} values")]
+    BrilligMemory(usize),
+    #[error("Brillig call exceeded the maximum call depth of {0}")]
+    BrilligCallDepth(usize),
+    #[error("Circuit exceeded the maximum of {0} solved opcodes")]
+    Opcodes(usize),
+    #[error("Execution did not complete before the deadline")]
+    Deadline,
+}
+
+impl From<LimitExceeded> for ResourceLimitError {
+    fn from(limit: LimitExceeded) -> Self {
+        match limit {
+            LimitExceeded::Steps(max_steps) => ResourceLimitError::BrilligSteps(max_steps),
+            LimitExceeded::MemorySize(max_size) => ResourceLimitError::BrilligMemory(max_size),
+            LimitExceeded::CallDepth(max_depth) => ResourceLimitError::BrilligCallDepth(max_depth),
+            LimitExceeded::Deadline => ResourceLimitError::Deadline,
+        }
+    }
+}
@@ -1,6 +1,11 @@
 // Re-usable methods that backends can use to implement their PWG
 
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+    io::Write,
+    time::Instant,
+};
 
 use acir::{
     brillig::ForeignCallResult,
@@ -8,11 +13,16 @@ use acir::{
     native_types::{Expression, Witness, WitnessMap},
     BlackBoxFunc, FieldElement,
 };
-use blackbox_solver::BlackBoxResolutionError;
+use blackbox_solver::{BlackBoxRegistry, BlackBoxResolutionError};
+use brillig_vm::{BrilligProfile, BrilligTrace};
 
 use self::{
-    arithmetic::ArithmeticSolver, brillig::BrilligSolver, directives::solve_directives,
+    arithmetic::ArithmeticSolver,
+    brillig::{BrilligOptions, BrilligSolver},
+    directives::solve_directives,
+    failures::is_recoverable,
     memory_op::MemoryOpSolver,
+    trace::TraceRecorder,
 };
 use crate::{BlackBoxFunctionSolver, Language};
 
@@ -26,9 +36,39 @@ mod brillig;
 mod directives;
 // black box functions
 mod blackbox;
+// Diagnostic mode which records all failures
+mod failures;
+// Bounds on the resources consumed during execution
+mod limits;
+// Native resolution of foreign calls
+mod foreign_call;
 mod memory_op;
+// Repeated execution of a single circuit
+mod plan;
+// Recording of where execution time is spent
+mod profiler;
+// Recording of the witnesses assigned by each opcode
+mod trace;
+// Concurrent solving of independent opcodes
+#[cfg(feature = "parallel")]
+mod parallel;
 
 pub use brillig::ForeignCallWaitInfo;
+pub use failures::{FailureReport, OpcodeFailure};
+pub use foreign_call::{
+    AsyncForeignCallExecutor, DispatchExecutor, ExecutorChain, ForeignCallError,
+    ForeignCallExecutor, ForeignCallFuture, ForeignCallTranscript, MockCall, MockExpectationError,
+    MockOracles, PrintExecutor, RecordingExecutor, ReplayExecutor, TranscriptEntry,
+    TranscriptError, TRANSCRIPT_VERSION,
+};
+pub use limits::{ResourceLimitError, ResourceLimits};
+use plan::PreparedOpcodes;
+pub use plan::{ExecutionFailure, ExecutionPlan};
+pub use profiler::{ExecutionProfile, LocationProfile, OpcodeStats};
+pub use trace::{
+    OpcodeTrace, TraceError, TraceFormat, TraceOptions, TraceReader, TraceRecord,
+    TRACE_FORMAT_VERSION,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ACVMStatus {
@@ -97,64 +137,347 @@ impl std::fmt::Display for ErrorLocation {
     }
 }
 
+/// Describes the values which caused a constraint to be unsatisfied.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UnsatisfiedConstraintInfo {
+    /// An [`Expression`] which did not evaluate to zero.
+    Expression {
+        expression: Expression,
+        /// The values assigned to each witness which appears in `expression`.
+        witness_values: Vec<(Witness, FieldElement)>,
+        /// The value which `expression` evaluated to.
+        residual: FieldElement,
+    },
+    /// A black box function whose outputs are inconsistent with its inputs.
+    BlackBox {
+        func: BlackBoxFunc,
+        /// The values assigned to each of the function's inputs.
+        inputs: Vec<(Witness, FieldElement)>,
+        /// The values which were already assigned to the function's outputs.
+        expected_outputs: Vec<(Witness, FieldElement)>,
+        /// The outputs computed by the function from `inputs`.
+        computed_outputs: Vec<(Witness, FieldElement)>,
+    },
+}
+
+impl std::fmt::Display for UnsatisfiedConstraintInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_values(
+            f: &mut std::fmt::Formatter<'_>,
+            values: &[(Witness, FieldElement)],
+        ) -> std::fmt::Result {
+            write!(f, "[")?;
+            for (index, (witness, value)) in values.iter().enumerate() {
+                if index != 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "_{} = {value}", witness.witness_index())?;
+            }
+            write!(f, "]")
+        }
+
+        match self {
+            UnsatisfiedConstraintInfo::Expression { expression, witness_values, residual } => {
+                write!(f, "expression {expression} evaluated to {residual} with witnesses ")?;
+                write_values(f, witness_values)
+            }
+            UnsatisfiedConstraintInfo::BlackBox {
+                func,
+                inputs,
+                expected_outputs,
+                computed_outputs,
+            } => {
+                write!(f, "{func} with inputs ")?;
+                write_values(f, inputs)?;
+                write!(f, " expected outputs ")?;
+                write_values(f, expected_outputs)?;
+                write!(f, " but computed ")?;
+                write_values(f, computed_outputs)
+            }
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Error)]
 pub enum OpcodeResolutionError {
-    #[error("Cannot solve opcode: {0}")]
-    OpcodeNotSolvable(#[from] OpcodeNotSolvable),
-    #[error("Backend does not currently support the {0} opcode. ACVM does not currently have a fallback for this opcode.")]
-    UnsupportedBlackBoxFunc(BlackBoxFunc),
-    #[error("Cannot satisfy constraint")]
-    UnsatisfiedConstrain { opcode_location: ErrorLocation },
+    #[error("Cannot solve opcode: {reason}")]
+    OpcodeNotSolvable { opcode_location: ErrorLocation, reason: OpcodeNotSolvable },
+    #[error("Backend does not currently support the {func} opcode. ACVM does not currently have a fallback for this opcode.")]
+    UnsupportedBlackBoxFunc { opcode_location: ErrorLocation, func: BlackBoxFunc },
+    #[error("Cannot satisfy constraint{}", info.as_ref().map(|info| format!(": {info}")).unwrap_or_default())]
+    UnsatisfiedConstrain {
+        opcode_location: ErrorLocation,
+        /// The values which caused the constraint to fail, where these are known.
+        info: Option<Box<UnsatisfiedConstraintInfo>>,
+    },
     #[error("Index out of bounds, array has size {array_size:?}, but index was {index:?}")]
     IndexOutOfBounds { opcode_location: ErrorLocation, index: u32, array_size: u32 },
-    #[error("Failed to solve blackbox function: {0}, reason: {1}")]
-    BlackBoxFunctionFailed(BlackBoxFunc, String),
+    #[error("Failed to solve blackbox function: {func}, reason: {reason}")]
+    BlackBoxFunctionFailed {
+        opcode_location: ErrorLocation,
+        func: BlackBoxFunc,
+        reason: String,
+        /// The values assigned to each of the function's inputs.
+        inputs: Vec<(Witness, FieldElement)>,
+    },
     #[error("Failed to solve brillig function, reason: {message}")]
-    BrilligFunctionFailed { message: String, call_stack: Vec<OpcodeLocation> },
+    BrilligFunctionFailed {
+        opcode_location: ErrorLocation,
+        message: String,
+        call_stack: Vec<OpcodeLocation>,
+    },
+    #[error("Failed to resolve foreign call: {source}")]
+    ForeignCallFailed { opcode_location: ErrorLocation, source: ForeignCallError },
+    #[error("Resource limit exceeded: {limit}")]
+    ResourceLimitExceeded { opcode_location: ErrorLocation, limit: ResourceLimitError },
+    /// A black box function registered under a name which is not that of a [`BlackBoxFunc`] failed, e.g. when called
+    /// by the native implementation of another function.
+    #[error("Failed to solve blackbox function: {name}, reason: {reason}")]
+    BlackBoxExtensionFailed { opcode_location: ErrorLocation, name: String, reason: String },
+}
+
+impl OpcodeResolutionError {
+    /// Returns the location of the ACIR opcode which caused this error.
+    pub fn opcode_location(&self) -> ErrorLocation {
+        match self {
+            OpcodeResolutionError::OpcodeNotSolvable { opcode_location, .. }
+            | OpcodeResolutionError::UnsupportedBlackBoxFunc { opcode_location, .. }
+            | OpcodeResolutionError::UnsatisfiedConstrain { opcode_location, .. }
+            | OpcodeResolutionError::IndexOutOfBounds { opcode_location, .. }
+            | OpcodeResolutionError::BlackBoxFunctionFailed { opcode_location, .. }
+            | OpcodeResolutionError::BrilligFunctionFailed { opcode_location, .. }
+            | OpcodeResolutionError::ForeignCallFailed { opcode_location, .. }
+            | OpcodeResolutionError::ResourceLimitExceeded { opcode_location, .. }
+            | OpcodeResolutionError::BlackBoxExtensionFailed { opcode_location, .. } => {
+                *opcode_location
+            }
+        }
+    }
+
+    /// Sets the location of this error to `location` if it has not already been resolved.
+    fn resolve_location(&mut self, location: OpcodeLocation) {
+        let opcode_location = match self {
+            OpcodeResolutionError::OpcodeNotSolvable { opcode_location, .. }
+            | OpcodeResolutionError::UnsupportedBlackBoxFunc { opcode_location, .. }
+            | OpcodeResolutionError::UnsatisfiedConstrain { opcode_location, .. }
+            | OpcodeResolutionError::IndexOutOfBounds { opcode_location, .. }
+            | OpcodeResolutionError::BlackBoxFunctionFailed { opcode_location, .. }
+            | OpcodeResolutionError::BrilligFunctionFailed { opcode_location, .. }
+            | OpcodeResolutionError::ForeignCallFailed { opcode_location, .. }
+            | OpcodeResolutionError::ResourceLimitExceeded { opcode_location, .. }
+            | OpcodeResolutionError::BlackBoxExtensionFailed { opcode_location, .. } => {
+                opcode_location
+            }
+        };
+        if *opcode_location == ErrorLocation::Unresolved {
+            *opcode_location = ErrorLocation::Resolved(location);
+        }
+    }
+}
+
+impl From<OpcodeNotSolvable> for OpcodeResolutionError {
+    fn from(reason: OpcodeNotSolvable) -> Self {
+        OpcodeResolutionError::OpcodeNotSolvable {
+            opcode_location: ErrorLocation::Unresolved,
+            reason,
+        }
+    }
 }
 
 impl From<BlackBoxResolutionError> for OpcodeResolutionError {
     fn from(value: BlackBoxResolutionError) -> Self {
         match value {
             BlackBoxResolutionError::Failed(func, reason) => {
-                OpcodeResolutionError::BlackBoxFunctionFailed(func, reason)
+                OpcodeResolutionError::BlackBoxFunctionFailed {
+                    opcode_location: ErrorLocation::Unresolved,
+                    func,
+                    reason,
+                    inputs: Vec::new(),
+                }
             }
             BlackBoxResolutionError::Unsupported(func) => {
-                OpcodeResolutionError::UnsupportedBlackBoxFunc(func)
+                OpcodeResolutionError::UnsupportedBlackBoxFunc {
+                    opcode_location: ErrorLocation::Unresolved,
+                    func,
+                }
+            }
+            BlackBoxResolutionError::Unsatisfied(_) => {
+                OpcodeResolutionError::UnsatisfiedConstrain {
+                    opcode_location: ErrorLocation::Unresolved,
+                    info: None,
+                }
+            }
+            BlackBoxResolutionError::UnsupportedExtension(name) => {
+                OpcodeResolutionError::BlackBoxExtensionFailed {
+                    opcode_location: ErrorLocation::Unresolved,
+                    name,
+                    reason: "no definition is registered".to_string(),
+                }
+            }
+            BlackBoxResolutionError::ExtensionFailed(name, reason) => {
+                OpcodeResolutionError::BlackBoxExtensionFailed {
+                    opcode_location: ErrorLocation::Unresolved,
+                    name,
+                    reason,
+                }
             }
         }
     }
 }
 
-pub struct ACVM<'backend, B: BlackBoxFunctionSolver> {
+pub struct ACVM<'a, B: BlackBoxFunctionSolver> {
     status: ACVMStatus,
 
-    backend: &'backend B,
+    backend: &'a B,
+
+    /// Describes how each black box function is solved, including those called from Brillig opcodes.
+    black_box_registry: &'a BlackBoxRegistry,
 
     /// Stores the solver for memory operations acting on blocks of memory disambiguated by [block][`BlockId`].
     block_solvers: HashMap<BlockId, MemoryOpSolver>,
 
     /// A list of opcodes which are to be executed by the ACVM.
-    opcodes: Vec<Opcode>,
+    opcodes: Cow<'a, [Opcode]>,
     /// Index of the next opcode to be executed.
     instruction_pointer: usize,
+    /// Work done ahead of execution by an [`ExecutionPlan`], if the ACVM was created from one.
+    prepared: Option<&'a PreparedOpcodes<'a>>,
+
+    /// Results of the foreign calls made by the Brillig opcode at the current instruction pointer.
+    foreign_call_results: Vec<ForeignCallResult>,
 
     witness_map: WitnessMap,
+
+    /// Records failures rather than halting execution if the ACVM has been set to [report all failures][ACVM::report_all_failures].
+    failure_report: Option<FailureReport>,
+
+    /// Bounds on the resources which execution may consume.
+    resource_limits: ResourceLimits,
+    /// The number of opcodes which have been solved so far.
+    opcodes_solved: usize,
+
+    /// Records where execution time is spent if the ACVM has been set to [profile execution][ACVM::enable_profiling].
+    profile: Option<ExecutionProfile>,
+
+    /// Writes an execution trace if the ACVM has been set to [trace execution][ACVM::trace_execution].
+    tracer: Option<TraceRecorder<'a>>,
 }
 
-impl<'backend, B: BlackBoxFunctionSolver> ACVM<'backend, B> {
-    pub fn new(backend: &'backend B, opcodes: Vec<Opcode>, initial_witness: WitnessMap) -> Self {
+impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
+    pub fn new(backend: &'a B, opcodes: Vec<Opcode>, initial_witness: WitnessMap) -> Self {
+        Self::from_opcodes(backend, Cow::Owned(opcodes), initial_witness)
+    }
+
+    /// Creates an ACVM which executes `opcodes` without taking ownership of them.
+    ///
+    /// This avoids cloning the circuit when executing it multiple times, see [`ExecutionPlan`].
+    pub fn new_borrowed(
+        backend: &'a B,
+        opcodes: &'a [Opcode],
+        initial_witness: WitnessMap,
+    ) -> Self {
+        Self::from_opcodes(backend, Cow::Borrowed(opcodes), initial_witness)
+    }
+
+    fn from_opcodes(
+        backend: &'a B,
+        opcodes: Cow<'a, [Opcode]>,
+        initial_witness: WitnessMap,
+    ) -> Self {
         let status = if opcodes.is_empty() { ACVMStatus::Solved } else { ACVMStatus::InProgress };
         ACVM {
             status,
             backend,
+            black_box_registry: BlackBoxRegistry::standard(),
             block_solvers: HashMap::default(),
             opcodes,
             instruction_pointer: 0,
+            prepared: None,
+            foreign_call_results: Vec::new(),
             witness_map: initial_witness,
+            failure_report: None,
+            resource_limits: ResourceLimits::default(),
+            opcodes_solved: 0,
+            profile: None,
+            tracer: None,
+        }
+    }
+
+    /// Uses the work in `prepared` rather than repeating it, skipping each opcode which it marks as satisfied.
+    ///
+    /// `prepared` must have been created from the ACVM's opcodes.
+    fn use_prepared_opcodes(&mut self, prepared: &'a PreparedOpcodes<'a>) {
+        assert_eq!(prepared.satisfied.len(), self.opcodes.len());
+        self.prepared = Some(prepared);
+        if self.status == ACVMStatus::InProgress && prepared.satisfied[0] {
+            self.increment_instruction_pointer();
+        }
+    }
+
+    /// Sets the ACVM to continue executing after encountering an unsatisfied constraint.
+    ///
+    /// Each failure is recorded along with its message from `assert_messages` and execution continues with the next opcode.
+    /// Opcodes which cannot be solved because they depend on the outputs of a failed opcode are marked as tainted
+    /// rather than being reported as failures. Once all opcodes have been executed the ACVM enters the
+    /// [`Failure`][ACVMStatus::Failure] state with the first recorded error if any failures were encountered.
+    ///
+    /// The recorded failures can be retrieved with [`ACVM::failure_report`].
+    pub fn report_all_failures(&mut self, assert_messages: Vec<(OpcodeLocation, String)>) {
+        self.failure_report = Some(FailureReport::new(assert_messages));
+    }
+
+    /// Sets the ACVM to solve black box functions using `registry` rather than the
+    /// [standard registry][BlackBoxRegistry::standard].
+    ///
+    /// Only the native implementations of its definitions are used, so this may be the same registry which the
+    /// circuit was [compiled][crate::compiler::compile_with_registry] with.
+    pub fn set_black_box_registry(&mut self, registry: &'a BlackBoxRegistry) {
+        self.black_box_registry = registry;
+    }
+
+    /// Bounds the resources which may be consumed while executing the circuit.
+    ///
+    /// Exceeding any of `resource_limits` causes the ACVM to fail with an
+    /// [`OpcodeResolutionError::ResourceLimitExceeded`] error.
+    pub fn set_resource_limits(&mut self, resource_limits: ResourceLimits) {
+        self.resource_limits = resource_limits;
+    }
+
+    /// Sets the ACVM to record the time spent solving each opcode and the instructions executed by each Brillig opcode.
+    ///
+    /// The recorded profile can be retrieved with [`ACVM::profile`]. Profiling disables concurrent solving of opcodes
+    /// so that the time spent on each opcode can be measured.
+    pub fn enable_profiling(&mut self) {
+        self.profile = Some(ExecutionProfile::default());
+    }
+
+    /// Returns the profile recorded so far if the ACVM has been set to [profile execution][ACVM::enable_profiling].
+    pub fn profile(&self) -> Option<&ExecutionProfile> {
+        self.profile.as_ref()
+    }
+
+    /// Sets the ACVM to write a trace of the witnesses assigned by each opcode to `writer`.
+    ///
+    /// The trace begins with the current witness map and is written as each opcode is solved, so this should be
+    /// called before execution begins. Tracing disables concurrent solving of opcodes. Once execution is complete,
+    /// [`ACVM::finish_trace`] must be called to flush the trace and check for any errors while writing it.
+    pub fn trace_execution(&mut self, writer: impl Write + Send + 'a, options: TraceOptions) {
+        self.tracer = Some(TraceRecorder::new(writer, options, &self.witness_map));
+    }
+
+    /// Stops tracing execution, flushing the trace and returning the first error encountered while writing it.
+    pub fn finish_trace(&mut self) -> Result<(), TraceError> {
+        match self.tracer.take() {
+            Some(tracer) => tracer.finish(),
+            None => Ok(()),
         }
     }
 
+    /// Returns the failures recorded so far if the ACVM has been set to [report all failures][ACVM::report_all_failures].
+    pub fn failure_report(&self) -> Option<&FailureReport> {
+        self.failure_report.as_ref()
+    }
+
     /// Returns a reference to the current state of the ACVM's [`WitnessMap`].
     ///
     /// Once execution has completed, the witness map can be extracted using [`ACVM::finalize`]
@@ -216,12 +539,8 @@ impl<'backend, B: BlackBoxFunctionSolver> ACVM<'backend, B> {
             panic!("ACVM is not expecting a foreign call response as no call was made");
         }
 
-        // We want to inject the foreign call result into the brillig opcode which initiated the call.
-        let opcode = &mut self.opcodes[self.instruction_pointer];
-        let Opcode::Brillig(brillig) = opcode else {
-            unreachable!("ACVM can only enter `RequiresForeignCall` state on a Brillig opcode");
-        };
-        brillig.foreign_call_results.push(foreign_call_result);
+        // The foreign call result is provided to the brillig opcode which initiated the call when it is next solved.
+        self.foreign_call_results.push(foreign_call_result);
 
         // Now that the foreign call has been resolved then we can resume execution.
         self.status(ACVMStatus::InProgress);
@@ -240,14 +559,104 @@ impl<'backend, B: BlackBoxFunctionSolver> ACVM<'backend, B> {
         self.status.clone()
     }
 
+    /// Executes the ACVM's circuit to completion, resolving any foreign calls using `executor`.
+    ///
+    /// Returns either [`ACVMStatus::Solved`] or [`ACVMStatus::Failure`]. A foreign call which `executor`
+    /// fails or declines to resolve results in an [`OpcodeResolutionError::ForeignCallFailed`] error.
+    pub fn solve_with_executor(&mut self, executor: &mut impl ForeignCallExecutor) -> ACVMStatus {
+        loop {
+            let foreign_call = match self.solve() {
+                ACVMStatus::RequiresForeignCall(foreign_call) => foreign_call,
+                status => return status,
+            };
+
+            let result = executor.execute(&foreign_call);
+            if let Err(status) = self.complete_foreign_call(&foreign_call, result) {
+                return status;
+            }
+        }
+    }
+
+    /// Executes the ACVM's circuit to completion, resolving any foreign calls asynchronously using `executor`.
+    ///
+    /// This behaves identically to [`ACVM::solve_with_executor`] except that execution is suspended while
+    /// waiting for foreign calls to be resolved. The returned future does not depend upon any particular runtime.
+    pub async fn solve_async(
+        &mut self,
+        executor: &mut impl AsyncForeignCallExecutor,
+    ) -> ACVMStatus {
+        loop {
+            let foreign_call = match self.solve() {
+                ACVMStatus::RequiresForeignCall(foreign_call) => foreign_call,
+                status => return status,
+            };
+
+            let result = executor.execute(&foreign_call).await;
+            if let Err(status) = self.complete_foreign_call(&foreign_call, result) {
+                return status;
+            }
+        }
+    }
+
+    /// Resolves the pending `foreign_call` with the outcome of passing it to an executor.
+    ///
+    /// Fails the ACVM if the executor failed or declined to resolve the call, returning the resulting status.
+    fn complete_foreign_call(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+        result: Result<Option<ForeignCallResult>, ForeignCallError>,
+    ) -> Result<(), ACVMStatus> {
+        let result = result.and_then(|result| {
+            result.ok_or_else(|| ForeignCallError::Unhandled(foreign_call.function.clone()))
+        });
+        match result {
+            Ok(result) => {
+                self.resolve_pending_foreign_call(result);
+                Ok(())
+            }
+            Err(source) => Err(self.fail(OpcodeResolutionError::ForeignCallFailed {
+                opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(
+                    self.instruction_pointer,
+                )),
+                source,
+            })),
+        }
+    }
+
     pub fn solve_opcode(&mut self) -> ACVMStatus {
+        if let Some(limit) = self.exceeded_limit() {
+            return self.fail_resource_limit(limit);
+        }
         let opcode = &self.opcodes[self.instruction_pointer];
+        let start = self.profile.is_some().then(Instant::now);
+        let pending =
+            self.tracer.as_ref().map(|tracer| tracer.begin_opcode(opcode, &self.witness_map));
+        let mut brillig_profile = None;
+        let mut brillig_trace = None;
+
+        if let Some(report) = &mut self.failure_report {
+            if report.reads_tainted_memory(opcode) {
+                report.record_tainted(opcode, self.instruction_pointer, &self.witness_map);
+                return self.increment_instruction_pointer();
+            }
+        }
 
         let resolution = match opcode {
             Opcode::Arithmetic(expr) => ArithmeticSolver::solve(&mut self.witness_map, expr),
-            Opcode::BlackBoxFuncCall(bb_func) => {
-                blackbox::solve(self.backend, &mut self.witness_map, bb_func)
-            }
+            Opcode::BlackBoxFuncCall(bb_func) => match self
+                .prepared
+                .and_then(|prepared| prepared.black_box_calls[self.instruction_pointer].as_ref())
+            {
+                Some(call) => {
+                    blackbox::solve_prepared(self.backend, call, &mut self.witness_map, bb_func)
+                }
+                None => blackbox::solve(
+                    self.backend,
+                    self.black_box_registry,
+                    &mut self.witness_map,
+                    bb_func,
+                ),
+            },
             Opcode::Directive(directive) => solve_directives(&mut self.witness_map, directive),
             Opcode::MemoryInit { block_id, init } => {
                 let solver = self.block_solvers.entry(*block_id).or_default();
@@ -258,10 +667,24 @@ impl<'backend, B: BlackBoxFunctionSolver> ACVM<'backend, B> {
                 solver.solve_memory_op(op, &mut self.witness_map, predicate)
             }
             Opcode::Brillig(brillig) => {
+                brillig_profile = self.profile.as_ref().map(|_| BrilligProfile::default());
+                brillig_trace = self
+                    .tracer
+                    .as_ref()
+                    .filter(|tracer| tracer.traces_brillig())
+                    .map(|_| BrilligTrace::default());
+                let options = BrilligOptions {
+                    limits: self.resource_limits.brillig_limits(),
+                    black_box_registry: Some(self.black_box_registry),
+                    profile: brillig_profile.as_mut(),
+                    trace: brillig_trace.as_mut(),
+                };
                 match BrilligSolver::solve(
                     &mut self.witness_map,
                     brillig,
+                    &self.foreign_call_results,
                     self.backend,
+                    options,
                     self.instruction_pointer,
                 ) {
                     Ok(Some(foreign_call)) => return self.wait_for_foreign_call(foreign_call),
@@ -269,38 +692,156 @@ impl<'backend, B: BlackBoxFunctionSolver> ACVM<'backend, B> {
                 }
             }
         };
-        match resolution {
-            Ok(()) => {
-                self.instruction_pointer += 1;
-                if self.instruction_pointer == self.opcodes.len() {
-                    self.status(ACVMStatus::Solved)
-                } else {
-                    self.status(ACVMStatus::InProgress)
-                }
+        if let (Some(profile), Some(start)) = (&mut self.profile, start) {
+            profile.record_opcode(
+                self.instruction_pointer,
+                opcode,
+                start.elapsed(),
+                brillig_profile,
+            );
+        }
+        if let (Some(tracer), Some(pending)) = (&mut self.tracer, pending) {
+            tracer.record_opcode(
+                self.instruction_pointer,
+                pending,
+                &self.witness_map,
+                brillig_trace,
+            );
+        }
+        self.handle_opcode_resolution(resolution)
+    }
+
+    /// Returns the ACVM-level resource limit which would be exceeded by solving another opcode, if any.
+    fn exceeded_limit(&self) -> Option<ResourceLimitError> {
+        if let Some(max_opcodes) = self.resource_limits.max_opcodes {
+            if self.opcodes_solved >= max_opcodes {
+                return Some(ResourceLimitError::Opcodes(max_opcodes));
+            }
+        }
+        if let Some(deadline) = self.resource_limits.deadline {
+            if Instant::now() >= deadline {
+                return Some(ResourceLimitError::Deadline);
             }
+        }
+        None
+    }
+
+    fn fail_resource_limit(&mut self, limit: ResourceLimitError) -> ACVMStatus {
+        self.fail(OpcodeResolutionError::ResourceLimitExceeded {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(
+                self.instruction_pointer,
+            )),
+            limit,
+        })
+    }
+
+    /// Updates the status of the VM based on the result of solving the opcode at the current instruction pointer.
+    fn handle_opcode_resolution(
+        &mut self,
+        resolution: Result<(), OpcodeResolutionError>,
+    ) -> ACVMStatus {
+        match resolution {
+            Ok(()) => self.increment_instruction_pointer(),
             Err(mut error) => {
-                match &mut error {
-                    // If we have an index out of bounds or an unsatisfied constraint, the opcode label will be unresolved
-                    // because the solvers do not have knowledge of this information.
-                    // We resolve, by setting this to the corresponding opcode that we just attempted to solve.
-                    OpcodeResolutionError::IndexOutOfBounds {
-                        opcode_location: opcode_index,
-                        ..
+                // The solvers do not have knowledge of which opcode they are solving so the error location will be unresolved.
+                // We resolve, by setting this to the corresponding opcode that we just attempted to solve.
+                error.resolve_location(OpcodeLocation::Acir(self.instruction_pointer()));
+
+                if let Some(report) = &mut self.failure_report {
+                    let opcode = &self.opcodes[self.instruction_pointer];
+                    if report.is_caused_by_taint(&error) {
+                        report.record_tainted(opcode, self.instruction_pointer, &self.witness_map);
+                        return self.increment_instruction_pointer();
+                    } else if is_recoverable(&error) {
+                        let opcode_location = match &error {
+                            OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => {
+                                *call_stack
+                                    .last()
+                                    .expect("Brillig error call stacks cannot be empty")
+                            }
+                            _ => OpcodeLocation::Acir(self.instruction_pointer),
+                        };
+                        report.record_failure(opcode, opcode_location, error, &self.witness_map);
+                        return self.increment_instruction_pointer();
                     }
-                    | OpcodeResolutionError::UnsatisfiedConstrain {
-                        opcode_location: opcode_index,
-                    } => {
-                        *opcode_index = ErrorLocation::Resolved(OpcodeLocation::Acir(
-                            self.instruction_pointer(),
-                        ));
-                    }
-                    // All other errors are thrown normally.
-                    _ => (),
-                };
+                }
                 self.fail(error)
             }
         }
     }
+
+    /// Executes the ACVM's circuit until execution halts, solving independent opcodes concurrently.
+    ///
+    /// Whenever the next opcodes are black box function calls or Brillig opcodes whose inputs are already known,
+    /// they are solved together on the global thread pool. Their results are then applied in circuit order so the
+    /// resulting witness map and any error are identical to those produced by [`ACVM::solve`].
+    #[cfg(feature = "parallel")]
+    pub fn solve_parallel(&mut self) -> ACVMStatus
+    where
+        B: Sync,
+    {
+        while self.status == ACVMStatus::InProgress {
+            let start = self.instruction_pointer;
+            let batch_len = parallel::independent_prefix_len(
+                &self.opcodes[start..],
+                &self.witness_map,
+                self.black_box_registry,
+            );
+            if batch_len < 2 || self.profile.is_some() || self.tracer.is_some() {
+                self.solve_opcode();
+                continue;
+            }
+
+            let resolutions = parallel::solve_isolated(
+                self.backend,
+                self.black_box_registry,
+                &self.opcodes[start..start + batch_len],
+                &self.witness_map,
+                self.resource_limits.brillig_limits(),
+                start,
+            );
+            for (local_witness, resolution) in resolutions {
+                if let Some(limit) = self.exceeded_limit() {
+                    self.fail_resource_limit(limit);
+                    break;
+                }
+                for (witness, value) in local_witness {
+                    self.witness_map.insert(witness, value);
+                }
+                if self.handle_opcode_resolution(resolution) != ACVMStatus::InProgress {
+                    break;
+                }
+            }
+        }
+        self.status.clone()
+    }
+
+    /// Moves on to the next opcode, updating the status of the VM if all opcodes have been executed.
+    ///
+    /// If any failures have been recorded then the VM fails with the first of these once execution completes.
+    fn increment_instruction_pointer(&mut self) -> ACVMStatus {
+        self.foreign_call_results.clear();
+        self.opcodes_solved += 1;
+        self.instruction_pointer += 1;
+        if let Some(prepared) = self.prepared {
+            while prepared.satisfied.get(self.instruction_pointer) == Some(&true) {
+                self.instruction_pointer += 1;
+            }
+        }
+        if self.instruction_pointer < self.opcodes.len() {
+            return self.status(ACVMStatus::InProgress);
+        }
+
+        let first_failure = self
+            .failure_report
+            .as_ref()
+            .and_then(|report| report.failures().first())
+            .map(|failure| failure.error.clone());
+        match first_failure {
+            Some(error) => self.fail(error),
+            None => self.status(ACVMStatus::Solved),
+        }
+    }
 }
 
 // Returns the concrete value for a particular witness
@@ -325,9 +866,10 @@ pub fn get_value(
     let expr = ArithmeticSolver::evaluate(expr, initial_witness);
     match expr.to_const() {
         Some(value) => Ok(value),
-        None => Err(OpcodeResolutionError::OpcodeNotSolvable(
-            OpcodeNotSolvable::MissingAssignment(any_witness_from_expression(&expr).unwrap().0),
-        )),
+        None => {
+            Err(OpcodeNotSolvable::MissingAssignment(any_witness_from_expression(&expr).unwrap().0)
+                .into())
+        }
     }
 }
 
@@ -340,22 +882,55 @@ pub fn insert_value(
     value_to_insert: FieldElement,
     initial_witness: &mut WitnessMap,
 ) -> Result<(), OpcodeResolutionError> {
-    let optional_old_value = initial_witness.insert(*witness, value_to_insert);
-
-    let old_value = match optional_old_value {
-        Some(old_value) => old_value,
-        None => return Ok(()),
+    let old_value = match initial_witness.get(witness) {
+        Some(old_value) => *old_value,
+        None => {
+            initial_witness.insert(*witness, value_to_insert);
+            return Ok(());
+        }
     };
 
+    // The existing assignment is left in place so that it can be reported.
     if old_value != value_to_insert {
+        // The insertion is equivalent to the constraint `witness - value_to_insert = 0`.
+        let mut expression = Expression::from(*witness);
+        expression.q_c = -value_to_insert;
         return Err(OpcodeResolutionError::UnsatisfiedConstrain {
             opcode_location: ErrorLocation::Unresolved,
+            info: Some(Box::new(UnsatisfiedConstraintInfo::Expression {
+                expression,
+                witness_values: vec![(*witness, old_value)],
+                residual: old_value - value_to_insert,
+            })),
         });
     }
 
     Ok(())
 }
 
+/// Returns each distinct witness which appears in `expr` along with its assigned value.
+///
+/// Witnesses which do not have an assignment in `witness_map` are omitted.
+pub(crate) fn assigned_witness_values(
+    expr: &Expression,
+    witness_map: &WitnessMap,
+) -> Vec<(Witness, FieldElement)> {
+    let witnesses: BTreeSet<Witness> = expression_witnesses(expr).collect();
+    witnesses
+        .into_iter()
+        .filter_map(|witness| witness_map.get(&witness).map(|value| (witness, *value)))
+        .collect()
+}
+
+/// Returns an iterator over all witnesses which appear in `expr`.
+/// Witnesses which appear in multiple terms will be returned multiple times.
+pub(crate) fn expression_witnesses(expr: &Expression) -> impl Iterator<Item = Witness> + '_ {
+    expr.mul_terms
+        .iter()
+        .flat_map(|(_, lhs, rhs)| [*lhs, *rhs])
+        .chain(expr.linear_combinations.iter().map(|(_, witness)| *witness))
+}
+
 // Returns one witness belonging to an expression, in no relevant order
 // Returns None if the expression is const
 // The function is used during partial witness generation to report unsolved witness
@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use acir::{
+    brillig::Opcode as BrilligOpcode,
+    circuit::{brillig::BrilligInputs, opcodes::BlackBoxFuncCall, Opcode},
+    native_types::{Witness, WitnessMap},
+};
+use blackbox_solver::{BlackBoxCost, BlackBoxFunctionSolver, BlackBoxRegistry};
+use brillig_vm::VMLimits;
+use rayon::prelude::*;
+
+use super::{
+    blackbox,
+    brillig::{BrilligOptions, BrilligSolver},
+    expression_witnesses,
+    failures::opcode_witnesses,
+    OpcodeResolutionError,
+};
+
+/// The result of solving a single opcode in isolation.
+///
+/// Contains the values of each witness read or written by the opcode along with the outcome of solving it.
+pub(super) type IsolatedResolution = (WitnessMap, Result<(), OpcodeResolutionError>);
+
+/// Returns the number of opcodes at the start of `opcodes` which may be solved concurrently.
+///
+/// Each opcode in the batch must be expensive enough to be worth solving on another thread, must have all of its
+/// inputs assigned in `witness_map` and must not write to a witness which is written by an earlier opcode in the batch.
+pub(super) fn independent_prefix_len(
+    opcodes: &[Opcode],
+    witness_map: &WitnessMap,
+    registry: &BlackBoxRegistry,
+) -> usize {
+    let mut written = BTreeSet::new();
+    for (index, opcode) in opcodes.iter().enumerate() {
+        let Some((inputs, outputs)) = parallelizable_witnesses(opcode, registry) else {
+            return index;
+        };
+        let inputs_known = inputs.iter().all(|witness| witness_map.contains_key(witness));
+        if !inputs_known || outputs.iter().any(|witness| written.contains(witness)) {
+            return index;
+        }
+        written.extend(outputs);
+    }
+    opcodes.len()
+}
+
+/// Solves each of `opcodes` concurrently, each against its own copy of the witnesses it touches.
+///
+/// The results are returned in the same order as `opcodes`, regardless of the order in which they are solved.
+pub(super) fn solve_isolated<B: BlackBoxFunctionSolver + Sync>(
+    backend: &B,
+    registry: &BlackBoxRegistry,
+    opcodes: &[Opcode],
+    witness_map: &WitnessMap,
+    limits: VMLimits,
+    first_acir_index: usize,
+) -> Vec<IsolatedResolution> {
+    opcodes
+        .par_iter()
+        .enumerate()
+        .map(|(index, opcode)| {
+            let mut local_witness: WitnessMap = opcode_witnesses(opcode)
+                .into_iter()
+                .filter_map(|witness| witness_map.get(&witness).map(|value| (witness, *value)))
+                .collect::<BTreeMap<_, _>>()
+                .into();
+
+            let resolution = match opcode {
+                Opcode::BlackBoxFuncCall(bb_func) => {
+                    blackbox::solve(backend, registry, &mut local_witness, bb_func)
+                }
+                Opcode::Brillig(brillig) => BrilligSolver::solve(
+                    &mut local_witness,
+                    brillig,
+                    &[],
+                    backend,
+                    BrilligOptions {
+                        limits,
+                        black_box_registry: Some(registry),
+                        ..BrilligOptions::default()
+                    },
+                    first_acir_index + index,
+                )
+                .map(|foreign_call| {
+                    assert!(
+                        foreign_call.is_none(),
+                        "Brillig opcodes with foreign calls are not solved concurrently"
+                    );
+                }),
+                _ => unreachable!(
+                    "only black box functions and Brillig opcodes are solved concurrently"
+                ),
+            };
+            (local_witness, resolution)
+        })
+        .collect()
+}
+
+/// Returns the input and output witnesses of `opcode` if it may be solved concurrently with other opcodes.
+fn parallelizable_witnesses(
+    opcode: &Opcode,
+    registry: &BlackBoxRegistry,
+) -> Option<(BTreeSet<Witness>, BTreeSet<Witness>)> {
+    match opcode {
+        Opcode::BlackBoxFuncCall(bb_func) if is_expensive(bb_func, registry) => {
+            let inputs = bb_func.get_inputs_vec().iter().map(|input| input.witness).collect();
+            let outputs = bb_func.get_outputs_vec().into_iter().collect();
+            Some((inputs, outputs))
+        }
+        // Foreign calls must be resolved by the caller so cannot be handled on another thread.
+        Opcode::Brillig(brillig)
+            if !brillig
+                .bytecode
+                .iter()
+                .any(|opcode| matches!(opcode, BrilligOpcode::ForeignCall { .. })) =>
+        {
+            let mut inputs = BTreeSet::new();
+            for input in &brillig.inputs {
+                match input {
+                    BrilligInputs::Single(expr) => inputs.extend(expression_witnesses(expr)),
+                    BrilligInputs::Array(exprs) => {
+                        inputs.extend(exprs.iter().flat_map(expression_witnesses))
+                    }
+                }
+            }
+            inputs.extend(brillig.predicate.iter().flat_map(expression_witnesses));
+
+            let outputs = opcode_witnesses(opcode).difference(&inputs).copied().collect();
+            Some((inputs, outputs))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether solving `bb_func` is expensive enough to be worth offloading to another thread.
+fn is_expensive(bb_func: &BlackBoxFuncCall, registry: &BlackBoxRegistry) -> bool {
+    registry
+        .get(bb_func.name())
+        .map_or(true, |definition| definition.cost == BlackBoxCost::Expensive)
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use acir::{
+    circuit::{Circuit, Opcode, OpcodeLocation},
+    native_types::WitnessMap,
+};
+use blackbox_solver::BlackBoxRegistry;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{
+    blackbox::PreparedBlackBoxCall, ACVMStatus, ErrorLocation, ForeignCallExecutor,
+    OpcodeResolutionError, ACVM,
+};
+use crate::BlackBoxFunctionSolver;
+
+/// The reason that executing an [`ExecutionPlan`] on a particular witness failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionFailure {
+    pub error: Box<OpcodeResolutionError>,
+    /// The assertion message attached to the failing opcode in the circuit, if one exists.
+    pub assert_message: Option<String>,
+}
+
+/// Work which is done once for each opcode of a circuit rather than on every execution.
+pub(super) struct PreparedOpcodes<'a> {
+    /// Marks arithmetic opcodes which contain no witnesses and evaluate to zero, these never need to be solved.
+    pub(super) satisfied: Vec<bool>,
+    /// The definition and operands of each black box function call, indexed by opcode.
+    pub(super) black_box_calls: Vec<Option<PreparedBlackBoxCall<'a>>>,
+}
+
+impl<'a> PreparedOpcodes<'a> {
+    fn new(opcodes: &[Opcode], registry: &'a BlackBoxRegistry) -> Self {
+        let satisfied = opcodes
+            .iter()
+            .map(|opcode| match opcode {
+                Opcode::Arithmetic(expr) => expr.is_const() && expr.q_c.is_zero(),
+                _ => false,
+            })
+            .collect();
+        let black_box_calls = opcodes
+            .iter()
+            .map(|opcode| match opcode {
+                Opcode::BlackBoxFuncCall(bb_func) => PreparedBlackBoxCall::new(registry, bb_func),
+                _ => None,
+            })
+            .collect();
+        PreparedOpcodes { satisfied, black_box_calls }
+    }
+}
+
+/// A circuit which has been prepared to be executed against many initial witnesses.
+///
+/// Work which does not depend upon the witness, such as finding opcodes which never need to be solved and looking up
+/// the definition and operands of each black box function call, is performed once when the plan is created.
+/// Each execution borrows the circuit's opcodes rather than cloning them, and all executions share the same
+/// [`BlackBoxFunctionSolver`].
+///
+/// A plan is `Sync` whenever its solver is, allowing many witnesses to be generated concurrently from a single plan.
+pub struct ExecutionPlan<'a, B: BlackBoxFunctionSolver> {
+    backend: &'a B,
+    registry: &'a BlackBoxRegistry,
+    opcodes: &'a [Opcode],
+    prepared: PreparedOpcodes<'a>,
+    assert_messages: HashMap<OpcodeLocation, &'a str>,
+}
+
+impl<'a, B: BlackBoxFunctionSolver> ExecutionPlan<'a, B> {
+    pub fn new(backend: &'a B, circuit: &'a Circuit) -> Self {
+        Self::with_black_box_registry(backend, circuit, BlackBoxRegistry::standard())
+    }
+
+    /// Creates a plan which solves black box functions using the definitions in `registry`.
+    pub fn with_black_box_registry(
+        backend: &'a B,
+        circuit: &'a Circuit,
+        registry: &'a BlackBoxRegistry,
+    ) -> Self {
+        let assert_messages = circuit
+            .assert_messages
+            .iter()
+            .map(|(location, message)| (*location, message.as_str()))
+            .collect();
+
+        ExecutionPlan {
+            backend,
+            registry,
+            opcodes: &circuit.opcodes,
+            prepared: PreparedOpcodes::new(&circuit.opcodes, registry),
+            assert_messages,
+        }
+    }
+
+    /// Creates an [`ACVM`] which will execute the circuit on `initial_witness`.
+    ///
+    /// This allows the caller to drive execution directly, for instance to resolve foreign calls asynchronously.
+    pub fn acvm(&self, initial_witness: WitnessMap) -> ACVM<'_, B> {
+        let mut acvm = ACVM::new_borrowed(self.backend, self.opcodes, initial_witness);
+        acvm.set_black_box_registry(self.registry);
+        acvm.use_prepared_opcodes(&self.prepared);
+        acvm
+    }
+
+    /// Executes the circuit on `initial_witness`, resolving any foreign calls using `executor`.
+    pub fn execute(
+        &self,
+        initial_witness: WitnessMap,
+        executor: &mut impl ForeignCallExecutor,
+    ) -> Result<WitnessMap, ExecutionFailure> {
+        let mut acvm = self.acvm(initial_witness);
+        match acvm.solve_with_executor(executor) {
+            ACVMStatus::Solved => Ok(acvm.finalize()),
+            ACVMStatus::Failure(error) => {
+                let assert_message = self.assert_message(&error);
+                Err(ExecutionFailure { error: Box::new(error), assert_message })
+            }
+            ACVMStatus::InProgress | ACVMStatus::RequiresForeignCall(_) => {
+                unreachable!(
+                    "ACVM should run to completion when resolving foreign calls with an executor"
+                )
+            }
+        }
+    }
+
+    /// Executes the circuit on each of `initial_witnesses` in turn, returning the outcome of each execution in order.
+    pub fn execute_batch(
+        &self,
+        initial_witnesses: impl IntoIterator<Item = WitnessMap>,
+        executor: &mut impl ForeignCallExecutor,
+    ) -> Vec<Result<WitnessMap, ExecutionFailure>> {
+        initial_witnesses
+            .into_iter()
+            .map(|initial_witness| self.execute(initial_witness, executor))
+            .collect()
+    }
+
+    /// Executes the circuit on each of `initial_witnesses` concurrently on the global thread pool, returning the
+    /// outcome of each execution in order.
+    ///
+    /// Each execution resolves foreign calls using its own executor created by `make_executor`.
+    #[cfg(feature = "parallel")]
+    pub fn execute_batch_parallel<E: ForeignCallExecutor>(
+        &self,
+        initial_witnesses: Vec<WitnessMap>,
+        make_executor: impl Fn() -> E + Sync,
+    ) -> Vec<Result<WitnessMap, ExecutionFailure>>
+    where
+        B: Sync,
+    {
+        initial_witnesses
+            .into_par_iter()
+            .map(|initial_witness| self.execute(initial_witness, &mut make_executor()))
+            .collect()
+    }
+
+    /// Returns the assertion message for the opcode which caused `error`.
+    fn assert_message(&self, error: &OpcodeResolutionError) -> Option<String> {
+        let location = match error {
+            OpcodeResolutionError::BrilligFunctionFailed { call_stack, .. } => {
+                *call_stack.last()?
+            }
+            _ => match error.opcode_location() {
+                ErrorLocation::Resolved(location) => location,
+                ErrorLocation::Unresolved => return None,
+            },
+        };
+        self.assert_messages.get(&location).map(|message| message.to_string())
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use acir::circuit::{Opcode, OpcodeLocation};
+use brillig_vm::BrilligProfile;
+use serde::{Serialize, Serializer};
+
+use crate::compiler::AcirTransformationMap;
+
+/// The number of times that an opcode was solved along with the total time spent solving it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OpcodeStats {
+    pub count: u64,
+    pub time_ns: u64,
+}
+
+impl OpcodeStats {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.time_ns =
+            self.time_ns.saturating_add(u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX));
+    }
+
+    fn merge(&mut self, other: OpcodeStats) {
+        self.count += other.count;
+        self.time_ns = self.time_ns.saturating_add(other.time_ns);
+    }
+}
+
+/// The profile of a single ACIR opcode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LocationProfile {
+    /// The kind of [`Opcode`], e.g. `arithmetic` or `brillig`.
+    pub kind: &'static str,
+    /// The [name][Opcode::name] of the opcode, which identifies the black box function or directive being called.
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: OpcodeStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assert_message: Option<String>,
+    /// The instructions executed by a Brillig opcode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brillig: Option<BrilligProfile>,
+    /// Assertion messages attached to opcodes within the Brillig bytecode, keyed by bytecode location.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub brillig_assert_messages: BTreeMap<usize, String>,
+}
+
+/// A record of where time was spent while executing a circuit, see [`ACVM::enable_profiling`][super::ACVM::enable_profiling].
+///
+/// Locations refer to opcodes in the circuit which was executed. If the circuit was produced by
+/// [`compile`][crate::compiler::compile] then they can be mapped back to the original circuit with
+/// [`ExecutionProfile::map_locations`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ExecutionProfile {
+    /// Statistics for each kind of [`Opcode`].
+    pub opcodes: BTreeMap<&'static str, OpcodeStats>,
+    /// Statistics for each black box function.
+    pub black_box_functions: BTreeMap<&'static str, OpcodeStats>,
+    /// Statistics for each ACIR opcode which was solved.
+    #[serde(serialize_with = "serialize_locations")]
+    pub locations: BTreeMap<OpcodeLocation, LocationProfile>,
+}
+
+impl ExecutionProfile {
+    /// Records that the opcode at `acir_index` was solved in `elapsed`, along with the instructions it executed if it is a Brillig opcode.
+    pub(super) fn record_opcode(
+        &mut self,
+        acir_index: usize,
+        opcode: &Opcode,
+        elapsed: Duration,
+        brillig: Option<BrilligProfile>,
+    ) {
+        let kind = opcode_kind(opcode);
+        self.opcodes.entry(kind).or_default().record(elapsed);
+        if let Opcode::BlackBoxFuncCall(bb_func) = opcode {
+            let func_name = bb_func.get_black_box_func().name();
+            self.black_box_functions.entry(func_name).or_default().record(elapsed);
+        }
+
+        let profile = self.locations.entry(OpcodeLocation::Acir(acir_index)).or_insert_with(|| {
+            LocationProfile {
+                kind,
+                name: opcode.name().to_string(),
+                stats: OpcodeStats::default(),
+                assert_message: None,
+                brillig: None,
+                brillig_assert_messages: BTreeMap::new(),
+            }
+        });
+        profile.stats.record(elapsed);
+        if let Some(brillig) = brillig {
+            profile.brillig.get_or_insert_with(BrilligProfile::default).merge(&brillig);
+        }
+    }
+
+    /// Attaches each of `assert_messages` to the profile of the opcode at its location.
+    pub fn with_assert_messages(mut self, assert_messages: &[(OpcodeLocation, String)]) -> Self {
+        for (location, message) in assert_messages {
+            match *location {
+                OpcodeLocation::Acir(_) => {
+                    if let Some(profile) = self.locations.get_mut(location) {
+                        profile.assert_message = Some(message.clone());
+                    }
+                }
+                OpcodeLocation::Brillig { acir_index, brillig_index } => {
+                    if let Some(profile) = self.locations.get_mut(&OpcodeLocation::Acir(acir_index))
+                    {
+                        profile.brillig_assert_messages.insert(brillig_index, message.clone());
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Maps each location from the transformed circuit back to the location in the original circuit from which it was produced.
+    ///
+    /// Opcodes which were produced from the same original opcode are merged into a single entry.
+    pub fn map_locations(self, map: &AcirTransformationMap) -> Self {
+        let mut locations: BTreeMap<OpcodeLocation, LocationProfile> = BTreeMap::new();
+        for (location, profile) in self.locations {
+            let old_location = map.old_location(location);
+            match locations.get_mut(&old_location) {
+                Some(existing) => {
+                    existing.stats.merge(profile.stats);
+                    if let Some(brillig) = &profile.brillig {
+                        existing.brillig.get_or_insert_with(BrilligProfile::default).merge(brillig);
+                    }
+                    existing.assert_message =
+                        existing.assert_message.take().or(profile.assert_message);
+                    existing.brillig_assert_messages.extend(profile.brillig_assert_messages);
+                }
+                None => {
+                    locations.insert(old_location, profile);
+                }
+            }
+        }
+        ExecutionProfile { locations, ..self }
+    }
+
+    /// Serializes the profile as JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Returns the time spent solving each opcode, in nanoseconds, in the collapsed stack format used by flamegraph tools.
+    ///
+    /// Each stack consists of the kind of opcode, the black box function or directive being called and the opcode's location.
+    pub fn collapsed_stacks(&self) -> String {
+        let mut output = String::new();
+        for (location, profile) in &self.locations {
+            let mut stack = profile.kind.to_string();
+            if profile.name != profile.kind {
+                write!(stack, ";{}", frame_name(&profile.name)).unwrap();
+            }
+            write!(stack, ";{}", location_frame(*location, profile.assert_message.as_deref()))
+                .unwrap();
+            writeln!(output, "{stack} {}", profile.stats.time_ns).unwrap();
+        }
+        output
+    }
+
+    /// Returns the number of instructions executed by each Brillig opcode in the collapsed stack format used by flamegraph tools.
+    ///
+    /// Each stack consists of the location of the Brillig opcode followed by the functions active within the Brillig VM.
+    pub fn brillig_collapsed_stacks(&self) -> String {
+        let mut output = String::new();
+        for (location, profile) in &self.locations {
+            let Some(brillig) = &profile.brillig else {
+                continue;
+            };
+            let root = location_frame(*location, profile.assert_message.as_deref());
+            for (frames, count) in &brillig.stacks {
+                let mut stack = root.clone();
+                for function in frames {
+                    write!(stack, ";brillig@{function}").unwrap();
+                }
+                writeln!(output, "{stack} {count}").unwrap();
+            }
+        }
+        output
+    }
+}
+
+fn opcode_kind(opcode: &Opcode) -> &'static str {
+    match opcode {
+        Opcode::Arithmetic(_) => "arithmetic",
+        Opcode::BlackBoxFuncCall(_) => "black_box_func_call",
+        Opcode::Directive(_) => "directive",
+        Opcode::Brillig(_) => "brillig",
+        Opcode::MemoryOp { .. } => "memory_op",
+        Opcode::MemoryInit { .. } => "memory_init",
+    }
+}
+
+fn location_frame(location: OpcodeLocation, assert_message: Option<&str>) -> String {
+    match assert_message {
+        Some(message) => frame_name(&format!("opcode {location} ({message})")),
+        None => format!("opcode {location}"),
+    }
+}
+
+/// Removes characters from `name` which are not permitted within a frame of a collapsed stack.
+fn frame_name(name: &str) -> String {
+    name.replace(';', ",").replace('\n', " ")
+}
+
+/// Serializes locations as a map keyed by the string form of each location, as JSON only permits string keys.
+fn serialize_locations<S: Serializer>(
+    locations: &BTreeMap<OpcodeLocation, LocationProfile>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer
+        .collect_map(locations.iter().map(|(location, profile)| (location.to_string(), profile)))
+}
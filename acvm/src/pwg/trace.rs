@@ -0,0 +1,265 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+use acir::{
+    circuit::Opcode,
+    native_types::{Witness, WitnessMap},
+    FieldElement,
+};
+use brillig_vm::BrilligTrace;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::failures::opcode_witnesses;
+
+/// The version of the trace format written by [`ACVM::trace_execution`][super::ACVM::trace_execution].
+///
+/// This must be incremented whenever the serialized layout of a [`TraceRecord`] changes.
+pub const TRACE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum TraceError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize binary trace: {0}")]
+    Binary(#[from] bincode::Error),
+    #[error("Failed to (de)serialize JSON trace: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unsupported trace version {found}, expected version {TRACE_FORMAT_VERSION}")]
+    UnsupportedVersion { found: u32 },
+}
+
+/// The encoding used for an execution trace.
+///
+/// Both formats consist of a version header followed by a sequence of [`TraceRecord`]s which can be read
+/// one at a time using a [`TraceReader`], so traces never need to be held in memory in full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Each record is encoded with bincode and prefixed with its length as a little-endian `u32`.
+    #[default]
+    Binary,
+    /// Each record is encoded as a JSON object on its own line.
+    Json,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TraceOptions {
+    pub format: TraceFormat,
+    /// Whether to include the [registers and memory][BrilligTrace] modified by each Brillig opcode.
+    pub brillig: bool,
+}
+
+/// An entry in an execution trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceRecord {
+    /// The witnesses which were assigned before execution began.
+    InitialWitness(Vec<(Witness, FieldElement)>),
+    Opcode(OpcodeTrace),
+}
+
+/// The witnesses read and assigned by a single opcode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcodeTrace {
+    pub acir_index: usize,
+    /// The witnesses assigned by this opcode along with their values.
+    pub assignments: Vec<(Witness, FieldElement)>,
+    /// The witnesses read by this opcode along with the index of the opcode which first assigned each of them.
+    ///
+    /// Witnesses which were part of the initial witness have no defining opcode.
+    pub dependencies: Vec<(Witness, Option<usize>)>,
+    /// The registers and memory modified by a Brillig opcode, if requested.
+    pub brillig: Option<BrilligTrace>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonHeader {
+    version: u32,
+}
+
+/// The witnesses of an opcode which were and were not assigned before it was solved.
+pub(super) struct PendingOpcode {
+    inputs: Vec<Witness>,
+    unassigned: Vec<Witness>,
+}
+
+/// Writes a [`TraceRecord`] for each opcode solved by the ACVM.
+pub(super) struct TraceRecorder<'a> {
+    writer: Box<dyn Write + Send + 'a>,
+    options: TraceOptions,
+    /// The index of the opcode which assigned each witness.
+    definitions: HashMap<Witness, usize>,
+    /// The first error encountered while writing the trace, after which no further records are written.
+    error: Option<TraceError>,
+}
+
+impl<'a> TraceRecorder<'a> {
+    /// Creates a recorder which writes to `writer`, starting with the header and the `initial_witness`.
+    pub(super) fn new(
+        writer: impl Write + Send + 'a,
+        options: TraceOptions,
+        initial_witness: &WitnessMap,
+    ) -> Self {
+        let mut recorder = TraceRecorder {
+            writer: Box::new(writer),
+            options,
+            definitions: HashMap::new(),
+            error: None,
+        };
+        let header = match options.format {
+            TraceFormat::Binary => recorder
+                .writer
+                .write_all(&TRACE_FORMAT_VERSION.to_le_bytes())
+                .map_err(TraceError::from),
+            TraceFormat::Json => {
+                write_json_line(&mut recorder.writer, &JsonHeader { version: TRACE_FORMAT_VERSION })
+            }
+        };
+        recorder.error = header.err();
+
+        let initial_witness =
+            initial_witness.clone().into_iter().collect::<Vec<(Witness, FieldElement)>>();
+        recorder.write(&TraceRecord::InitialWitness(initial_witness));
+        recorder
+    }
+
+    pub(super) fn traces_brillig(&self) -> bool {
+        self.options.brillig
+    }
+
+    /// Captures which of the witnesses of `opcode` are already assigned before it is solved.
+    pub(super) fn begin_opcode(&self, opcode: &Opcode, witness_map: &WitnessMap) -> PendingOpcode {
+        let (inputs, unassigned) = opcode_witnesses(opcode)
+            .into_iter()
+            .partition(|witness| witness_map.contains_key(witness));
+        PendingOpcode { inputs, unassigned }
+    }
+
+    /// Records the witnesses assigned by the opcode at `acir_index` once it has been solved.
+    pub(super) fn record_opcode(
+        &mut self,
+        acir_index: usize,
+        pending: PendingOpcode,
+        witness_map: &WitnessMap,
+        brillig: Option<BrilligTrace>,
+    ) {
+        let assignments: Vec<_> = pending
+            .unassigned
+            .into_iter()
+            .filter_map(|witness| witness_map.get(&witness).map(|value| (witness, *value)))
+            .collect();
+        let dependencies = pending
+            .inputs
+            .into_iter()
+            .map(|witness| (witness, self.definitions.get(&witness).copied()))
+            .collect();
+        self.definitions.extend(assignments.iter().map(|(witness, _)| (*witness, acir_index)));
+
+        self.write(&TraceRecord::Opcode(OpcodeTrace {
+            acir_index,
+            assignments,
+            dependencies,
+            brillig,
+        }));
+    }
+
+    /// Flushes the trace, returning the first error encountered while writing it.
+    pub(super) fn finish(mut self) -> Result<(), TraceError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Ok(self.writer.flush()?)
+    }
+
+    fn write(&mut self, record: &TraceRecord) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = match self.options.format {
+            TraceFormat::Binary => write_binary_record(&mut self.writer, record),
+            TraceFormat::Json => write_json_line(&mut self.writer, record),
+        };
+        self.error = result.err();
+    }
+}
+
+fn write_binary_record(writer: &mut impl Write, record: &TraceRecord) -> Result<(), TraceError> {
+    let bytes = bincode::serialize(record)?;
+    let length = u32::try_from(bytes.len()).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("trace record of {} bytes exceeds the 4GiB limit", bytes.len()),
+        )
+    })?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+fn write_json_line(writer: &mut impl Write, value: &impl Serialize) -> Result<(), TraceError> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads the [`TraceRecord`]s of an execution trace one at a time.
+pub struct TraceReader<R: BufRead> {
+    reader: R,
+    format: TraceFormat,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> TraceReader<R> {
+    /// Reads the header of a trace written in `format`.
+    ///
+    /// Returns an error if the trace was written using a different [trace version][TRACE_FORMAT_VERSION].
+    pub fn new(mut reader: R, format: TraceFormat) -> Result<Self, TraceError> {
+        let version = match format {
+            TraceFormat::Binary => {
+                let mut version = [0u8; 4];
+                reader.read_exact(&mut version)?;
+                u32::from_le_bytes(version)
+            }
+            TraceFormat::Json => {
+                let mut line = String::new();
+                reader.read_line(&mut line)?;
+                serde_json::from_str::<JsonHeader>(&line)?.version
+            }
+        };
+        if version != TRACE_FORMAT_VERSION {
+            return Err(TraceError::UnsupportedVersion { found: version });
+        }
+        Ok(TraceReader { reader, format, buffer: Vec::new() })
+    }
+
+    fn read_record(&mut self) -> Result<Option<TraceRecord>, TraceError> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        // Field elements can only be deserialized from borrowed data so each record is read in full.
+        self.buffer.clear();
+        let record = match self.format {
+            TraceFormat::Binary => {
+                let mut length = [0u8; 4];
+                self.reader.read_exact(&mut length)?;
+                self.buffer.resize(u32::from_le_bytes(length) as usize, 0);
+                self.reader.read_exact(&mut self.buffer)?;
+                bincode::deserialize(&self.buffer)?
+            }
+            TraceFormat::Json => {
+                self.reader.read_until(b'\n', &mut self.buffer)?;
+                serde_json::from_slice(&self.buffer)?
+            }
+        };
+        Ok(Some(record))
+    }
+}
+
+impl<R: BufRead> Iterator for TraceReader<R> {
+    type Item = Result<TraceRecord, TraceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
@@ -1,21 +1,36 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
 
 use acir::{
     brillig::{BinaryFieldOp, Opcode as BrilligOpcode, RegisterIndex, RegisterOrMemory, Value},
     circuit::{
         brillig::{Brillig, BrilligInputs, BrilligOutputs},
-        opcodes::{BlockId, MemOp},
-        Opcode, OpcodeLocation,
+        opcodes::{BlackBoxFuncCall, BlockId, FunctionInput, MemOp},
+        Circuit, Opcode, OpcodeLocation,
     },
     native_types::{Expression, Witness, WitnessMap},
-    FieldElement,
+    BlackBoxFunc, FieldElement,
 };
 
 use acvm::{
-    pwg::{ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeResolutionError, ACVM},
-    BlackBoxFunctionSolver,
+    compiler::{black_box_registry, compile_with_registry, CompileError},
+    pwg::{
+        ACVMStatus, AsyncForeignCallExecutor, DispatchExecutor, ErrorLocation, ExecutionPlan,
+        ExecutorChain, ForeignCallError, ForeignCallFuture, ForeignCallWaitInfo, MockCall,
+        MockOracles, OpcodeFailure, OpcodeResolutionError, OpcodeTrace, PrintExecutor,
+        ResourceLimitError, ResourceLimits, TraceFormat, TraceOptions, TraceReader, TraceRecord,
+        UnsatisfiedConstraintInfo, ACVM,
+    },
+    BlackBoxFunctionSolver, Language,
+};
+use blackbox_solver::{
+    mock_key_hash, BlackBoxDefinition, BlackBoxRegistry, BlackBoxResolutionError,
+    MockRecursiveVerifier,
 };
-use blackbox_solver::BlackBoxResolutionError;
 
 pub(crate) struct StubbedBackend;
 
@@ -42,6 +57,23 @@ impl BlackBoxFunctionSolver for StubbedBackend {
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
         panic!("Path not trodden by this test")
     }
+    fn embedded_curve_add(
+        &self,
+        _input1_x: &FieldElement,
+        _input1_y: &FieldElement,
+        _input2_x: &FieldElement,
+        _input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        panic!("Path not trodden by this test")
+    }
+    fn variable_base_scalar_mul(
+        &self,
+        _point_x: &FieldElement,
+        _point_y: &FieldElement,
+        _scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        panic!("Path not trodden by this test")
+    }
 }
 
 // Reenable these test cases once we move the brillig implementation of inversion down into the acvm stdlib.
@@ -424,6 +456,271 @@ fn oracle_dependent_execution() {
     acvm.finalize();
 }
 
+#[test]
+fn solve_with_executor_resolves_foreign_calls() {
+    let w_x = Witness(1);
+    let w_x_inverse = Witness(2);
+
+    // Prints `x` before requesting its inverse from an oracle.
+    let brillig_opcode = Opcode::Brillig(Brillig {
+        inputs: vec![BrilligInputs::Single(w_x.into())],
+        outputs: vec![BrilligOutputs::Simple(w_x_inverse)],
+        bytecode: vec![
+            BrilligOpcode::ForeignCall {
+                function: "print".into(),
+                destinations: vec![],
+                inputs: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+            },
+            BrilligOpcode::ForeignCall {
+                function: "invert".into(),
+                destinations: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+                inputs: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+            },
+        ],
+        predicate: None,
+        foreign_call_results: vec![],
+    });
+    // x * x_inverse == 1
+    let check_inverse = Opcode::Arithmetic(Expression {
+        mul_terms: vec![(FieldElement::one(), w_x, w_x_inverse)],
+        linear_combinations: vec![],
+        q_c: -FieldElement::one(),
+    });
+    let opcodes = vec![brillig_opcode, check_inverse];
+    let witness_assignments = BTreeMap::from([(w_x, FieldElement::from(5u128))]).into();
+
+    let mut printer = PrintExecutor::new("print", Vec::new());
+    let mut executor = ExecutorChain::new()
+        .with(&mut printer)
+        .with(DispatchExecutor::new().register("invert", |inputs| {
+            Ok(Value::from(inputs[0][0].to_field().inverse()).into())
+        }));
+
+    let mut acvm = ACVM::new(&StubbedBackend, opcodes.clone(), witness_assignments);
+    assert_eq!(acvm.solve_with_executor(&mut executor), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+    assert_eq!(witness_map[&w_x_inverse], FieldElement::from(5u128).inverse());
+
+    drop(executor);
+    let printed = String::from_utf8(printer.into_writer()).unwrap();
+    assert_eq!(printed, format!("0x{}\n", FieldElement::from(5u128).to_hex()));
+
+    // A foreign call which no executor handles results in a failure.
+    let witness_assignments = BTreeMap::from([(w_x, FieldElement::from(5u128))]).into();
+    let mut acvm = ACVM::new(&StubbedBackend, opcodes, witness_assignments);
+    let mut printer = PrintExecutor::new("print", std::io::sink());
+    assert_eq!(
+        acvm.solve_with_executor(&mut printer),
+        ACVMStatus::Failure(OpcodeResolutionError::ForeignCallFailed {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(0)),
+            source: ForeignCallError::Unhandled("invert".to_string()),
+        })
+    );
+}
+
+/// Polls `future` to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// Resolves calls to `invert`, suspending once before returning each result.
+struct AsyncInverter;
+
+impl AsyncForeignCallExecutor for AsyncInverter {
+    fn execute<'a>(&'a mut self, foreign_call: &'a ForeignCallWaitInfo) -> ForeignCallFuture<'a> {
+        Box::pin(async move {
+            let mut suspended = false;
+            std::future::poll_fn(|context| {
+                if suspended {
+                    Poll::Ready(())
+                } else {
+                    suspended = true;
+                    context.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await;
+
+            if foreign_call.function != "invert" {
+                return Ok(None);
+            }
+            Ok(Some(Value::from(foreign_call.inputs[0][0].to_field().inverse()).into()))
+        })
+    }
+}
+
+#[test]
+fn solve_async_resolves_foreign_calls() {
+    let w_x = Witness(1);
+    let w_x_inverse = Witness(2);
+
+    let brillig_opcode = Opcode::Brillig(Brillig {
+        inputs: vec![BrilligInputs::Single(w_x.into())],
+        outputs: vec![BrilligOutputs::Simple(w_x_inverse)],
+        bytecode: vec![BrilligOpcode::ForeignCall {
+            function: "invert".into(),
+            destinations: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+            inputs: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+        }],
+        predicate: None,
+        foreign_call_results: vec![],
+    });
+    let witness_assignments = BTreeMap::from([(w_x, FieldElement::from(5u128))]).into();
+
+    let mut acvm = ACVM::new(&StubbedBackend, vec![brillig_opcode], witness_assignments);
+    let mut executor = AsyncInverter;
+    let solving = acvm.solve_async(&mut executor);
+    // The future may be spawned onto multi-threaded runtimes.
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fn assert_send(_: &impl Send) {}
+        assert_send(&solving);
+    }
+    assert_eq!(block_on(solving), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+    assert_eq!(witness_map[&w_x_inverse], FieldElement::from(5u128).inverse());
+}
+
+#[test]
+fn execution_plan_runs_batch_of_inputs() {
+    let w_x = Witness(1);
+    let w_x_inverse = Witness(2);
+
+    // Requests the inverse of `x` from an oracle.
+    let brillig_opcode = Opcode::Brillig(Brillig {
+        inputs: vec![BrilligInputs::Single(w_x.into())],
+        outputs: vec![BrilligOutputs::Simple(w_x_inverse)],
+        bytecode: vec![BrilligOpcode::ForeignCall {
+            function: "invert".into(),
+            destinations: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+            inputs: vec![RegisterOrMemory::RegisterIndex(RegisterIndex::from(0))],
+        }],
+        predicate: None,
+        foreign_call_results: vec![],
+    });
+    let circuit = Circuit {
+        current_witness_index: 2,
+        opcodes: vec![
+            // A constant opcode which is always satisfied.
+            Opcode::Arithmetic(Expression::default()),
+            brillig_opcode,
+            // x * x_inverse == 1
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![(FieldElement::one(), w_x, w_x_inverse)],
+                linear_combinations: vec![],
+                q_c: -FieldElement::one(),
+            }),
+        ],
+        assert_messages: vec![(OpcodeLocation::Acir(2), "x must be invertible".to_string())],
+        ..Circuit::default()
+    };
+
+    let plan = ExecutionPlan::new(&StubbedBackend, &circuit);
+    let mut oracles = MockOracles::new().mock(
+        MockCall::new("invert")
+            .returns_with(|inputs| Value::from(inputs[0][0].to_field().inverse()).into())
+            .times(3),
+    );
+    let initial_witnesses =
+        [2u128, 0, 5].map(|x| WitnessMap::from(BTreeMap::from([(w_x, FieldElement::from(x))])));
+
+    let results = plan.execute_batch(initial_witnesses, &mut oracles);
+    assert_eq!(oracles.verify(), Ok(()));
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap()[&w_x_inverse], FieldElement::from(2u128).inverse());
+    assert_eq!(
+        results[1].as_ref().unwrap_err().assert_message.as_deref(),
+        Some("x must be invertible")
+    );
+    assert_eq!(results[2].as_ref().unwrap()[&w_x_inverse], FieldElement::from(5u128).inverse());
+}
+
+#[test]
+fn execution_plan_is_shared_between_threads() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let w_x = Witness(1);
+    let w_y = Witness(2);
+    let circuit = Circuit {
+        current_witness_index: 2,
+        // y == 2 * x
+        opcodes: vec![Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![
+                (FieldElement::from(2u128), w_x),
+                (-FieldElement::one(), w_y),
+            ],
+            q_c: FieldElement::zero(),
+        })],
+        ..Circuit::default()
+    };
+
+    let plan = ExecutionPlan::new(&StubbedBackend, &circuit);
+    assert_send_sync(&plan);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (1..=4u128)
+            .map(|x| {
+                let plan = &plan;
+                scope.spawn(move || {
+                    let initial_witness =
+                        WitnessMap::from(BTreeMap::from([(w_x, FieldElement::from(x))]));
+                    let mut acvm = plan.acvm(initial_witness);
+                    assert_eq!(
+                        acvm.solve_with_executor(&mut MockOracles::new()),
+                        ACVMStatus::Solved
+                    );
+                    (x, acvm.finalize()[&w_y])
+                })
+            })
+            .collect();
+        for handle in handles {
+            let (x, y) = handle.join().unwrap();
+            assert_eq!(y, FieldElement::from(2 * x));
+        }
+    });
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn execution_plan_runs_batch_in_parallel() {
+    let w_input = Witness(1);
+    let outputs: Vec<Witness> = (2..34).map(Witness).collect();
+    let circuit = Circuit {
+        current_witness_index: 33,
+        opcodes: vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SHA256 {
+            inputs: vec![FunctionInput { witness: w_input, num_bits: 8 }],
+            outputs: outputs.clone(),
+        })],
+        ..Circuit::default()
+    };
+    let initial_witnesses: Vec<WitnessMap> = (0..16u128)
+        .map(|x| WitnessMap::from(BTreeMap::from([(w_input, FieldElement::from(x))])))
+        .collect();
+
+    let plan = ExecutionPlan::new(&StubbedBackend, &circuit);
+    let serial = plan.execute_batch(initial_witnesses.clone(), &mut MockOracles::new());
+    let parallel = plan.execute_batch_parallel(initial_witnesses, MockOracles::new);
+    assert_eq!(parallel.len(), 16);
+    for (serial, parallel) in serial.into_iter().zip(parallel) {
+        let (serial, parallel) = (serial.unwrap(), parallel.unwrap());
+        for output in &outputs {
+            assert_eq!(serial[output], parallel[output]);
+        }
+    }
+}
+
 #[test]
 fn brillig_oracle_predicate() {
     let fe_0 = FieldElement::zero();
@@ -511,13 +808,23 @@ fn unsatisfied_opcode_resolved() {
     values.insert(c, FieldElement::from(1_i128));
     values.insert(d, FieldElement::from(2_i128));
 
-    let opcodes = vec![Opcode::Arithmetic(opcode_a)];
+    let opcodes = vec![Opcode::Arithmetic(opcode_a.clone())];
     let mut acvm = ACVM::new(&StubbedBackend, opcodes, values);
     let solver_status = acvm.solve();
     assert_eq!(
         solver_status,
         ACVMStatus::Failure(OpcodeResolutionError::UnsatisfiedConstrain {
             opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(0)),
+            info: Some(Box::new(UnsatisfiedConstraintInfo::Expression {
+                expression: opcode_a,
+                witness_values: vec![
+                    (a, FieldElement::from(4_i128)),
+                    (b, FieldElement::from(2_i128)),
+                    (c, FieldElement::from(1_i128)),
+                    (d, FieldElement::from(2_i128)),
+                ],
+                residual: -FieldElement::one(),
+            })),
         }),
         "The first opcode is not satisfiable, expected an error indicating this"
     );
@@ -599,6 +906,7 @@ fn unsatisfied_opcode_resolved_brillig() {
     assert_eq!(
         solver_status,
         ACVMStatus::Failure(OpcodeResolutionError::BrilligFunctionFailed {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(0)),
             message: "explicit trap hit in brillig".to_string(),
             call_stack: vec![OpcodeLocation::Brillig { acir_index: 0, brillig_index: 2 }]
         }),
@@ -606,6 +914,318 @@ fn unsatisfied_opcode_resolved_brillig() {
     );
 }
 
+#[test]
+fn unsatisfied_black_box_reports_outputs() {
+    let lhs = Witness(1);
+    let rhs = Witness(2);
+    let output = Witness(3);
+
+    let opcodes = vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::AND {
+        lhs: FunctionInput { witness: lhs, num_bits: 8 },
+        rhs: FunctionInput { witness: rhs, num_bits: 8 },
+        output,
+    })];
+
+    let witness_assignments = BTreeMap::from([
+        (lhs, FieldElement::from(0b1100_u128)),
+        (rhs, FieldElement::from(0b1010_u128)),
+        (output, FieldElement::from(0b1110_u128)),
+    ])
+    .into();
+
+    let mut acvm = ACVM::new(&StubbedBackend, opcodes, witness_assignments);
+    let solver_status = acvm.solve();
+    assert_eq!(
+        solver_status,
+        ACVMStatus::Failure(OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(0)),
+            info: Some(Box::new(UnsatisfiedConstraintInfo::BlackBox {
+                func: BlackBoxFunc::AND,
+                inputs: vec![
+                    (lhs, FieldElement::from(0b1100_u128)),
+                    (rhs, FieldElement::from(0b1010_u128)),
+                ],
+                expected_outputs: vec![(output, FieldElement::from(0b1110_u128))],
+                computed_outputs: vec![(output, FieldElement::from(0b1000_u128))],
+            })),
+        }),
+    );
+}
+
+fn schnorr_verify_opcode() -> (Opcode, Witness) {
+    let byte = |index| FunctionInput { witness: Witness(index), num_bits: 8 };
+    let output = Witness(100);
+    let opcode = Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SchnorrVerify {
+        public_key_x: FunctionInput { witness: Witness(1), num_bits: FieldElement::max_num_bits() },
+        public_key_y: FunctionInput { witness: Witness(2), num_bits: FieldElement::max_num_bits() },
+        signature: (3..67).map(byte).collect(),
+        message: (67..70).map(byte).collect(),
+        output,
+    });
+    (opcode, output)
+}
+
+fn schnorr_verify_definition() -> BlackBoxDefinition {
+    *BlackBoxRegistry::standard().get(BlackBoxFunc::SchnorrVerify.name()).unwrap()
+}
+
+#[test]
+fn registered_native_implementation_solves_black_box() {
+    let (opcode, output) = schnorr_verify_opcode();
+    let witness_assignments: WitnessMap =
+        BTreeMap::from_iter((1..70).map(|index| (Witness(index), FieldElement::one()))).into();
+
+    let mut registry = BlackBoxRegistry::new();
+    registry.register(BlackBoxDefinition {
+        native: Some(|_, inputs, _| {
+            // Accept signatures over messages beginning with a one.
+            Ok(vec![vec![FieldElement::from(inputs.get(3, 0).value.is_one())]])
+        }),
+        ..schnorr_verify_definition()
+    });
+
+    let mut acvm = ACVM::new(&StubbedBackend, vec![opcode], witness_assignments);
+    acvm.set_black_box_registry(&registry);
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    assert_eq!(acvm.witness_map()[&output], FieldElement::one());
+}
+
+#[test]
+fn registered_fallback_replaces_unsupported_black_box() {
+    let (opcode, output) = schnorr_verify_opcode();
+    let circuit =
+        Circuit { current_witness_index: 100, opcodes: vec![opcode], ..Circuit::default() };
+    let is_supported = |opcode: &Opcode| !matches!(opcode, Opcode::BlackBoxFuncCall(_));
+
+    let unsupported =
+        compile_with_registry(circuit.clone(), Language::R1CS, is_supported, &black_box_registry());
+    assert_eq!(
+        unsupported.unwrap_err(),
+        CompileError::UnsupportedBlackBox(BlackBoxFunc::SchnorrVerify)
+    );
+
+    let mut registry = black_box_registry();
+    registry.register(BlackBoxDefinition {
+        // Constrain the output to be zero, rejecting every signature.
+        fallback: Some(|_, outputs, context| {
+            let rejected = Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(FieldElement::one(), outputs[0][0])],
+                q_c: FieldElement::zero(),
+            };
+            Some((context.num_witness, vec![Opcode::Arithmetic(rejected)]))
+        }),
+        ..schnorr_verify_definition()
+    });
+    let (circuit, _) =
+        compile_with_registry(circuit, Language::R1CS, is_supported, &registry).unwrap();
+    assert_eq!(circuit.opcodes, vec![Opcode::Arithmetic(Expression::from(output))],);
+}
+
+#[test]
+fn registered_definition_serves_compilation_and_solving() {
+    let (opcode, output) = schnorr_verify_opcode();
+    let circuit =
+        Circuit { current_witness_index: 100, opcodes: vec![opcode], ..Circuit::default() };
+    let witness_assignments: WitnessMap =
+        BTreeMap::from_iter((1..70).map(|index| (Witness(index), FieldElement::one()))).into();
+
+    let mut registry = black_box_registry();
+    registry.register(BlackBoxDefinition {
+        // Accept every signature.
+        native: Some(|_, _, _| Ok(vec![vec![FieldElement::one()]])),
+        fallback: Some(|_, outputs, context| {
+            let accepted = Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(FieldElement::one(), outputs[0][0])],
+                q_c: -FieldElement::one(),
+            };
+            Some((context.num_witness, vec![Opcode::Arithmetic(accepted)]))
+        }),
+        ..schnorr_verify_definition()
+    });
+
+    // The fallback replaces the opcode when the backend does not support it.
+    let is_supported = |opcode: &Opcode| !matches!(opcode, Opcode::BlackBoxFuncCall(_));
+    let (fallback_circuit, _) =
+        compile_with_registry(circuit.clone(), Language::R1CS, is_supported, &registry).unwrap();
+    assert!(fallback_circuit.opcodes.iter().all(|opcode| matches!(opcode, Opcode::Arithmetic(_))));
+
+    // Otherwise the opcode is kept and solved by the native implementation from the same registry.
+    let (native_circuit, _) =
+        compile_with_registry(circuit, Language::R1CS, |_| true, &registry).unwrap();
+    let mut acvm = ACVM::new(&StubbedBackend, native_circuit.opcodes, witness_assignments);
+    acvm.set_black_box_registry(&registry);
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    assert_eq!(acvm.witness_map()[&output], FieldElement::one());
+}
+
+#[test]
+fn brillig_step_limit_halts_infinite_loop() {
+    let brillig = Brillig {
+        inputs: vec![],
+        outputs: vec![],
+        bytecode: vec![BrilligOpcode::Jump { location: 0 }],
+        predicate: None,
+        foreign_call_results: vec![],
+    };
+
+    let mut acvm = ACVM::new(&StubbedBackend, vec![Opcode::Brillig(brillig)], WitnessMap::new());
+    acvm.set_resource_limits(ResourceLimits {
+        max_brillig_steps: Some(1000),
+        ..ResourceLimits::default()
+    });
+    assert_eq!(
+        acvm.solve(),
+        ACVMStatus::Failure(OpcodeResolutionError::ResourceLimitExceeded {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Brillig {
+                acir_index: 0,
+                brillig_index: 0,
+            }),
+            limit: ResourceLimitError::BrilligSteps(1000),
+        })
+    );
+}
+
+#[test]
+fn opcode_limit_halts_execution() {
+    let a = Witness(0);
+    // a = 1
+    let assign_a = Expression {
+        mul_terms: vec![],
+        linear_combinations: vec![(FieldElement::one(), a)],
+        q_c: -FieldElement::one(),
+    };
+    let opcodes = vec![Opcode::Arithmetic(assign_a.clone()), Opcode::Arithmetic(assign_a)];
+
+    let mut acvm = ACVM::new(&StubbedBackend, opcodes, WitnessMap::new());
+    acvm.set_resource_limits(ResourceLimits { max_opcodes: Some(1), ..ResourceLimits::default() });
+    assert_eq!(
+        acvm.solve(),
+        ACVMStatus::Failure(OpcodeResolutionError::ResourceLimitExceeded {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(1)),
+            limit: ResourceLimitError::Opcodes(1),
+        })
+    );
+}
+
+#[test]
+fn profile_records_opcodes_and_brillig_instructions() {
+    let w_brillig = Witness(1);
+    let w_sum = Witness(2);
+    let brillig = Brillig {
+        inputs: vec![],
+        outputs: vec![BrilligOutputs::Simple(w_brillig)],
+        bytecode: vec![
+            BrilligOpcode::Call { location: 2 },
+            BrilligOpcode::Jump { location: 4 },
+            BrilligOpcode::Const { destination: RegisterIndex::from(0), value: Value::from(5u128) },
+            BrilligOpcode::Return,
+        ],
+        predicate: None,
+        foreign_call_results: vec![],
+    };
+    // w_sum = w_brillig + 1
+    let sum = Expression {
+        mul_terms: vec![],
+        linear_combinations: vec![(FieldElement::one(), w_brillig), (-FieldElement::one(), w_sum)],
+        q_c: FieldElement::one(),
+    };
+
+    let opcodes = vec![Opcode::Brillig(brillig), Opcode::Arithmetic(sum)];
+    let mut acvm = ACVM::new(&StubbedBackend, opcodes, WitnessMap::new());
+    acvm.enable_profiling();
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+
+    let profile = acvm.profile().unwrap().clone().with_assert_messages(&[(
+        OpcodeLocation::Brillig { acir_index: 0, brillig_index: 2 },
+        "unreachable".to_string(),
+    )]);
+    assert_eq!(profile.opcodes["brillig"].count, 1);
+    assert_eq!(profile.opcodes["arithmetic"].count, 1);
+
+    let brillig_location = &profile.locations[&OpcodeLocation::Acir(0)];
+    assert_eq!(brillig_location.brillig_assert_messages[&2], "unreachable");
+    let brillig_profile = brillig_location.brillig.as_ref().unwrap();
+    assert_eq!(brillig_profile.call_counts, BTreeMap::from([(2, 1)]));
+    assert_eq!(brillig_profile.function_instruction_counts, BTreeMap::from([(0, 2), (2, 2)]));
+
+    let json: serde_json::Value = serde_json::from_str(&profile.to_json().unwrap()).unwrap();
+    assert_eq!(json["locations"]["1"]["count"], 1);
+    assert_eq!(json["locations"]["0"]["brillig"]["call_counts"]["2"], 1);
+
+    let stacks = profile.collapsed_stacks();
+    let frames: Vec<_> = stacks.lines().map(|line| line.rsplit_once(' ').unwrap().0).collect();
+    assert_eq!(frames, vec!["brillig;opcode 0", "arithmetic;opcode 1"]);
+    assert_eq!(profile.brillig_collapsed_stacks(), "opcode 0 2\nopcode 0;brillig@2 2\n");
+}
+
+#[test]
+fn execution_trace_records_assignments_and_dependencies() {
+    let w_x = Witness(1);
+    let w_double = Witness(2);
+    let w_sum = Witness(3);
+    // double = 2 * x, computed in Brillig
+    let brillig = Brillig {
+        inputs: vec![BrilligInputs::Single(w_x.into())],
+        outputs: vec![BrilligOutputs::Simple(w_double)],
+        bytecode: vec![BrilligOpcode::BinaryFieldOp {
+            op: BinaryFieldOp::Add,
+            lhs: RegisterIndex::from(0),
+            rhs: RegisterIndex::from(0),
+            destination: RegisterIndex::from(0),
+        }],
+        predicate: None,
+        foreign_call_results: vec![],
+    };
+    // sum = x + double
+    let sum = Expression {
+        mul_terms: vec![],
+        linear_combinations: vec![
+            (FieldElement::one(), w_x),
+            (FieldElement::one(), w_double),
+            (-FieldElement::one(), w_sum),
+        ],
+        q_c: FieldElement::zero(),
+    };
+    let opcodes = vec![Opcode::Brillig(brillig), Opcode::Arithmetic(sum)];
+    let x = FieldElement::from(3u128);
+
+    for format in [TraceFormat::Binary, TraceFormat::Json] {
+        let mut trace = Vec::new();
+        let initial_witness = BTreeMap::from([(w_x, x)]).into();
+        let mut acvm = ACVM::new(&StubbedBackend, opcodes.clone(), initial_witness);
+        acvm.trace_execution(&mut trace, TraceOptions { format, brillig: true });
+        assert_eq!(acvm.solve(), ACVMStatus::Solved);
+        acvm.finish_trace().unwrap();
+        drop(acvm);
+
+        let records: Vec<_> =
+            TraceReader::new(trace.as_slice(), format).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0], TraceRecord::InitialWitness(vec![(w_x, x)]));
+
+        let TraceRecord::Opcode(brillig_trace) = &records[1] else {
+            panic!("expected an opcode record");
+        };
+        assert_eq!(brillig_trace.assignments, vec![(w_double, FieldElement::from(6u128))]);
+        assert_eq!(brillig_trace.dependencies, vec![(w_x, None)]);
+        let steps = &brillig_trace.brillig.as_ref().unwrap().steps;
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].register_writes, vec![(RegisterIndex::from(0), Value::from(6u128))]);
+
+        assert_eq!(
+            records[2],
+            TraceRecord::Opcode(OpcodeTrace {
+                acir_index: 1,
+                assignments: vec![(w_sum, FieldElement::from(9u128))],
+                dependencies: vec![(w_x, None), (w_double, Some(0))],
+                brillig: None,
+            })
+        );
+    }
+}
+
 #[test]
 fn memory_operations() {
     let initial_witness = WitnessMap::from(BTreeMap::from_iter([
@@ -645,3 +1265,287 @@ fn memory_operations() {
 
     assert_eq!(witness_map[&Witness(8)], FieldElement::from(6u128));
 }
+
+#[test]
+fn reports_all_failures() {
+    let w_x = Witness(1);
+    let w_y = Witness(2);
+    let w_brillig_output = Witness(3);
+    let w_dependent = Witness(4);
+
+    // x == 1
+    let assert_x = Expression {
+        mul_terms: vec![],
+        linear_combinations: vec![(FieldElement::one(), w_x)],
+        q_c: -FieldElement::one(),
+    };
+
+    // A Brillig function which always fails, leaving its output unassigned.
+    let failing_brillig = Opcode::Brillig(Brillig {
+        inputs: vec![BrilligInputs::Single(w_x.into())],
+        outputs: vec![BrilligOutputs::Simple(w_brillig_output)],
+        bytecode: vec![BrilligOpcode::Trap],
+        predicate: None,
+        foreign_call_results: vec![],
+    });
+
+    // dependent = brillig_output * brillig_output
+    let dependent = Opcode::Arithmetic(Expression {
+        mul_terms: vec![(FieldElement::one(), w_brillig_output, w_brillig_output)],
+        linear_combinations: vec![(-FieldElement::one(), w_dependent)],
+        q_c: FieldElement::zero(),
+    });
+
+    // y == 2
+    let assert_y = Expression {
+        mul_terms: vec![],
+        linear_combinations: vec![(FieldElement::one(), w_y)],
+        q_c: -FieldElement::from(2_i128),
+    };
+
+    let opcodes = vec![
+        Opcode::Arithmetic(assert_x.clone()),
+        failing_brillig,
+        dependent,
+        Opcode::Arithmetic(assert_y.clone()),
+    ];
+    let assert_messages = vec![
+        (OpcodeLocation::Acir(0), "x must be one".to_string()),
+        (OpcodeLocation::Acir(3), "y must be two".to_string()),
+    ];
+
+    let witness_assignments =
+        BTreeMap::from([(w_x, FieldElement::from(3_i128)), (w_y, FieldElement::from(5_i128))])
+            .into();
+
+    let mut acvm = ACVM::new(&StubbedBackend, opcodes, witness_assignments);
+    acvm.report_all_failures(assert_messages);
+    let solver_status = acvm.solve();
+
+    let first_error = OpcodeResolutionError::UnsatisfiedConstrain {
+        opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(0)),
+        info: Some(Box::new(UnsatisfiedConstraintInfo::Expression {
+            expression: assert_x,
+            witness_values: vec![(w_x, FieldElement::from(3_i128))],
+            residual: FieldElement::from(2_i128),
+        })),
+    };
+    assert_eq!(solver_status, ACVMStatus::Failure(first_error.clone()));
+
+    let report = acvm.failure_report().expect("ACVM should be reporting all failures");
+    assert_eq!(
+        report.failures(),
+        &[
+            OpcodeFailure {
+                opcode_location: OpcodeLocation::Acir(0),
+                assert_message: Some("x must be one".to_string()),
+                error: first_error,
+            },
+            OpcodeFailure {
+                opcode_location: OpcodeLocation::Brillig { acir_index: 1, brillig_index: 0 },
+                assert_message: None,
+                error: OpcodeResolutionError::BrilligFunctionFailed {
+                    opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(1)),
+                    message: "explicit trap hit in brillig".to_string(),
+                    call_stack: vec![OpcodeLocation::Brillig { acir_index: 1, brillig_index: 0 }],
+                },
+            },
+            OpcodeFailure {
+                opcode_location: OpcodeLocation::Acir(3),
+                assert_message: Some("y must be two".to_string()),
+                error: OpcodeResolutionError::UnsatisfiedConstrain {
+                    opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(3)),
+                    info: Some(Box::new(UnsatisfiedConstraintInfo::Expression {
+                        expression: assert_y,
+                        witness_values: vec![(w_y, FieldElement::from(5_i128))],
+                        residual: FieldElement::from(3_i128),
+                    })),
+                },
+            },
+        ]
+    );
+    assert_eq!(report.tainted_opcodes(), &[OpcodeLocation::Acir(2)]);
+    assert!(report.is_witness_tainted(&w_brillig_output));
+    assert!(report.is_witness_tainted(&w_dependent));
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn parallel_solving_matches_serial_solving() {
+    let inputs: Vec<Witness> = (1..=4).map(Witness).collect();
+    let mut next_witness = 5;
+    let mut hash_opcode = |input: Witness| {
+        let outputs: Vec<Witness> = (next_witness..next_witness + 32).map(Witness).collect();
+        next_witness += 32;
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SHA256 {
+            inputs: vec![FunctionInput { witness: input, num_bits: 8 }],
+            outputs,
+        })
+    };
+    let opcodes: Vec<Opcode> = inputs.iter().map(|input| hash_opcode(*input)).collect();
+
+    let initial_witness: WitnessMap = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, witness)| (*witness, FieldElement::from(index as u128)))
+        .collect::<BTreeMap<_, _>>()
+        .into();
+
+    let mut serial = ACVM::new(&StubbedBackend, opcodes.clone(), initial_witness.clone());
+    let mut parallel = ACVM::new(&StubbedBackend, opcodes.clone(), initial_witness.clone());
+    assert_eq!(serial.solve(), ACVMStatus::Solved);
+    assert_eq!(parallel.solve_parallel(), ACVMStatus::Solved);
+    assert_eq!(serial.finalize(), parallel.finalize());
+
+    // Preassign incorrect outputs for the last two hashes, only the first of these should be reported.
+    let mut initial_witness = initial_witness;
+    initial_witness.insert(Witness(5 + 2 * 32), FieldElement::from(1000u128));
+    initial_witness.insert(Witness(5 + 3 * 32), FieldElement::from(1000u128));
+
+    let mut serial = ACVM::new(&StubbedBackend, opcodes.clone(), initial_witness.clone());
+    let mut parallel = ACVM::new(&StubbedBackend, opcodes, initial_witness);
+    let status = serial.solve();
+    assert!(matches!(
+        &status,
+        ACVMStatus::Failure(OpcodeResolutionError::UnsatisfiedConstrain {
+            opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(2)),
+            ..
+        })
+    ));
+    assert_eq!(parallel.solve_parallel(), status);
+    assert_eq!(serial.witness_map(), parallel.witness_map());
+}
+
+fn recursive_aggregation_opcodes() -> Vec<Opcode> {
+    let function_input = |witness: u32| FunctionInput { witness: Witness(witness), num_bits: 254 };
+    let aggregation = |input_aggregation_object: Option<Vec<FunctionInput>>,
+                       output_aggregation_object: Vec<Witness>| {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RecursiveAggregation {
+            verification_key: vec![function_input(1), function_input(2)],
+            proof: vec![function_input(3)],
+            public_inputs: vec![function_input(4)],
+            key_hash: function_input(5),
+            input_aggregation_object,
+            output_aggregation_object,
+        })
+    };
+
+    vec![
+        aggregation(None, vec![Witness(6), Witness(7)]),
+        aggregation(Some(vec![function_input(6), function_input(7)]), vec![Witness(8), Witness(9)]),
+    ]
+}
+
+fn recursive_aggregation_inputs(key_hash: FieldElement) -> WitnessMap {
+    BTreeMap::from([
+        (Witness(1), FieldElement::from(1u128)),
+        (Witness(2), FieldElement::from(2u128)),
+        (Witness(3), FieldElement::from(3u128)),
+        (Witness(4), FieldElement::from(4u128)),
+        (Witness(5), key_hash),
+    ])
+    .into()
+}
+
+#[test]
+fn recursive_aggregation_defaults_to_zero() {
+    let mut acvm = ACVM::new(
+        &StubbedBackend,
+        recursive_aggregation_opcodes(),
+        recursive_aggregation_inputs(FieldElement::zero()),
+    );
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+
+    let witness_map = acvm.finalize();
+    for witness in 6..=9 {
+        assert_eq!(witness_map[&Witness(witness)], FieldElement::zero());
+    }
+}
+
+#[test]
+fn recursive_aggregation_uses_backend_hook() {
+    let backend = MockRecursiveVerifier::new(StubbedBackend);
+    let verification_key = [FieldElement::from(1u128), FieldElement::from(2u128)];
+    let key_hash = mock_key_hash(&verification_key);
+
+    let mut acvm = ACVM::new(
+        &backend,
+        recursive_aggregation_opcodes(),
+        recursive_aggregation_inputs(key_hash),
+    );
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+
+    let proof = [FieldElement::from(3u128)];
+    let public_inputs = [FieldElement::from(4u128)];
+    let first = backend
+        .recursive_aggregation(&verification_key, &proof, &public_inputs, &key_hash, None, 2)
+        .unwrap();
+    let second = backend
+        .recursive_aggregation(
+            &verification_key,
+            &proof,
+            &public_inputs,
+            &key_hash,
+            Some(&first),
+            2,
+        )
+        .unwrap();
+    assert_ne!(first, vec![FieldElement::zero(); 2]);
+    assert_eq!([witness_map[&Witness(6)], witness_map[&Witness(7)]], first[..]);
+    assert_eq!([witness_map[&Witness(8)], witness_map[&Witness(9)]], second[..]);
+}
+
+#[test]
+fn recursive_aggregation_rejects_mismatched_key_hash() {
+    let backend = MockRecursiveVerifier::new(StubbedBackend);
+    let mut acvm = ACVM::new(
+        &backend,
+        recursive_aggregation_opcodes(),
+        recursive_aggregation_inputs(FieldElement::one()),
+    );
+
+    let ACVMStatus::Failure(OpcodeResolutionError::BlackBoxFunctionFailed { func, .. }) =
+        acvm.solve()
+    else {
+        panic!("expected the recursive aggregation to fail");
+    };
+    assert_eq!(func, BlackBoxFunc::RecursiveAggregation);
+}
+
+#[test]
+#[cfg(feature = "bls12_381")]
+fn solves_bandersnatch_fixed_base_scalar_mul() {
+    use blackbox_solver::BandersnatchSolver;
+
+    // The generator of the prime order subgroup published alongside Bandersnatch.
+    let generator_x = FieldElement::from_hex(
+        "0x29c132cc2c0b34c5743711777bbe42f32b79c022ad998465e1e71866a252ae18",
+    )
+    .unwrap();
+    let generator_y = FieldElement::from_hex(
+        "0x2a6c669eda123e0f157d8b50badcd586358cad81eee464605e3167b6cc974166",
+    )
+    .unwrap();
+    // The order of the prime order subgroup.
+    let subgroup_order = FieldElement::from_hex(
+        "0x1cfb69d4ca675f520cce760202687600ff8f87007419047174fd06b52876e7e1",
+    )
+    .unwrap();
+
+    let w_scalar = Witness(1);
+    let outputs = (Witness(2), Witness(3));
+    let opcodes = vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::FixedBaseScalarMul {
+        input: FunctionInput { witness: w_scalar, num_bits: FieldElement::max_num_bits() },
+        outputs,
+    })];
+
+    // Scalars are reduced modulo the order of the subgroup, so both of these multiply the generator by one.
+    for scalar in [FieldElement::one(), subgroup_order + FieldElement::one()] {
+        let initial_witness = BTreeMap::from([(w_scalar, scalar)]).into();
+        let mut acvm = ACVM::new(&BandersnatchSolver, opcodes.clone(), initial_witness);
+        assert_eq!(acvm.solve(), ACVMStatus::Solved);
+        let witness_map = acvm.finalize();
+        assert_eq!((witness_map[&outputs.0], witness_map[&outputs.1]), (generator_x, generator_y));
+    }
+}
@@ -7,18 +7,25 @@ use acir::{
         Circuit, Opcode,
     },
     native_types::{Expression, Witness},
-    FieldElement,
+    BlackBoxFunc, FieldElement,
 };
 use acvm::{
-    compiler::compile,
+    compiler::{compile, CompileError},
     pwg::{ACVMStatus, ACVM},
     Language,
 };
-use blackbox_solver::{blake2s, hash_to_field_128_security, keccak256, sha256};
+use blackbox_solver::{
+    aes128_cbc_encrypt, aes128_ctr_encrypt, bigint_add, bigint_mod, bigint_mul, blake2s, blake3,
+    eddsa_verify, hash_to_field_128_security, keccak256, poseidon2_permutation, sha256, sha512,
+    BlackBoxFunctionSolver, GrumpkinSolver,
+};
 use paste::paste;
 use proptest::prelude::*;
 use std::collections::{BTreeMap, BTreeSet};
-use stdlib::blackbox_fallbacks::{UInt32, UInt64, UInt8};
+use stdlib::{
+    blackbox_fallbacks::{UInt32, UInt64, UInt8},
+    ethereum::address_from_public_key,
+};
 
 test_uint!(test_uint8, UInt8, u8, 8);
 test_uint!(test_uint32, UInt32, u32, 32);
@@ -221,6 +228,10 @@ macro_rules! test_uint_inner {
 test_hashes!(test_sha256, sha256, SHA256, does_not_support_sha256);
 test_hashes!(test_blake2s, blake2s, Blake2s, does_not_support_blake2s);
 test_hashes!(test_keccak, keccak256, Keccak256, does_not_support_keccak);
+test_hashes!(test_sha512, sha512, SHA512, does_not_support_sha512);
+test_hashes!(test_sha512_two_blocks, sha512, SHA512, does_not_support_sha512, 112..140);
+test_hashes!(test_blake3, blake3, Blake3, does_not_support_blake3);
+test_hashes!(test_blake3_three_chunks, blake3, Blake3, does_not_support_blake3, 2049..2100);
 
 fn does_not_support_sha256(opcode: &Opcode) -> bool {
     !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SHA256 { .. }))
@@ -231,6 +242,58 @@ fn does_not_support_blake2s(opcode: &Opcode) -> bool {
 fn does_not_support_keccak(opcode: &Opcode) -> bool {
     !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Keccak256 { .. }))
 }
+fn does_not_support_sha512(opcode: &Opcode) -> bool {
+    !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SHA512 { .. }))
+}
+fn does_not_support_blake3(opcode: &Opcode) -> bool {
+    !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Blake3 { .. }))
+}
+
+/// Solves a SHA256 of `inputs`, replacing it with its fallback if `use_fallback` is set, and returns the digest.
+fn solve_sha256(inputs: &[(u128, u32)], use_fallback: bool) -> Vec<u8> {
+    let input_witnesses: Vec<FunctionInput> = (1..=inputs.len() as u32)
+        .zip(inputs)
+        .map(|(witness, (_, num_bits))| FunctionInput {
+            witness: Witness(witness),
+            num_bits: *num_bits,
+        })
+        .collect();
+    let output_witnesses: Vec<Witness> =
+        (inputs.len() as u32 + 1..=inputs.len() as u32 + 32).map(Witness).collect();
+    let witness_assignments: BTreeMap<_, _> = input_witnesses
+        .iter()
+        .zip(inputs)
+        .map(|(input, (value, _))| (input.witness, FieldElement::from(*value)))
+        .collect();
+
+    let circuit = Circuit {
+        current_witness_index: inputs.len() as u32 + 32,
+        opcodes: vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::SHA256 {
+            inputs: input_witnesses,
+            outputs: output_witnesses.clone(),
+        })],
+        ..Circuit::default()
+    };
+    let is_supported = if use_fallback { does_not_support_sha256 } else { |_: &Opcode| true };
+    let circuit = compile(circuit, Language::PLONKCSat { width: 3 }, is_supported).unwrap().0;
+
+    let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+    output_witnesses.iter().map(|witness| witness_map[witness].to_u128() as u8).collect()
+}
+
+#[test]
+fn hash_inputs_wider_than_a_byte_are_packed_little_endian() {
+    // A 32 bit input contributes its four bytes to the message, least significant first, when solved natively.
+    let packed = [(0x6162_6364, 32)];
+    assert_eq!(solve_sha256(&packed, false), sha256(b"dcba").unwrap());
+
+    // Byte inputs are hashed in order, both when solved natively and when replaced by their fallback.
+    let bytes = [(0x64, 8), (0x63, 8), (0x62, 8), (0x61, 8)];
+    assert_eq!(solve_sha256(&bytes, false), sha256(b"dcba").unwrap());
+    assert_eq!(solve_sha256(&bytes, true), sha256(b"dcba").unwrap());
+}
 
 #[macro_export]
 macro_rules! test_hashes {
@@ -239,11 +302,20 @@ macro_rules! test_hashes {
         $hasher:ident,
         $opcode:ident,
         $opcode_support: ident
+    ) => {
+        test_hashes!($name, $hasher, $opcode, $opcode_support, 1..50);
+    };
+    (
+        $name:ident,
+        $hasher:ident,
+        $opcode:ident,
+        $opcode_support: ident,
+        $input_len: expr
     ) => {
         proptest! {
             #![proptest_config(ProptestConfig::with_cases(3))]
             #[test]
-            fn $name(input_values in proptest::collection::vec(0..u8::MAX, 1..50)) {
+            fn $name(input_values in proptest::collection::vec(0..u8::MAX, $input_len)) {
                 let mut opcodes = Vec::new();
                 let mut witness_assignments = BTreeMap::new();
                 let mut input_witnesses: Vec<FunctionInput> = Vec::new();
@@ -253,6 +325,7 @@ macro_rules! test_hashes {
                 // prepare test data
                 let mut counter = 0;
                 let output = $hasher(&input_values).unwrap();
+                let num_outputs = output.len() as u32;
                 for inp_v in input_values {
                     counter += 1;
                     let function_input = FunctionInput { witness: Witness(counter), num_bits: 8 };
@@ -266,7 +339,7 @@ macro_rules! test_hashes {
                     witness_assignments.insert(Witness(counter), FieldElement::from(o_v as u128));
                 }
 
-                for _ in 0..32 {
+                for _ in 0..num_outputs {
                     counter += 1;
                     output_witnesses.push(Witness(counter));
                 }
@@ -282,7 +355,7 @@ macro_rules! test_hashes {
 
                 // compile circuit
                 let circuit = Circuit {
-                    current_witness_index: witness_assignments.len() as u32 + 32,
+                    current_witness_index: witness_assignments.len() as u32 + num_outputs,
                     opcodes,
                     private_parameters: BTreeSet::new(), // This is not correct but is unused in this test.
                     ..Circuit::default()
@@ -352,3 +425,689 @@ proptest! {
         prop_assert_eq!(solver_status, ACVMStatus::Solved, "should be fully solved");
     }
 }
+
+fn does_not_support_poseidon2_permutation(opcode: &Opcode) -> bool {
+    !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Poseidon2Permutation { .. }))
+}
+
+#[test]
+fn poseidon2_permutation_fallback_rejects_malformed_state() {
+    let inputs: Vec<FunctionInput> = (1..=3)
+        .map(|index| FunctionInput {
+            witness: Witness(index),
+            num_bits: FieldElement::max_num_bits(),
+        })
+        .collect();
+    let circuit = Circuit {
+        current_witness_index: 6,
+        opcodes: vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Poseidon2Permutation {
+            inputs,
+            outputs: (4..=6).map(Witness).collect(),
+            len: 3,
+        })],
+        ..Circuit::default()
+    };
+    let error =
+        compile(circuit, Language::PLONKCSat { width: 3 }, does_not_support_poseidon2_permutation)
+            .unwrap_err();
+    assert!(matches!(error, CompileError::UnsupportedBlackBox(BlackBoxFunc::Poseidon2Permutation)));
+}
+
+#[test]
+fn logic_fallbacks_reject_inputs_of_different_widths() {
+    let lhs = FunctionInput { witness: Witness(1), num_bits: 8 };
+    let rhs = FunctionInput { witness: Witness(2), num_bits: 16 };
+    let calls = [
+        (BlackBoxFunc::AND, BlackBoxFuncCall::AND { lhs, rhs, output: Witness(3) }),
+        (BlackBoxFunc::XOR, BlackBoxFuncCall::XOR { lhs, rhs, output: Witness(3) }),
+    ];
+    for (func, call) in calls {
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![Opcode::BlackBoxFuncCall(call)],
+            ..Circuit::default()
+        };
+        let error = compile(circuit, Language::PLONKCSat { width: 3 }, |opcode| {
+            !matches!(opcode, Opcode::BlackBoxFuncCall(_))
+        })
+        .unwrap_err();
+        assert_eq!(error, CompileError::UnsupportedBlackBox(func));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(3))]
+    #[test]
+    fn test_poseidon2_permutation(input_values in proptest::array::uniform4(any::<u128>())) {
+        let input_values = input_values.map(FieldElement::from);
+        let expected_outputs = poseidon2_permutation(&input_values, 4).unwrap();
+
+        let input_witnesses: Vec<FunctionInput> = (1..=4)
+            .map(|index| FunctionInput { witness: Witness(index), num_bits: FieldElement::max_num_bits() })
+            .collect();
+        let output_witnesses: Vec<Witness> = (5..=8).map(Witness).collect();
+        let witness_assignments: BTreeMap<Witness, FieldElement> =
+            input_witnesses.iter().map(|input| input.witness).zip(input_values).collect();
+
+        let blackbox = Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Poseidon2Permutation {
+            inputs: input_witnesses,
+            outputs: output_witnesses.clone(),
+            len: 4,
+        });
+        let circuit = Circuit {
+            current_witness_index: 8,
+            opcodes: vec![blackbox],
+            // The inputs must be marked as parameters so that the compiler can reduce expressions which use them.
+            private_parameters: (1..=4).map(Witness).collect(),
+            ..Circuit::default()
+        };
+        let circuit = compile(circuit, Language::PLONKCSat{ width: 3 }, does_not_support_poseidon2_permutation).unwrap().0;
+        prop_assert!(!circuit.opcodes.iter().any(|opcode| !does_not_support_poseidon2_permutation(opcode)));
+
+        // solve witnesses
+        let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+        let solver_status = acvm.solve();
+
+        prop_assert_eq!(solver_status, ACVMStatus::Solved, "should be fully solved");
+        let witness_map = acvm.finalize();
+        for (witness, expected) in output_witnesses.iter().zip(expected_outputs) {
+            prop_assert_eq!(witness_map[witness], expected);
+        }
+    }
+}
+
+fn does_not_support_eddsa_verify(opcode: &Opcode) -> bool {
+    !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EddsaVerify { .. }))
+}
+
+/// Solves an `EddsaVerify` opcode using its fallback, returning the value assigned to its output.
+fn solve_eddsa_verify_fallback(input_values: [FieldElement; 6]) -> FieldElement {
+    let inputs: Vec<FunctionInput> = (1..=6)
+        .map(|index| FunctionInput {
+            witness: Witness(index),
+            num_bits: FieldElement::max_num_bits(),
+        })
+        .collect();
+    let output = Witness(7);
+    let witness_assignments: BTreeMap<Witness, FieldElement> =
+        inputs.iter().map(|input| input.witness).zip(input_values).collect();
+
+    let blackbox = Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EddsaVerify {
+        public_key_x: inputs[0],
+        public_key_y: inputs[1],
+        signature_s: inputs[2],
+        signature_r8_x: inputs[3],
+        signature_r8_y: inputs[4],
+        message: inputs[5],
+        output,
+    });
+    let circuit = Circuit {
+        current_witness_index: 7,
+        opcodes: vec![blackbox],
+        // The inputs must be marked as parameters so that the compiler can reduce expressions which use them.
+        private_parameters: (1..=6).map(Witness).collect(),
+        ..Circuit::default()
+    };
+    let circuit = compile(circuit, Language::PLONKCSat { width: 3 }, does_not_support_eddsa_verify)
+        .unwrap()
+        .0;
+    assert!(circuit.opcodes.iter().all(does_not_support_eddsa_verify));
+
+    let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+    let solver_status = acvm.solve();
+    assert_eq!(solver_status, ACVMStatus::Solved, "should be fully solved");
+    acvm.finalize()[&output]
+}
+
+/// Returns circomlibjs' Poseidon signature of the message 1234 paired with `message`.
+fn eddsa_signature(message: u128) -> [FieldElement; 6] {
+    [
+        "0x1d5ac1f31407018b7d413a4f52c8f74463b30e6ac2238220ad8b254de4eaa3a2",
+        "0x1e1de8a908826c3f9ac2e0ceee929ecd0caf3b99b3ef24523aaab796a6f733c4",
+        "0x0471b36e0376770c8eb33853d39a2b24b2dd647898d65ac4fe0ae8dcf9a59cdd",
+        "0x18ceb45c7a6da96b750ae8b4eae07737f39ec4b9396f60d82c1d7797d3b3ef14",
+        "0x053bf0d678b6119c61565d2d949a0b5851344053af540908873a382612a89a4e",
+    ]
+    .map(|hex| FieldElement::from_hex(hex).unwrap())
+    .into_iter()
+    .chain([FieldElement::from(message)])
+    .collect::<Vec<_>>()
+    .try_into()
+    .unwrap()
+}
+
+#[test]
+fn test_eddsa_verify() {
+    for (input_values, expected) in [
+        (eddsa_signature(1234), true),
+        (eddsa_signature(1235), false),
+        // A public key which is not on the curve.
+        ([FieldElement::one(); 6], false),
+    ] {
+        let [public_key_x, public_key_y, signature_s, signature_r8_x, signature_r8_y, message] =
+            input_values;
+        let native = eddsa_verify(
+            &public_key_x,
+            &public_key_y,
+            &signature_s,
+            &signature_r8_x,
+            &signature_r8_y,
+            &message,
+        )
+        .unwrap();
+        assert_eq!(native, expected);
+        assert_eq!(solve_eddsa_verify_fallback(input_values), FieldElement::from(expected));
+    }
+}
+
+fn does_not_support_embedded_curve_ops(opcode: &Opcode) -> bool {
+    !matches!(
+        opcode,
+        Opcode::BlackBoxFuncCall(
+            BlackBoxFuncCall::EmbeddedCurveAdd { .. }
+                | BlackBoxFuncCall::VariableBaseScalarMul { .. }
+        )
+    )
+}
+
+/// Solves the fallback of the embedded curve operation built by `opcode` from its input and output witnesses,
+/// returning the values assigned to its outputs or the status of the solver if it could not be solved.
+fn solve_embedded_curve_fallback<const N: usize>(
+    input_values: [FieldElement; N],
+    opcode: impl FnOnce([FunctionInput; N], (Witness, Witness)) -> BlackBoxFuncCall,
+) -> Result<(FieldElement, FieldElement), ACVMStatus> {
+    let inputs = std::array::from_fn(|index| FunctionInput {
+        witness: Witness(index as u32 + 1),
+        num_bits: FieldElement::max_num_bits(),
+    });
+    let num_inputs = N as u32;
+    let outputs = (Witness(num_inputs + 1), Witness(num_inputs + 2));
+    let witness_assignments: BTreeMap<Witness, FieldElement> =
+        inputs.iter().map(|input| input.witness).zip(input_values).collect();
+
+    let circuit = Circuit {
+        current_witness_index: num_inputs + 2,
+        opcodes: vec![Opcode::BlackBoxFuncCall(opcode(inputs, outputs))],
+        // The inputs must be marked as parameters so that the compiler can reduce expressions which use them.
+        private_parameters: (1..=num_inputs).map(Witness).collect(),
+        ..Circuit::default()
+    };
+    let circuit =
+        compile(circuit, Language::PLONKCSat { width: 3 }, does_not_support_embedded_curve_ops)
+            .unwrap()
+            .0;
+    assert!(circuit.opcodes.iter().all(does_not_support_embedded_curve_ops));
+
+    let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+    match acvm.solve() {
+        ACVMStatus::Solved => {
+            let witness_map = acvm.finalize();
+            Ok((witness_map[&outputs.0], witness_map[&outputs.1]))
+        }
+        status => Err(status),
+    }
+}
+
+fn grumpkin_multiple(scalar: FieldElement) -> (FieldElement, FieldElement) {
+    GrumpkinSolver.fixed_base_scalar_mul(&scalar).unwrap()
+}
+
+#[test]
+fn test_embedded_curve_add() {
+    let infinity = (FieldElement::zero(), FieldElement::zero());
+    let generator = grumpkin_multiple(FieldElement::one());
+    let cases = [
+        (generator, grumpkin_multiple(FieldElement::from(2u128))),
+        (generator, generator),
+        (generator, (generator.0, -generator.1)),
+        (infinity, generator),
+        (generator, infinity),
+        (infinity, infinity),
+    ];
+    for (lhs, rhs) in cases {
+        let native = GrumpkinSolver.embedded_curve_add(&lhs.0, &lhs.1, &rhs.0, &rhs.1).unwrap();
+        let fallback = solve_embedded_curve_fallback(
+            [lhs.0, lhs.1, rhs.0, rhs.1],
+            |[input1_x, input1_y, input2_x, input2_y], outputs| {
+                BlackBoxFuncCall::EmbeddedCurveAdd {
+                    input1_x,
+                    input1_y,
+                    input2_x,
+                    input2_y,
+                    outputs,
+                }
+            },
+        );
+        assert_eq!(fallback, Ok(native));
+    }
+
+    // A point which is not on the curve.
+    let fallback = solve_embedded_curve_fallback(
+        [FieldElement::one(), FieldElement::one(), generator.0, generator.1],
+        |[input1_x, input1_y, input2_x, input2_y], outputs| BlackBoxFuncCall::EmbeddedCurveAdd {
+            input1_x,
+            input1_y,
+            input2_x,
+            input2_y,
+            outputs,
+        },
+    );
+    assert!(matches!(fallback, Err(ACVMStatus::Failure(_))));
+}
+
+#[test]
+fn test_variable_base_scalar_mul() {
+    let infinity = (FieldElement::zero(), FieldElement::zero());
+    let point = grumpkin_multiple(FieldElement::from(3u128));
+    let large_scalar = FieldElement::from_hex(
+        "0x1fa3b7e5d1c9b6d2c09a8b1e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a",
+    )
+    .unwrap();
+    let cases = [
+        (point, FieldElement::zero()),
+        (point, FieldElement::one()),
+        (point, FieldElement::from(17u128)),
+        (point, large_scalar),
+        (point, -FieldElement::one()),
+        (infinity, large_scalar),
+    ];
+    for (point, scalar) in cases {
+        let native = GrumpkinSolver.variable_base_scalar_mul(&point.0, &point.1, &scalar).unwrap();
+        let fallback = solve_embedded_curve_fallback(
+            [point.0, point.1, scalar],
+            |[point_x, point_y, scalar], outputs| BlackBoxFuncCall::VariableBaseScalarMul {
+                point_x,
+                point_y,
+                scalar,
+                outputs,
+            },
+        );
+        assert_eq!(fallback, Ok(native));
+    }
+}
+
+fn does_not_support_bigint_ops(opcode: &Opcode) -> bool {
+    !matches!(
+        opcode,
+        Opcode::BlackBoxFuncCall(
+            BlackBoxFuncCall::BigIntAdd { .. }
+                | BlackBoxFuncCall::BigIntMul { .. }
+                | BlackBoxFuncCall::BigIntMod { .. }
+        )
+    )
+}
+
+/// Solves the fallback of the big integer operation built by `opcode` from the bytes of its operands and modulus,
+/// returning the bytes assigned to its outputs or the status of the solver if it could not be solved.
+fn solve_bigint_fallback(
+    operands: &[&[u8]],
+    modulus: &[u8],
+    opcode: impl FnOnce(Vec<Vec<FunctionInput>>, Vec<FunctionInput>, Vec<Witness>) -> BlackBoxFuncCall,
+) -> Result<Vec<u8>, ACVMStatus> {
+    let mut witness_assignments = BTreeMap::new();
+    let mut byte_inputs = |bytes: &[u8]| -> Vec<FunctionInput> {
+        bytes
+            .iter()
+            .map(|byte| {
+                let witness = Witness(witness_assignments.len() as u32 + 1);
+                witness_assignments.insert(witness, FieldElement::from(*byte as u128));
+                FunctionInput { witness, num_bits: 8 }
+            })
+            .collect()
+    };
+    let operand_inputs: Vec<Vec<FunctionInput>> =
+        operands.iter().map(|operand| byte_inputs(operand)).collect();
+    let modulus_inputs = byte_inputs(modulus);
+    let num_inputs = witness_assignments.len() as u32;
+    let outputs: Vec<Witness> =
+        (1..=modulus.len() as u32).map(|index| Witness(num_inputs + index)).collect();
+
+    let circuit = Circuit {
+        current_witness_index: num_inputs + modulus.len() as u32,
+        opcodes: vec![Opcode::BlackBoxFuncCall(opcode(
+            operand_inputs,
+            modulus_inputs,
+            outputs.clone(),
+        ))],
+        // The inputs must be marked as parameters so that the compiler can reduce expressions which use them.
+        private_parameters: (1..=num_inputs).map(Witness).collect(),
+        ..Circuit::default()
+    };
+    let circuit =
+        compile(circuit, Language::PLONKCSat { width: 3 }, does_not_support_bigint_ops).unwrap().0;
+    assert!(circuit.opcodes.iter().all(does_not_support_bigint_ops));
+
+    let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+    match acvm.solve() {
+        ACVMStatus::Solved => {
+            let witness_map = acvm.finalize();
+            Ok(outputs
+                .iter()
+                .map(|output| *witness_map[output].to_be_bytes().last().unwrap())
+                .collect())
+        }
+        status => Err(status),
+    }
+}
+
+/// The base field modulus of secp256k1, `2^256 - 2^32 - 977`.
+fn secp256k1_modulus() -> Vec<u8> {
+    let mut modulus = vec![0xff; 32];
+    modulus[27] = 0xfe;
+    modulus[28..].copy_from_slice(&[0xff, 0xff, 0xfc, 0x2f]);
+    modulus
+}
+
+fn solve_bigint_add_fallback(
+    lhs: &[u8],
+    rhs: &[u8],
+    modulus: &[u8],
+) -> Result<Vec<u8>, ACVMStatus> {
+    solve_bigint_fallback(&[lhs, rhs], modulus, |mut operands, modulus, outputs| {
+        let rhs = operands.pop().unwrap();
+        let lhs = operands.pop().unwrap();
+        BlackBoxFuncCall::BigIntAdd { lhs, rhs, modulus, outputs }
+    })
+}
+
+fn solve_bigint_mul_fallback(
+    lhs: &[u8],
+    rhs: &[u8],
+    modulus: &[u8],
+) -> Result<Vec<u8>, ACVMStatus> {
+    solve_bigint_fallback(&[lhs, rhs], modulus, |mut operands, modulus, outputs| {
+        let rhs = operands.pop().unwrap();
+        let lhs = operands.pop().unwrap();
+        BlackBoxFuncCall::BigIntMul { lhs, rhs, modulus, outputs }
+    })
+}
+
+fn solve_bigint_mod_fallback(input: &[u8], modulus: &[u8]) -> Result<Vec<u8>, ACVMStatus> {
+    solve_bigint_fallback(&[input], modulus, |mut operands, modulus, outputs| {
+        BlackBoxFuncCall::BigIntMod { input: operands.pop().unwrap(), modulus, outputs }
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(3))]
+    #[test]
+    fn test_bigint_secp256k1(lhs in proptest::array::uniform32(any::<u8>()), rhs in proptest::array::uniform32(any::<u8>())) {
+        let modulus = secp256k1_modulus();
+        prop_assert_eq!(solve_bigint_add_fallback(&lhs, &rhs, &modulus), Ok(bigint_add(&lhs, &rhs, &modulus).unwrap()));
+        prop_assert_eq!(solve_bigint_mul_fallback(&lhs, &rhs, &modulus), Ok(bigint_mul(&lhs, &rhs, &modulus).unwrap()));
+        prop_assert_eq!(solve_bigint_mod_fallback(&lhs, &modulus), Ok(bigint_mod(&lhs, &modulus).unwrap()));
+    }
+
+    #[test]
+    fn test_bigint_mixed_lengths(
+        lhs in proptest::collection::vec(any::<u8>(), 0..6),
+        rhs in proptest::collection::vec(any::<u8>(), 0..6),
+        modulus in proptest::collection::vec(any::<u8>(), 1..4),
+    ) {
+        prop_assume!(modulus.iter().any(|byte| *byte != 0));
+        prop_assert_eq!(solve_bigint_add_fallback(&lhs, &rhs, &modulus), Ok(bigint_add(&lhs, &rhs, &modulus).unwrap()));
+        prop_assert_eq!(solve_bigint_mul_fallback(&lhs, &rhs, &modulus), Ok(bigint_mul(&lhs, &rhs, &modulus).unwrap()));
+        prop_assert_eq!(solve_bigint_mod_fallback(&lhs, &modulus), Ok(bigint_mod(&lhs, &modulus).unwrap()));
+    }
+}
+
+#[test]
+fn test_bigint_zero_modulus() {
+    let fallback = solve_bigint_mul_fallback(&[0x12, 0x34], &[0x56], &[0, 0]);
+    assert!(matches!(fallback, Err(ACVMStatus::Failure(_))));
+}
+
+#[test]
+fn test_bigint_outputs_not_matching_modulus() {
+    let byte = |index| FunctionInput { witness: Witness(index), num_bits: 8 };
+    let circuit = Circuit {
+        current_witness_index: 4,
+        opcodes: vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::BigIntMod {
+            input: vec![byte(1)],
+            modulus: vec![byte(2), byte(3)],
+            outputs: vec![Witness(4)],
+        })],
+        ..Circuit::default()
+    };
+    let error = compile(circuit, Language::PLONKCSat { width: 3 }, does_not_support_bigint_ops)
+        .unwrap_err();
+    assert!(matches!(error, CompileError::UnsupportedBlackBox(BlackBoxFunc::BigIntMod)));
+}
+
+fn does_not_support_aes128(opcode: &Opcode) -> bool {
+    !matches!(
+        opcode,
+        Opcode::BlackBoxFuncCall(
+            BlackBoxFuncCall::AES128CbcEncrypt { .. } | BlackBoxFuncCall::AES128CtrEncrypt { .. }
+        )
+    )
+}
+
+fn does_not_support_aes128_or_memory(opcode: &Opcode) -> bool {
+    does_not_support_aes128(opcode)
+        && !matches!(opcode, Opcode::MemoryInit { .. } | Opcode::MemoryOp { .. })
+}
+
+type Aes128Opcode = fn(
+    Vec<FunctionInput>,
+    Vec<FunctionInput>,
+    Vec<FunctionInput>,
+    Vec<Witness>,
+) -> BlackBoxFuncCall;
+
+const AES128_CBC: Aes128Opcode =
+    |inputs, iv, key, outputs| BlackBoxFuncCall::AES128CbcEncrypt { inputs, iv, key, outputs };
+const AES128_CTR: Aes128Opcode =
+    |inputs, iv, key, outputs| BlackBoxFuncCall::AES128CtrEncrypt { inputs, iv, key, outputs };
+
+fn solve_aes128_fallback(
+    opcode: Aes128Opcode,
+    inputs: &[u8],
+    iv: &[u8],
+    key: &[u8],
+    num_outputs: usize,
+    supports_memory: bool,
+) -> Result<Vec<u8>, ACVMStatus> {
+    let mut witness_assignments = BTreeMap::new();
+    let mut byte_inputs = |bytes: &[u8]| -> Vec<FunctionInput> {
+        bytes
+            .iter()
+            .map(|byte| {
+                let witness = Witness(witness_assignments.len() as u32 + 1);
+                witness_assignments.insert(witness, FieldElement::from(*byte as u128));
+                FunctionInput { witness, num_bits: 8 }
+            })
+            .collect()
+    };
+    let (inputs, iv, key) = (byte_inputs(inputs), byte_inputs(iv), byte_inputs(key));
+    let num_inputs = witness_assignments.len() as u32;
+    let outputs: Vec<Witness> =
+        (1..=num_outputs as u32).map(|index| Witness(num_inputs + index)).collect();
+
+    let circuit = Circuit {
+        current_witness_index: num_inputs + num_outputs as u32,
+        opcodes: vec![Opcode::BlackBoxFuncCall(opcode(inputs, iv, key, outputs.clone()))],
+        private_parameters: (1..=num_inputs).map(Witness).collect(),
+        ..Circuit::default()
+    };
+    let is_supported =
+        if supports_memory { does_not_support_aes128 } else { does_not_support_aes128_or_memory };
+    let circuit = compile(circuit, Language::PLONKCSat { width: 3 }, is_supported).unwrap().0;
+    assert!(circuit.opcodes.iter().all(is_supported));
+
+    let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+    match acvm.solve() {
+        ACVMStatus::Solved => {
+            let witness_map = acvm.finalize();
+            Ok(outputs
+                .iter()
+                .map(|output| *witness_map[output].to_be_bytes().last().unwrap())
+                .collect())
+        }
+        status => Err(status),
+    }
+}
+
+// Test vectors from NIST SP 800-38A, appendix F.
+const AES128_KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+];
+const AES128_PLAINTEXT: [u8; 32] = [
+    0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+    0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+];
+
+#[test]
+fn test_aes128_cbc_nist_vectors() {
+    let iv: Vec<u8> = (0..16).collect();
+    let ciphertext =
+        solve_aes128_fallback(AES128_CBC, &AES128_PLAINTEXT, &iv, &AES128_KEY, 48, true).unwrap();
+
+    assert_eq!(
+        ciphertext[..32],
+        [
+            0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9,
+            0x19, 0x7d, 0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, 0x95, 0xdb, 0x11, 0x3a,
+            0x91, 0x76, 0x78, 0xb2
+        ]
+    );
+    assert_eq!(ciphertext, aes128_cbc_encrypt(&AES128_PLAINTEXT, &iv, &AES128_KEY).unwrap());
+}
+
+#[test]
+fn test_aes128_ctr_nist_vectors() {
+    let iv: Vec<u8> = (0xf0..=0xff).collect();
+    let expected = [
+        0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6,
+        0xce, 0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff,
+        0xfd, 0xff,
+    ];
+
+    let ciphertext =
+        solve_aes128_fallback(AES128_CTR, &AES128_PLAINTEXT, &iv, &AES128_KEY, 32, true).unwrap();
+    assert_eq!(ciphertext, expected);
+
+    // The S-box is evaluated arithmetically when memory opcodes are not supported.
+    let ciphertext =
+        solve_aes128_fallback(AES128_CTR, &AES128_PLAINTEXT[..16], &iv, &AES128_KEY, 16, false)
+            .unwrap();
+    assert_eq!(ciphertext, expected[..16]);
+}
+
+#[test]
+fn test_aes128_ctr_counter_carry() {
+    // The counter wraps around from `2^128 - 1` to zero.
+    let iv = [0xff; 16];
+    let plaintext = [0x42; 40];
+    let ciphertext =
+        solve_aes128_fallback(AES128_CTR, &plaintext, &iv, &AES128_KEY, 40, true).unwrap();
+    assert_eq!(ciphertext, aes128_ctr_encrypt(&plaintext, &iv, &AES128_KEY).unwrap());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(3))]
+    #[test]
+    fn test_aes128_matches_native(
+        inputs in proptest::collection::vec(any::<u8>(), 0..40),
+        iv in proptest::collection::vec(any::<u8>(), 16),
+        key in proptest::collection::vec(any::<u8>(), 16),
+    ) {
+        let expected = aes128_cbc_encrypt(&inputs, &iv, &key).unwrap();
+        let ciphertext = solve_aes128_fallback(AES128_CBC, &inputs, &iv, &key, expected.len(), true);
+        prop_assert_eq!(ciphertext, Ok(expected));
+
+        let expected = aes128_ctr_encrypt(&inputs, &iv, &key).unwrap();
+        let ciphertext = solve_aes128_fallback(AES128_CTR, &inputs, &iv, &key, expected.len(), true);
+        prop_assert_eq!(ciphertext, Ok(expected));
+    }
+}
+
+#[test]
+fn test_ecdsa_secp256k1_recover_and_address() {
+    // The SHA256 hash of "hello" signed by the private key 1, whose public key is the generator.
+    let hashed_message: [u8; 32] = [
+        0x2c, 0xf2, 0x4d, 0xba, 0x5f, 0xb0, 0xa3, 0x0e, 0x26, 0xe8, 0x3b, 0x2a, 0xc5, 0xb9, 0xe2,
+        0x9e, 0x1b, 0x16, 0x1e, 0x5c, 0x1f, 0xa7, 0x42, 0x5e, 0x73, 0x04, 0x33, 0x62, 0x93, 0x8b,
+        0x98, 0x24,
+    ];
+    let signature: [u8; 64] = [
+        0x0f, 0x2f, 0xff, 0x86, 0x20, 0xd8, 0xff, 0xe9, 0x70, 0x40, 0xf8, 0xcf, 0x72, 0xae, 0x47,
+        0x6e, 0xf8, 0xff, 0x44, 0x12, 0x37, 0x39, 0x29, 0xc0, 0x32, 0x4c, 0xe8, 0x42, 0x8d, 0x33,
+        0x52, 0xe7, 0x18, 0x45, 0xae, 0x49, 0x03, 0x02, 0x76, 0x67, 0x00, 0x58, 0x46, 0xf8, 0xf0,
+        0xbe, 0x3e, 0x5e, 0xd2, 0xdb, 0x5c, 0x38, 0x26, 0xba, 0x83, 0xa6, 0xe5, 0x42, 0xe0, 0x80,
+        0x79, 0x2f, 0x9a, 0x9d,
+    ];
+    // 0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798
+    let generator_x: [u8; 32] = [
+        0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b,
+        0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8,
+        0x17, 0x98,
+    ];
+    // 0x7e5f4552091a69125d5dfcb7b8c2659029395bdf
+    let address: [u8; 20] = [
+        0x7e, 0x5f, 0x45, 0x52, 0x09, 0x1a, 0x69, 0x12, 0x5d, 0x5d, 0xfc, 0xb7, 0xb8, 0xc2, 0x65,
+        0x90, 0x29, 0x39, 0x5b, 0xdf,
+    ];
+
+    let mut witness_assignments = BTreeMap::new();
+    let mut byte_inputs = |bytes: &[u8]| -> Vec<FunctionInput> {
+        bytes
+            .iter()
+            .map(|byte| {
+                let witness = Witness(witness_assignments.len() as u32 + 1);
+                witness_assignments.insert(witness, FieldElement::from(*byte as u128));
+                FunctionInput { witness, num_bits: 8 }
+            })
+            .collect()
+    };
+    let hashed_message = byte_inputs(&hashed_message);
+    let signature = byte_inputs(&signature);
+    let recovery_id = byte_inputs(&[0])[0];
+    let num_inputs = witness_assignments.len() as u32;
+    let new_witnesses =
+        |start: u32, len: u32| -> Vec<Witness> { (start..start + len).map(Witness).collect() };
+    let public_key_x = new_witnesses(num_inputs + 1, 32);
+    let public_key_y = new_witnesses(num_inputs + 33, 32);
+    let address_witnesses = new_witnesses(num_inputs + 65, 20);
+
+    let (num_witness, address_opcodes) =
+        address_from_public_key(&public_key_x, &public_key_y, &address_witnesses, num_inputs + 85);
+    let mut opcodes = vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EcdsaSecp256k1Recover {
+        hashed_message,
+        signature,
+        recovery_id,
+        public_key_x: public_key_x.clone(),
+        public_key_y,
+    })];
+    opcodes.extend(address_opcodes);
+    let circuit = Circuit {
+        current_witness_index: num_witness - 1,
+        opcodes,
+        private_parameters: (1..=num_inputs).map(Witness).collect(),
+        ..Circuit::default()
+    };
+    // The recovered key cannot be bound to the recovery id in ACIR, so there is no fallback for recovery.
+    let does_not_support_recover = |opcode: &Opcode| {
+        !matches!(opcode, Opcode::BlackBoxFuncCall(BlackBoxFuncCall::EcdsaSecp256k1Recover { .. }))
+    };
+    let error =
+        compile(circuit.clone(), Language::PLONKCSat { width: 3 }, does_not_support_recover)
+            .unwrap_err();
+    assert!(matches!(
+        error,
+        CompileError::UnsupportedBlackBox(BlackBoxFunc::EcdsaSecp256k1Recover)
+    ));
+    let circuit = compile(circuit, Language::PLONKCSat { width: 3 }, |_| true).unwrap().0;
+
+    let mut acvm = ACVM::new(&StubbedBackend, circuit.opcodes, witness_assignments.into());
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    let witness_map = acvm.finalize();
+    let to_bytes = |witnesses: &[Witness]| -> Vec<u8> {
+        witnesses
+            .iter()
+            .map(|witness| *witness_map[witness].to_be_bytes().last().unwrap())
+            .collect()
+    };
+    assert_eq!(to_bytes(&public_key_x), generator_x);
+    assert_eq!(to_bytes(&address_witnesses), address);
+}
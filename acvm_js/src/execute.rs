@@ -80,6 +80,7 @@ pub async fn execute_circuit_with_black_box_solver(
                 let (assert_message, call_stack) = match &error {
                     OpcodeResolutionError::UnsatisfiedConstrain {
                         opcode_location: ErrorLocation::Resolved(opcode_location),
+                        ..
                     }
                     | OpcodeResolutionError::IndexOutOfBounds {
                         opcode_location: ErrorLocation::Resolved(opcode_location),
@@ -1,12 +1,12 @@
-use acvm::brillig_vm::brillig::{ForeignCallOutput, ForeignCallResult, Value};
+use acvm::brillig_vm::brillig::{ForeignCallParam, ForeignCallResult, Value};
 use wasm_bindgen::JsValue;
 
 use crate::js_witness_map::js_value_to_field_element;
 
-fn decode_foreign_call_output(output: JsValue) -> Result<ForeignCallOutput, String> {
+fn decode_foreign_call_output(output: JsValue) -> Result<ForeignCallParam, String> {
     if output.is_string() {
         let value = Value::from(js_value_to_field_element(output)?);
-        Ok(ForeignCallOutput::Single(value))
+        Ok(ForeignCallParam::Single(value))
     } else if output.is_array() {
         let output = js_sys::Array::from(&output);
 
@@ -14,7 +14,7 @@ fn decode_foreign_call_output(output: JsValue) -> Result<ForeignCallOutput, Stri
         for elem in output.iter() {
             values.push(Value::from(js_value_to_field_element(elem)?))
         }
-        Ok(ForeignCallOutput::Array(values))
+        Ok(ForeignCallParam::Array(values))
     } else {
         return Err("Non-string-or-array element in foreign_call_handler return".into());
     }
@@ -23,7 +23,7 @@ fn decode_foreign_call_output(output: JsValue) -> Result<ForeignCallOutput, Stri
 pub(super) fn decode_foreign_call_result(
     js_array: js_sys::Array,
 ) -> Result<ForeignCallResult, String> {
-    let mut values: Vec<ForeignCallOutput> = Vec::with_capacity(js_array.length() as usize);
+    let mut values: Vec<ForeignCallParam> = Vec::with_capacity(js_array.length() as usize);
     for elem in js_array.iter() {
         values.push(decode_foreign_call_output(elem)?);
     }
@@ -1,24 +1,9 @@
-use acvm::acir::{
-    circuit::Circuit,
-    native_types::{Witness, WitnessMap},
-};
+use acvm::acir::{circuit::Circuit, native_types::WitnessMap};
 use js_sys::JsString;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::JsWitnessMap;
 
-fn extract_indices(witness_map: &WitnessMap, indices: Vec<Witness>) -> Result<WitnessMap, String> {
-    let mut extracted_witness_map = WitnessMap::new();
-    for witness in indices {
-        let witness_value = witness_map.get(&witness).ok_or(format!(
-            "Failed to extract witness {} from witness map. Witness not found.",
-            witness.0
-        ))?;
-        extracted_witness_map.insert(witness, *witness_value);
-    }
-    Ok(extracted_witness_map)
-}
-
 /// Extracts a `WitnessMap` containing the witness indices corresponding to the circuit's return values.
 ///
 /// @param {Uint8Array} circuit - A serialized representation of an ACIR circuit
@@ -34,7 +19,7 @@ pub fn get_return_witness(
     let witness_map = WitnessMap::from(witness_map);
 
     let return_witness =
-        extract_indices(&witness_map, circuit.return_values.0.into_iter().collect())?;
+        circuit.return_values.witness_map(&witness_map).map_err(|err| err.to_string())?;
 
     Ok(JsWitnessMap::from(return_witness))
 }
@@ -54,7 +39,7 @@ pub fn get_public_parameters_witness(
     let witness_map = WitnessMap::from(solved_witness);
 
     let public_params_witness =
-        extract_indices(&witness_map, circuit.public_parameters.0.into_iter().collect())?;
+        circuit.public_parameters.witness_map(&witness_map).map_err(|err| err.to_string())?;
 
     Ok(JsWitnessMap::from(public_params_witness))
 }
@@ -74,7 +59,7 @@ pub fn get_public_witness(
     let witness_map = WitnessMap::from(solved_witness);
 
     let public_witness =
-        extract_indices(&witness_map, circuit.public_inputs().0.into_iter().collect())?;
+        circuit.public_inputs().witness_map(&witness_map).map_err(|err| err.to_string())?;
 
     Ok(JsWitnessMap::from(public_witness))
 }
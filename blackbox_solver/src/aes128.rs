@@ -0,0 +1,132 @@
+//! AES-128 encryption in the CBC and CTR modes of operation.
+//!
+//! CBC pads the plaintext to a whole number of blocks following PKCS#7, so the ciphertext is always longer than
+//! the plaintext. CTR treats the IV as the initial counter block, which is incremented as a 128-bit big-endian
+//! integer, and its ciphertext has as many bytes as the plaintext.
+
+use acir::BlackBoxFunc;
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes128,
+};
+
+use crate::BlackBoxResolutionError;
+
+/// The number of bytes in a block, key and IV of AES-128.
+pub const AES128_BLOCK_SIZE: usize = 16;
+
+/// Encrypts `inputs` using AES-128 in CBC mode, after padding them following PKCS#7.
+pub fn aes128_cbc_encrypt(
+    inputs: &[u8],
+    iv: &[u8],
+    key: &[u8],
+) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    let (cipher, iv) = init_cipher(iv, key, BlackBoxFunc::AES128CbcEncrypt)?;
+
+    let mut plaintext = inputs.to_vec();
+    let padding = AES128_BLOCK_SIZE - inputs.len() % AES128_BLOCK_SIZE;
+    plaintext.resize(inputs.len() + padding, padding as u8);
+
+    let mut chain = iv;
+    let mut ciphertext = Vec::with_capacity(plaintext.len());
+    for block in plaintext.chunks(AES128_BLOCK_SIZE) {
+        let mut state = GenericArray::from(chain);
+        state.iter_mut().zip(block).for_each(|(byte, input)| *byte ^= input);
+        cipher.encrypt_block(&mut state);
+        chain = state.into();
+        ciphertext.extend(chain);
+    }
+    Ok(ciphertext)
+}
+
+/// Encrypts `inputs` using AES-128 in CTR mode, where `iv` is the initial counter block.
+pub fn aes128_ctr_encrypt(
+    inputs: &[u8],
+    iv: &[u8],
+    key: &[u8],
+) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    let (cipher, iv) = init_cipher(iv, key, BlackBoxFunc::AES128CtrEncrypt)?;
+
+    let mut counter = u128::from_be_bytes(iv);
+    let mut ciphertext = Vec::with_capacity(inputs.len());
+    for block in inputs.chunks(AES128_BLOCK_SIZE) {
+        let mut keystream = GenericArray::from(counter.to_be_bytes());
+        cipher.encrypt_block(&mut keystream);
+        ciphertext.extend(block.iter().zip(keystream).map(|(input, key_byte)| input ^ key_byte));
+        counter = counter.wrapping_add(1);
+    }
+    Ok(ciphertext)
+}
+
+fn init_cipher(
+    iv: &[u8],
+    key: &[u8],
+    func: BlackBoxFunc,
+) -> Result<(Aes128, [u8; AES128_BLOCK_SIZE]), BlackBoxResolutionError> {
+    let iv: [u8; AES128_BLOCK_SIZE] = iv.try_into().map_err(|_| {
+        BlackBoxResolutionError::Failed(
+            func,
+            format!("expected an IV of {AES128_BLOCK_SIZE} bytes, found {}", iv.len()),
+        )
+    })?;
+    let cipher = Aes128::new_from_slice(key).map_err(|_| {
+        BlackBoxResolutionError::Failed(
+            func,
+            format!("expected a key of {AES128_BLOCK_SIZE} bytes, found {}", key.len()),
+        )
+    })?;
+    Ok((cipher, iv))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{aes128_cbc_encrypt, aes128_ctr_encrypt};
+
+    // Test vectors from NIST SP 800-38A, appendix F.
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ];
+    const PLAINTEXT: [u8; 32] = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17,
+        0x2a, 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf,
+        0x8e, 0x51,
+    ];
+
+    #[test]
+    fn cbc_matches_nist_test_vectors() {
+        let iv: Vec<u8> = (0..16).collect();
+        let ciphertext = aes128_cbc_encrypt(&PLAINTEXT, &iv, &KEY).unwrap();
+
+        // The final block only holds padding.
+        assert_eq!(ciphertext.len(), 48);
+        assert_eq!(
+            ciphertext[..32],
+            [
+                0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, 0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9,
+                0x19, 0x7d, 0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, 0x95, 0xdb, 0x11, 0x3a,
+                0x91, 0x76, 0x78, 0xb2
+            ]
+        );
+    }
+
+    #[test]
+    fn ctr_matches_nist_test_vectors() {
+        let iv: Vec<u8> = (0xf0..=0xff).collect();
+        let ciphertext = aes128_ctr_encrypt(&PLAINTEXT[..20], &iv, &KEY).unwrap();
+
+        assert_eq!(
+            ciphertext,
+            [
+                0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d,
+                0xb6, 0xce, 0x98, 0x06, 0xf6, 0x6b
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_key_and_iv() {
+        assert!(aes128_cbc_encrypt(&PLAINTEXT, &[0; 15], &KEY).is_err());
+        assert!(aes128_ctr_encrypt(&PLAINTEXT, &[0; 16], &[0; 24]).is_err());
+    }
+}
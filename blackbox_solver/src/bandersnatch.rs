@@ -0,0 +1,283 @@
+//! A native implementation of the operations over Bandersnatch, a twisted Edwards curve embedded in BLS12-381.
+//!
+//! Bandersnatch is defined over the scalar field of BLS12-381 so its points can be represented within ACIR
+//! using [`FieldElement`]s. As there is no existing backend to match, Pedersen generators are derived here by
+//! hashing to the curve and Schnorr signatures follow the construction used by Barretenberg over Grumpkin.
+
+use acir::{BlackBoxFunc, FieldElement};
+use ark_ec::{twisted_edwards::TECurveConfig, AffineRepr, CurveGroup};
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsConfig, EdwardsProjective, Fq, Fr};
+use ark_ff::{PrimeField, Zero};
+use blake2::{Blake2s256, Digest};
+
+use crate::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+
+/// The domain used when hashing to the curve to derive Pedersen generators.
+const GENERATOR_DOMAIN: &[u8] = b"acvm_bandersnatch_pedersen";
+
+/// Derives the Pedersen generator at `index` for the hash with `domain_separator`.
+///
+/// Candidate y-coordinates are produced by hashing the domain, separator, index and an attempt counter until
+/// one lies on the curve. The cofactor is then cleared so that the generator lies in the prime order subgroup.
+pub(crate) fn derive_generator(domain_separator: u32, index: u32) -> EdwardsAffine {
+    (0u32..)
+        .find_map(|attempt| {
+            let mut hasher = Blake2s256::new();
+            hasher.update(GENERATOR_DOMAIN);
+            hasher.update(domain_separator.to_le_bytes());
+            hasher.update(index.to_le_bytes());
+            hasher.update(attempt.to_le_bytes());
+            let y = Fq::from_le_bytes_mod_order(&hasher.finalize());
+
+            let point = EdwardsAffine::get_point_from_y_unchecked(y, false)?.mul_by_cofactor();
+            (!point.is_zero()).then_some(point)
+        })
+        .expect("a generator should be found before the attempt counter overflows")
+}
+
+/// Computes the Pedersen commitment to `inputs` using the generators for `domain_separator`.
+///
+/// Each input is split into a low and a high 128-bit limb, each with its own generator, as the base field of
+/// Bandersnatch is larger than its scalar field.
+pub(crate) fn pedersen_commit(inputs: &[Fq], domain_separator: u32) -> EdwardsAffine {
+    let mut commitment = EdwardsProjective::zero();
+    for (index, input) in inputs.iter().enumerate() {
+        let bytes = FieldElement::from_repr(*input).to_be_bytes();
+        let (high, low) = bytes.split_at(bytes.len() - 16);
+        let index = u32::try_from(index).expect("too many inputs to a pedersen hash");
+
+        commitment += derive_generator(domain_separator, 2 * index)
+            * Fr::from_be_bytes_mod_order(low)
+            + derive_generator(domain_separator, 2 * index + 1) * Fr::from_be_bytes_mod_order(high);
+    }
+    commitment.into_affine()
+}
+
+/// Computes the challenge `e = blake2s(pedersen(R.x, public_key.x, public_key.y) || message)` of a Schnorr signature.
+fn schnorr_challenge(r: &EdwardsAffine, public_key: &EdwardsAffine, message: &[u8]) -> Fr {
+    let compressed_keys = pedersen_commit(&[r.x, public_key.x, public_key.y], 0).x;
+
+    let mut hasher = Blake2s256::new();
+    hasher.update(FieldElement::from_repr(compressed_keys).to_be_bytes());
+    hasher.update(message);
+    Fr::from_be_bytes_mod_order(&hasher.finalize())
+}
+
+/// Verifies that `signature`, the big-endian encodings of `s` followed by `e`, is a signature over `message`
+/// by the owner of `public_key`, i.e. that `e == challenge(s * G + e * public_key, public_key, message)`.
+pub(crate) fn verify_schnorr_signature(
+    public_key: &EdwardsAffine,
+    signature: &[u8; 64],
+    message: &[u8],
+) -> bool {
+    if !public_key.is_on_curve() || !public_key.is_in_correct_subgroup_assuming_on_curve() {
+        return false;
+    }
+
+    let s = Fr::from_be_bytes_mod_order(&signature[0..32]);
+    let e = Fr::from_be_bytes_mod_order(&signature[32..64]);
+    if s.is_zero() || e.is_zero() {
+        return false;
+    }
+
+    let r = (EdwardsConfig::GENERATOR * s + *public_key * e).into_affine();
+    if r.is_zero() {
+        return false;
+    }
+
+    schnorr_challenge(&r, public_key, message) == e
+}
+
+/// Returns the coordinates of `point`, the point at infinity being represented as `(0, 1)`.
+fn to_coordinates(point: EdwardsAffine) -> (FieldElement, FieldElement) {
+    (FieldElement::from_repr(point.x), FieldElement::from_repr(point.y))
+}
+
+/// Returns the point with the given coordinates, the point at infinity being represented as `(0, 1)`.
+///
+/// Returns an error attributed to `func` unless the point lies in the prime order subgroup.
+fn from_coordinates(
+    x: &FieldElement,
+    y: &FieldElement,
+    func: BlackBoxFunc,
+) -> Result<EdwardsAffine, BlackBoxResolutionError> {
+    let point = EdwardsAffine::new_unchecked(x.into_repr(), y.into_repr());
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(BlackBoxResolutionError::Failed(
+            func,
+            format!("point ({}, {}) is not in the prime order subgroup", x.to_hex(), y.to_hex()),
+        ));
+    }
+    Ok(point)
+}
+
+/// A [`BlackBoxFunctionSolver`] for BLS12-381 which is implemented natively in Rust over the Bandersnatch curve.
+///
+/// Scalars passed to [fixed base scalar multiplication][BlackBoxFunc::FixedBaseScalarMul] are reduced modulo
+/// the order of the Bandersnatch prime order subgroup.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BandersnatchSolver;
+
+impl BlackBoxFunctionSolver for BandersnatchSolver {
+    fn schnorr_verify(
+        &self,
+        public_key_x: &FieldElement,
+        public_key_y: &FieldElement,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        let signature: &[u8; 64] = signature.try_into().map_err(|_| {
+            BlackBoxResolutionError::Failed(
+                BlackBoxFunc::SchnorrVerify,
+                format!("signature should be 64 bytes, found {}", signature.len()),
+            )
+        })?;
+        let public_key =
+            EdwardsAffine::new_unchecked(public_key_x.into_repr(), public_key_y.into_repr());
+        Ok(verify_schnorr_signature(&public_key, signature, message))
+    }
+
+    fn pedersen(
+        &self,
+        inputs: &[FieldElement],
+        domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        let inputs: Vec<Fq> = inputs.iter().map(|input| input.into_repr()).collect();
+        Ok(to_coordinates(pedersen_commit(&inputs, domain_separator)))
+    }
+
+    fn fixed_base_scalar_mul(
+        &self,
+        input: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        let scalar = Fr::from_be_bytes_mod_order(&input.to_be_bytes());
+        Ok(to_coordinates((EdwardsConfig::GENERATOR * scalar).into_affine()))
+    }
+
+    fn embedded_curve_add(
+        &self,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        let lhs = from_coordinates(input1_x, input1_y, BlackBoxFunc::EmbeddedCurveAdd)?;
+        let rhs = from_coordinates(input2_x, input2_y, BlackBoxFunc::EmbeddedCurveAdd)?;
+        Ok(to_coordinates((lhs + rhs).into_affine()))
+    }
+
+    fn variable_base_scalar_mul(
+        &self,
+        point_x: &FieldElement,
+        point_y: &FieldElement,
+        scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        let point = from_coordinates(point_x, point_y, BlackBoxFunc::VariableBaseScalarMul)?;
+        let scalar = Fr::from_be_bytes_mod_order(&scalar.to_be_bytes());
+        Ok(to_coordinates((point * scalar).into_affine()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use acir::FieldElement;
+    use ark_ec::{twisted_edwards::TECurveConfig, CurveGroup};
+    use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsConfig, Fr};
+    use ark_ff::{BigInteger, PrimeField};
+
+    use super::{
+        derive_generator, schnorr_challenge, verify_schnorr_signature, BandersnatchSolver,
+    };
+    use crate::BlackBoxFunctionSolver;
+
+    fn sign(private_key: Fr, nonce: Fr, message: &[u8]) -> (EdwardsAffine, [u8; 64]) {
+        let public_key = (EdwardsConfig::GENERATOR * private_key).into_affine();
+        let r = (EdwardsConfig::GENERATOR * nonce).into_affine();
+        let e = schnorr_challenge(&r, &public_key, message);
+        let s = nonce - e * private_key;
+
+        let mut signature = [0u8; 64];
+        signature[0..32].copy_from_slice(&s.into_bigint().to_bytes_be());
+        signature[32..64].copy_from_slice(&e.into_bigint().to_bytes_be());
+        (public_key, signature)
+    }
+
+    #[test]
+    fn derived_generators_are_in_prime_order_subgroup() {
+        let generators = [derive_generator(0, 0), derive_generator(0, 1), derive_generator(1, 0)];
+        for generator in generators {
+            assert!(generator.is_on_curve());
+            assert!(generator.is_in_correct_subgroup_assuming_on_curve());
+            assert!(!generator.is_zero());
+        }
+        assert_ne!(generators[0], generators[1]);
+        assert_ne!(generators[0], generators[2]);
+        assert_eq!(generators[0], derive_generator(0, 0));
+    }
+
+    #[test]
+    fn pedersen_depends_on_inputs_and_domain_separator() {
+        let inputs = [FieldElement::zero(), FieldElement::one()];
+        let hash = BandersnatchSolver.pedersen(&inputs, 0).unwrap();
+
+        assert_eq!(hash, BandersnatchSolver.pedersen(&inputs, 0).unwrap());
+        assert_ne!(hash, BandersnatchSolver.pedersen(&inputs, 1).unwrap());
+        assert_ne!(hash, BandersnatchSolver.pedersen(&[FieldElement::one()], 0).unwrap());
+        // Inputs which are equal modulo the order of the scalar field must not collide.
+        let large_input = FieldElement::from_be_bytes_reduce(&Fr::MODULUS.to_bytes_be());
+        assert_ne!(
+            BandersnatchSolver.pedersen(&[large_input], 0).unwrap(),
+            BandersnatchSolver.pedersen(&[FieldElement::zero()], 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn fixed_base_scalar_mul_multiplies_generator() {
+        let (x, y) = BandersnatchSolver.fixed_base_scalar_mul(&FieldElement::one()).unwrap();
+        assert_eq!(
+            (x.into_repr(), y.into_repr()),
+            (EdwardsConfig::GENERATOR.x, EdwardsConfig::GENERATOR.y)
+        );
+
+        let (x, y) = BandersnatchSolver.fixed_base_scalar_mul(&FieldElement::zero()).unwrap();
+        assert_eq!((x, y), (FieldElement::zero(), FieldElement::one()));
+    }
+
+    #[test]
+    fn embedded_curve_operations_match_fixed_base_scalar_mul() {
+        let solver = BandersnatchSolver;
+        let (x, y) = solver.fixed_base_scalar_mul(&FieldElement::one()).unwrap();
+        let (two_x, two_y) = solver.fixed_base_scalar_mul(&FieldElement::from(2u128)).unwrap();
+
+        assert_eq!(solver.embedded_curve_add(&x, &y, &x, &y).unwrap(), (two_x, two_y));
+        assert_eq!(
+            solver.variable_base_scalar_mul(&two_x, &two_y, &FieldElement::from(21u128)).unwrap(),
+            solver.fixed_base_scalar_mul(&FieldElement::from(42u128)).unwrap()
+        );
+        assert!(solver
+            .embedded_curve_add(&FieldElement::one(), &FieldElement::one(), &x, &y)
+            .is_err());
+    }
+
+    #[test]
+    fn verifies_schnorr_signatures() {
+        let message = b"hello world";
+        let (public_key, signature) = sign(Fr::from(42u64), Fr::from(1337u64), message);
+        assert!(verify_schnorr_signature(&public_key, &signature, message));
+        assert!(!verify_schnorr_signature(&public_key, &signature, b"hello worle"));
+
+        let mut tampered_signature = signature;
+        tampered_signature[31] ^= 1;
+        assert!(!verify_schnorr_signature(&public_key, &tampered_signature, message));
+
+        let valid = BandersnatchSolver
+            .schnorr_verify(
+                &FieldElement::from_repr(public_key.x),
+                &FieldElement::from_repr(public_key.y),
+                &signature,
+                message,
+            )
+            .unwrap();
+        assert!(valid);
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Mutex, PoisonError};
+
 use acir::{BlackBoxFunc, FieldElement};
 
 use crate::{BlackBoxFunctionSolver, BlackBoxResolutionError};
@@ -45,16 +48,103 @@ impl BlackBoxFunctionSolver for BarretenbergSolver {
         signature: &[u8],
         message: &[u8],
     ) -> Result<bool, BlackBoxResolutionError> {
-        let pub_key_bytes: Vec<u8> =
-            public_key_x.to_be_bytes().iter().copied().chain(public_key_y.to_be_bytes()).collect();
+        schnorr_verify(&self.blackbox_vendor, public_key_x, public_key_y, signature, message)
+    }
 
-        let pub_key: [u8; 64] = pub_key_bytes.try_into().unwrap();
-        let sig_s: [u8; 32] = signature[0..32].try_into().unwrap();
-        let sig_e: [u8; 32] = signature[32..64].try_into().unwrap();
+    fn pedersen(
+        &self,
+        inputs: &[FieldElement],
+        domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        pedersen(&self.blackbox_vendor, inputs, domain_separator)
+    }
 
-        #[allow(deprecated)]
-        self.blackbox_vendor.verify_signature(pub_key, sig_s, sig_e, message).map_err(|err| {
-            BlackBoxResolutionError::Failed(BlackBoxFunc::SchnorrVerify, err.to_string())
+    fn fixed_base_scalar_mul(
+        &self,
+        input: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        fixed_base_scalar_mul(&self.blackbox_vendor, input)
+    }
+
+    fn embedded_curve_add(
+        &self,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        embedded_curve_add(input1_x, input1_y, input2_x, input2_y)
+    }
+
+    fn variable_base_scalar_mul(
+        &self,
+        point_x: &FieldElement,
+        point_y: &FieldElement,
+        scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        variable_base_scalar_mul(point_x, point_y, scalar)
+    }
+}
+
+/// A [`BlackBoxFunctionSolver`] backed by Barretenberg which is `Send + Sync` and so may be shared between threads.
+///
+/// A Barretenberg instance can only be used by a single thread at a time, so the pool hands out an idle instance
+/// for each black box function call, creating a new instance whenever all existing instances are in use.
+/// The number of instances therefore grows to the number of threads solving black box functions concurrently.
+#[cfg(not(target_arch = "wasm32"))]
+#[deprecated = "The `BarretenbergSolverPool` is a temporary solution and will be removed in future."]
+#[derive(Debug)]
+pub struct BarretenbergSolverPool {
+    idle_instances: Mutex<Vec<Barretenberg>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(deprecated)]
+impl BarretenbergSolverPool {
+    /// Creates a pool containing a single Barretenberg instance.
+    pub fn new() -> BarretenbergSolverPool {
+        Self::with_instances(1)
+    }
+
+    /// Creates a pool containing `instances` Barretenberg instances, avoiding the cost of instantiating them
+    /// on first use when the expected number of concurrent callers is known in advance.
+    pub fn with_instances(instances: usize) -> BarretenbergSolverPool {
+        let idle_instances = (0..instances).map(|_| Barretenberg::new()).collect();
+        BarretenbergSolverPool { idle_instances: Mutex::new(idle_instances) }
+    }
+
+    /// Calls `f` with an instance which is not in use by any other thread, returning it to the pool afterwards.
+    fn with_instance<T>(&self, f: impl FnOnce(&Barretenberg) -> T) -> T {
+        // An instance is never shared while it is in use so a panic elsewhere cannot leave the pool inconsistent.
+        let idle_instance =
+            self.idle_instances.lock().unwrap_or_else(PoisonError::into_inner).pop();
+        let instance = idle_instance.unwrap_or_else(Barretenberg::new);
+        let result = f(&instance);
+        self.idle_instances.lock().unwrap_or_else(PoisonError::into_inner).push(instance);
+        result
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(deprecated)]
+impl Default for BarretenbergSolverPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(deprecated)]
+impl BlackBoxFunctionSolver for BarretenbergSolverPool {
+    fn schnorr_verify(
+        &self,
+        public_key_x: &FieldElement,
+        public_key_y: &FieldElement,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        self.with_instance(|instance| {
+            schnorr_verify(instance, public_key_x, public_key_y, signature, message)
         })
     }
 
@@ -63,19 +153,118 @@ impl BlackBoxFunctionSolver for BarretenbergSolver {
         inputs: &[FieldElement],
         domain_separator: u32,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
-        #[allow(deprecated)]
-        self.blackbox_vendor
-            .encrypt(inputs.to_vec(), domain_separator)
-            .map_err(|err| BlackBoxResolutionError::Failed(BlackBoxFunc::Pedersen, err.to_string()))
+        self.with_instance(|instance| pedersen(instance, inputs, domain_separator))
     }
 
     fn fixed_base_scalar_mul(
         &self,
         input: &FieldElement,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
-        #[allow(deprecated)]
-        self.blackbox_vendor.fixed_base(input).map_err(|err| {
-            BlackBoxResolutionError::Failed(BlackBoxFunc::FixedBaseScalarMul, err.to_string())
-        })
+        self.with_instance(|instance| fixed_base_scalar_mul(instance, input))
+    }
+
+    fn embedded_curve_add(
+        &self,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        embedded_curve_add(input1_x, input1_y, input2_x, input2_y)
+    }
+
+    fn variable_base_scalar_mul(
+        &self,
+        point_x: &FieldElement,
+        point_y: &FieldElement,
+        scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        variable_base_scalar_mul(point_x, point_y, scalar)
+    }
+}
+
+fn schnorr_verify(
+    blackbox_vendor: &Barretenberg,
+    public_key_x: &FieldElement,
+    public_key_y: &FieldElement,
+    signature: &[u8],
+    message: &[u8],
+) -> Result<bool, BlackBoxResolutionError> {
+    let pub_key_bytes: Vec<u8> =
+        public_key_x.to_be_bytes().iter().copied().chain(public_key_y.to_be_bytes()).collect();
+
+    let pub_key: [u8; 64] = pub_key_bytes.try_into().unwrap();
+    let sig_s: [u8; 32] = signature[0..32].try_into().unwrap();
+    let sig_e: [u8; 32] = signature[32..64].try_into().unwrap();
+
+    #[allow(deprecated)]
+    blackbox_vendor.verify_signature(pub_key, sig_s, sig_e, message).map_err(|err| {
+        BlackBoxResolutionError::Failed(BlackBoxFunc::SchnorrVerify, err.to_string())
+    })
+}
+
+fn pedersen(
+    blackbox_vendor: &Barretenberg,
+    inputs: &[FieldElement],
+    domain_separator: u32,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    #[allow(deprecated)]
+    blackbox_vendor
+        .encrypt(inputs.to_vec(), domain_separator)
+        .map_err(|err| BlackBoxResolutionError::Failed(BlackBoxFunc::Pedersen, err.to_string()))
+}
+
+fn fixed_base_scalar_mul(
+    blackbox_vendor: &Barretenberg,
+    input: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    #[allow(deprecated)]
+    blackbox_vendor.fixed_base(input).map_err(|err| {
+        BlackBoxResolutionError::Failed(BlackBoxFunc::FixedBaseScalarMul, err.to_string())
+    })
+}
+
+// Barretenberg does not expose operations over arbitrary points so these are computed natively over Grumpkin.
+
+fn embedded_curve_add(
+    input1_x: &FieldElement,
+    input1_y: &FieldElement,
+    input2_x: &FieldElement,
+    input2_y: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    #[cfg(feature = "bn254")]
+    {
+        crate::grumpkin::embedded_curve_add((input1_x, input1_y), (input2_x, input2_y))
+    }
+    #[cfg(not(feature = "bn254"))]
+    {
+        let _ = (input1_x, input1_y, input2_x, input2_y);
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::EmbeddedCurveAdd))
+    }
+}
+
+fn variable_base_scalar_mul(
+    point_x: &FieldElement,
+    point_y: &FieldElement,
+    scalar: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    #[cfg(feature = "bn254")]
+    {
+        crate::grumpkin::variable_base_scalar_mul((point_x, point_y), scalar)
+    }
+    #[cfg(not(feature = "bn254"))]
+    {
+        let _ = (point_x, point_y, scalar);
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::VariableBaseScalarMul))
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    #[test]
+    #[allow(deprecated)]
+    fn solver_pool_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<super::BarretenbergSolverPool>();
     }
 }
@@ -74,7 +74,8 @@ use wasmer::{
 /// the written data will begin to corrupt the stack.
 ///
 /// Using this scratch space isn't particularly safe if we have multiple threads interacting with the wasm however,
-/// each thread could write to the same pointer address simultaneously.
+/// each thread could write to the same pointer address simultaneously. A [`Barretenberg`] instance is therefore not `Sync`
+/// and threads must each use their own instance, e.g. through a [`BarretenbergSolverPool`][crate::BarretenbergSolverPool].
 pub(super) const WASM_SCRATCH_BYTES: usize = 1024;
 
 /// Embed the Barretenberg WASM file
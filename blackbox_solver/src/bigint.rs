@@ -0,0 +1,115 @@
+//! Arithmetic over big integers modulo a modulus which is only known at runtime, such as an RSA modulus or the
+//! base field of a curve which is not native to ACIR.
+//!
+//! Integers are represented by their big-endian bytes. Results are reduced modulo the modulus and are returned
+//! with as many bytes as the modulus.
+
+use acir::BlackBoxFunc;
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::{BlackBoxResolutionError, BIGINT_DIV_MOD};
+
+/// Computes `(lhs + rhs) % modulus`.
+pub fn bigint_add(
+    lhs: &[u8],
+    rhs: &[u8],
+    modulus: &[u8],
+) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    let modulus_value = parse_modulus(modulus, BlackBoxFunc::BigIntAdd.name())?;
+    let sum = BigUint::from_bytes_be(lhs) + BigUint::from_bytes_be(rhs);
+    Ok(to_be_bytes_padded(&(sum % modulus_value), modulus.len()))
+}
+
+/// Computes `(lhs * rhs) % modulus`.
+pub fn bigint_mul(
+    lhs: &[u8],
+    rhs: &[u8],
+    modulus: &[u8],
+) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    let modulus_value = parse_modulus(modulus, BlackBoxFunc::BigIntMul.name())?;
+    let product = BigUint::from_bytes_be(lhs) * BigUint::from_bytes_be(rhs);
+    Ok(to_be_bytes_padded(&(product % modulus_value), modulus.len()))
+}
+
+/// Computes `input % modulus`.
+pub fn bigint_mod(input: &[u8], modulus: &[u8]) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    let modulus_value = parse_modulus(modulus, BlackBoxFunc::BigIntMod.name())?;
+    Ok(to_be_bytes_padded(&(BigUint::from_bytes_be(input) % modulus_value), modulus.len()))
+}
+
+/// Computes the quotient and remainder of `lhs / rhs`, which have as many bytes as `lhs` and `rhs` respectively.
+pub fn bigint_div_mod(
+    lhs: &[u8],
+    rhs: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), BlackBoxResolutionError> {
+    let lhs_value = BigUint::from_bytes_be(lhs);
+    let rhs_value = parse_modulus(rhs, BIGINT_DIV_MOD)?;
+    Ok((
+        to_be_bytes_padded(&(&lhs_value / &rhs_value), lhs.len()),
+        to_be_bytes_padded(&(lhs_value % rhs_value), rhs.len()),
+    ))
+}
+
+fn parse_modulus(modulus: &[u8], name: &str) -> Result<BigUint, BlackBoxResolutionError> {
+    let modulus = BigUint::from_bytes_be(modulus);
+    if modulus.is_zero() {
+        return Err(BlackBoxResolutionError::failed(name, "modulus must be non-zero".to_string()));
+    }
+    Ok(modulus)
+}
+
+/// Returns the big-endian bytes of `value` left-padded with zeros to `len` bytes, where `value` must fit.
+fn to_be_bytes_padded(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = if value.is_zero() { Vec::new() } else { value.to_bytes_be() };
+    assert!(bytes.len() <= len, "value should fit within {len} bytes");
+    let mut padded = vec![0; len - bytes.len()];
+    padded.extend(bytes);
+    padded
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bigint_add, bigint_div_mod, bigint_mod, bigint_mul};
+
+    /// The base field modulus of secp256k1, `2^256 - 2^32 - 977`.
+    fn secp256k1_modulus() -> Vec<u8> {
+        let mut modulus = vec![0xff; 32];
+        modulus[27] = 0xfe;
+        modulus[28..].copy_from_slice(&[0xff, 0xff, 0xfc, 0x2f]);
+        modulus
+    }
+
+    #[test]
+    fn wraps_around_modulus() {
+        let modulus = secp256k1_modulus();
+        let mut minus_one = modulus.clone();
+        minus_one[31] -= 1;
+
+        let mut expected = vec![0; 32];
+        expected[31] = 1;
+        assert_eq!(bigint_add(&minus_one, &[2], &modulus).unwrap(), expected);
+        // (-1) * (-1) = 1
+        assert_eq!(bigint_mul(&minus_one, &minus_one, &modulus).unwrap(), expected);
+        assert_eq!(bigint_mod(&modulus, &modulus).unwrap(), vec![0; 32]);
+    }
+
+    #[test]
+    fn results_have_the_width_of_the_modulus() {
+        assert_eq!(bigint_mul(&[0x12, 0x34], &[0x56], &[0, 0, 0x65]).unwrap(), vec![0, 0, 0x5d]);
+        assert_eq!(bigint_mod(&[0x01, 0x00, 0x00], &[0x01, 0x00]).unwrap(), vec![0, 0]);
+    }
+
+    #[test]
+    fn divides_with_remainder() {
+        // 1000 = 7 * 142 + 6
+        assert_eq!(bigint_div_mod(&[0x03, 0xe8], &[7]).unwrap(), (vec![0, 142], vec![6]));
+    }
+
+    #[test]
+    fn rejects_zero_modulus() {
+        assert!(bigint_add(&[1], &[1], &[0, 0]).is_err());
+        assert!(bigint_mod(&[1], &[]).is_err());
+        assert!(bigint_div_mod(&[1], &[0]).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Verification of EdDSA signatures over Baby JubJub, as implemented by circomlib.
+//!
+//! Baby JubJub is the twisted Edwards curve `168700x^2 + y^2 = 1 + 168696x^2y^2` over the scalar field of BN254.
+//! A signature `(R8, S)` over the message `M` is valid for the public key `A` when `S * B8 = R8 + 8 * H(R8, A, M) * A`,
+//! where `B8` generates the prime order subgroup of the curve and `H` is the [Poseidon hash][PoseidonConfig::hash].
+
+use acir::FieldElement;
+
+use crate::PoseidonConfig;
+
+/// The coefficient `a` of the twisted Edwards equation of Baby JubJub.
+pub const BABY_JUBJUB_A: u128 = 168700;
+/// The coefficient `d` of the twisted Edwards equation of Baby JubJub.
+pub const BABY_JUBJUB_D: u128 = 168696;
+
+/// The order of the subgroup generated by [`BabyJubJubPoint::base8`].
+const SUBGROUP_ORDER: &str = "0x060c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1";
+
+/// A point on Baby JubJub in affine coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BabyJubJubPoint {
+    pub x: FieldElement,
+    pub y: FieldElement,
+}
+
+impl BabyJubJubPoint {
+    /// Returns the identity of the curve, `(0, 1)`.
+    pub fn identity() -> Self {
+        BabyJubJubPoint { x: FieldElement::zero(), y: FieldElement::one() }
+    }
+
+    /// Returns `B8`, the generator of the prime order subgroup used by circomlib.
+    pub fn base8() -> Self {
+        BabyJubJubPoint {
+            x: FieldElement::from_hex(
+                "0x0bb77a6ad63e739b4eacb2e09d6277c12ab8d8010534e0b62893f3f6bb957051",
+            )
+            .unwrap(),
+            y: FieldElement::from_hex(
+                "0x25797203f7a0b24925572e1cd16bf9edfce0051fb9e133774b3c257a872d7d8b",
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let x2 = self.x * self.x;
+        let y2 = self.y * self.y;
+        FieldElement::from(BABY_JUBJUB_A) * x2 + y2
+            == FieldElement::one() + FieldElement::from(BABY_JUBJUB_D) * x2 * y2
+    }
+
+    /// Adds two points using the twisted Edwards addition law, which is complete on Baby JubJub.
+    pub fn add(&self, other: &BabyJubJubPoint) -> BabyJubJubPoint {
+        let x1x2 = self.x * other.x;
+        let y1y2 = self.y * other.y;
+        let dx1x2y1y2 = FieldElement::from(BABY_JUBJUB_D) * x1x2 * y1y2;
+        BabyJubJubPoint {
+            x: (self.x * other.y + self.y * other.x) / (FieldElement::one() + dx1x2y1y2),
+            y: (y1y2 - FieldElement::from(BABY_JUBJUB_A) * x1x2)
+                / (FieldElement::one() - dx1x2y1y2),
+        }
+    }
+
+    /// Multiplies the point by the integer representation of `scalar`.
+    pub fn mul(&self, scalar: &FieldElement) -> BabyJubJubPoint {
+        scalar.bits().into_iter().fold(BabyJubJubPoint::identity(), |result, bit| {
+            let result = result.add(&result);
+            if bit {
+                result.add(self)
+            } else {
+                result
+            }
+        })
+    }
+}
+
+/// Returns the order of the subgroup generated by [`BabyJubJubPoint::base8`].
+pub fn baby_jubjub_subgroup_order() -> FieldElement {
+    FieldElement::from_hex(SUBGROUP_ORDER).unwrap()
+}
+
+/// Verifies a signature as circomlib's `verifyPoseidon` does.
+pub(crate) fn verify_eddsa_signature(
+    public_key: BabyJubJubPoint,
+    signature_s: &FieldElement,
+    signature_r8: BabyJubJubPoint,
+    message: &FieldElement,
+) -> bool {
+    if !public_key.is_on_curve() || !signature_r8.is_on_curve() {
+        return false;
+    }
+    if *signature_s >= baby_jubjub_subgroup_order() {
+        return false;
+    }
+    // Public keys of small order, for which `8 * A` is the identity, are rejected.
+    let public_key8 = public_key.mul(&FieldElement::from(8u128));
+    if public_key8.x.is_zero() {
+        return false;
+    }
+
+    let hash = PoseidonConfig::bn254().hash(&[
+        signature_r8.x,
+        signature_r8.y,
+        public_key.x,
+        public_key.y,
+        *message,
+    ]);
+    BabyJubJubPoint::base8().mul(signature_s) == signature_r8.add(&public_key8.mul(&hash))
+}
+
+#[cfg(test)]
+mod test {
+    use acir::FieldElement;
+
+    use super::{baby_jubjub_subgroup_order, verify_eddsa_signature, BabyJubJubPoint};
+
+    fn point(x: &str, y: &str) -> BabyJubJubPoint {
+        BabyJubJubPoint {
+            x: FieldElement::from_hex(x).unwrap(),
+            y: FieldElement::from_hex(y).unwrap(),
+        }
+    }
+
+    // The key pair and Poseidon signature of the message 1234 from circomlibjs' EdDSA tests, where the private key is
+    // the bytes `0x0001020304050607080900010203040506070809000102030405060708090001`.
+    fn public_key() -> BabyJubJubPoint {
+        point(
+            "0x1d5ac1f31407018b7d413a4f52c8f74463b30e6ac2238220ad8b254de4eaa3a2",
+            "0x1e1de8a908826c3f9ac2e0ceee929ecd0caf3b99b3ef24523aaab796a6f733c4",
+        )
+    }
+
+    fn signature() -> (FieldElement, BabyJubJubPoint) {
+        let s = FieldElement::from_hex(
+            "0x0471b36e0376770c8eb33853d39a2b24b2dd647898d65ac4fe0ae8dcf9a59cdd",
+        )
+        .unwrap();
+        let r8 = point(
+            "0x18ceb45c7a6da96b750ae8b4eae07737f39ec4b9396f60d82c1d7797d3b3ef14",
+            "0x053bf0d678b6119c61565d2d949a0b5851344053af540908873a382612a89a4e",
+        );
+        (s, r8)
+    }
+
+    #[test]
+    fn base8_generates_prime_order_subgroup() {
+        let base8 = BabyJubJubPoint::base8();
+        assert!(base8.is_on_curve());
+        assert_eq!(base8.mul(&baby_jubjub_subgroup_order()), BabyJubJubPoint::identity());
+    }
+
+    #[test]
+    fn verifies_valid_signature() {
+        let (s, r8) = signature();
+        assert!(verify_eddsa_signature(public_key(), &s, r8, &FieldElement::from(1234u128)));
+    }
+
+    #[test]
+    fn rejects_signature_over_different_message() {
+        let (s, r8) = signature();
+        assert!(!verify_eddsa_signature(public_key(), &s, r8, &FieldElement::from(1235u128)));
+    }
+
+    #[test]
+    fn rejects_non_canonical_scalar() {
+        let (s, r8) = signature();
+        let s = s + baby_jubjub_subgroup_order();
+        assert!(!verify_eddsa_signature(public_key(), &s, r8, &FieldElement::from(1234u128)));
+    }
+}
@@ -0,0 +1,240 @@
+//! A native implementation of the operations over Grumpkin, the curve embedded in BN254.
+//!
+//! Grumpkin is defined by `y^2 = x^3 - 17` over the scalar field of BN254, so its points can be represented
+//! within ACIR using [`FieldElement`]s.
+
+use acir::{BlackBoxFunc, FieldElement};
+use ark_bn254::{Fq, Fr};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveConfig, CurveGroup,
+};
+use ark_ff::{Field, MontFp, PrimeField};
+
+use crate::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+
+/// The parameters of the Grumpkin curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrumpkinParameters;
+
+pub type GrumpkinAffine = Affine<GrumpkinParameters>;
+pub type GrumpkinProjective = Projective<GrumpkinParameters>;
+
+impl CurveConfig for GrumpkinParameters {
+    type BaseField = Fr;
+    type ScalarField = Fq;
+
+    const COFACTOR: &'static [u64] = &[1];
+    const COFACTOR_INV: Fq = Fq::ONE;
+}
+
+impl SWCurveConfig for GrumpkinParameters {
+    const COEFF_A: Fr = Fr::ZERO;
+    const COEFF_B: Fr = MontFp!("-17");
+
+    /// The generator used by Barretenberg, `(1, sqrt(-16))`.
+    const GENERATOR: GrumpkinAffine = Affine::new_unchecked(
+        MontFp!("1"),
+        MontFp!("17631683881184975370165255887551781615748388533673675138860"),
+    );
+
+    #[inline(always)]
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Fr::ZERO
+    }
+}
+
+/// Returns the coordinates of `point`, with the point at infinity represented as `(0, 0)` as in Barretenberg.
+pub(crate) fn to_coordinates(point: GrumpkinAffine) -> (FieldElement, FieldElement) {
+    match point.xy() {
+        Some((x, y)) => (FieldElement::from_repr(*x), FieldElement::from_repr(*y)),
+        None => (FieldElement::zero(), FieldElement::zero()),
+    }
+}
+
+/// Returns the point with the given coordinates, where `(0, 0)` represents the point at infinity.
+///
+/// Returns an error attributed to `func` if the point does not lie on the curve.
+pub(crate) fn from_coordinates(
+    x: &FieldElement,
+    y: &FieldElement,
+    func: BlackBoxFunc,
+) -> Result<GrumpkinAffine, BlackBoxResolutionError> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(GrumpkinAffine::identity());
+    }
+    let point = GrumpkinAffine::new_unchecked(x.into_repr(), y.into_repr());
+    if !point.is_on_curve() {
+        return Err(BlackBoxResolutionError::Failed(
+            func,
+            format!("point ({}, {}) is not on the curve", x.to_hex(), y.to_hex()),
+        ));
+    }
+    Ok(point)
+}
+
+/// Converts `scalar` into an element of the Grumpkin scalar field.
+fn to_scalar(scalar: &FieldElement) -> Fq {
+    // The BN254 scalar field is smaller than the Grumpkin scalar field so this conversion never reduces.
+    Fq::from_be_bytes_mod_order(&scalar.to_be_bytes())
+}
+
+/// Multiplies the Grumpkin generator by `scalar`.
+pub(crate) fn fixed_base_scalar_mul(scalar: &FieldElement) -> (FieldElement, FieldElement) {
+    to_coordinates((GrumpkinAffine::generator() * to_scalar(scalar)).into_affine())
+}
+
+/// Adds two points on Grumpkin.
+pub(crate) fn embedded_curve_add(
+    input1: (&FieldElement, &FieldElement),
+    input2: (&FieldElement, &FieldElement),
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let lhs = from_coordinates(input1.0, input1.1, BlackBoxFunc::EmbeddedCurveAdd)?;
+    let rhs = from_coordinates(input2.0, input2.1, BlackBoxFunc::EmbeddedCurveAdd)?;
+    Ok(to_coordinates((lhs + rhs).into_affine()))
+}
+
+/// Multiplies a point on Grumpkin by `scalar`.
+pub(crate) fn variable_base_scalar_mul(
+    point: (&FieldElement, &FieldElement),
+    scalar: &FieldElement,
+) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+    let point = from_coordinates(point.0, point.1, BlackBoxFunc::VariableBaseScalarMul)?;
+    Ok(to_coordinates((point * to_scalar(scalar)).into_affine()))
+}
+
+/// A [`BlackBoxFunctionSolver`] for BN254 which is implemented natively in Rust over the Grumpkin curve.
+///
+/// Unlike the [`BarretenbergSolver`][crate::BarretenbergSolver], this solver does not require the Barretenberg WASM
+/// and is `Send + Sync`, so a single instance can be shared between threads.
+///
+/// Pedersen hashes and Schnorr signatures depend upon the generator tables of Barretenberg's lookup-based Pedersen
+/// hash, which have not yet been ported, so these functions are reported as [unsupported][BlackBoxResolutionError::Unsupported].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GrumpkinSolver;
+
+impl BlackBoxFunctionSolver for GrumpkinSolver {
+    fn schnorr_verify(
+        &self,
+        _public_key_x: &FieldElement,
+        _public_key_y: &FieldElement,
+        _signature: &[u8],
+        _message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::SchnorrVerify))
+    }
+
+    fn pedersen(
+        &self,
+        _inputs: &[FieldElement],
+        _domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::Pedersen))
+    }
+
+    fn fixed_base_scalar_mul(
+        &self,
+        input: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Ok(fixed_base_scalar_mul(input))
+    }
+
+    fn embedded_curve_add(
+        &self,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        embedded_curve_add((input1_x, input1_y), (input2_x, input2_y))
+    }
+
+    fn variable_base_scalar_mul(
+        &self,
+        point_x: &FieldElement,
+        point_y: &FieldElement,
+        scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        variable_base_scalar_mul((point_x, point_y), scalar)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use acir::FieldElement;
+    use ark_ec::{short_weierstrass::SWCurveConfig, AffineRepr};
+
+    use super::{
+        embedded_curve_add, fixed_base_scalar_mul, variable_base_scalar_mul, GrumpkinAffine,
+        GrumpkinParameters,
+    };
+
+    #[test]
+    fn generator_is_on_curve() {
+        let generator = GrumpkinParameters::GENERATOR;
+        assert!(generator.is_on_curve());
+        assert!(generator.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(GrumpkinAffine::generator(), generator);
+    }
+
+    #[test]
+    fn fixed_base_scalar_mul_matches_barretenberg() {
+        let (x, y) = fixed_base_scalar_mul(&FieldElement::one());
+        assert_eq!(x.to_hex(), "0000000000000000000000000000000000000000000000000000000000000001");
+        assert_eq!(y.to_hex(), "0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c");
+    }
+
+    #[test]
+    fn fixed_base_scalar_mul_of_zero_is_point_at_infinity() {
+        assert_eq!(
+            fixed_base_scalar_mul(&FieldElement::zero()),
+            (FieldElement::zero(), FieldElement::zero())
+        );
+    }
+
+    #[test]
+    fn fixed_base_scalar_mul_is_homomorphic() {
+        let two = FieldElement::from(2u128);
+        let (x, y) = fixed_base_scalar_mul(&two);
+        let generator = GrumpkinAffine::generator();
+        let doubled = (generator + generator).into();
+        assert_eq!((x, y), super::to_coordinates(doubled));
+    }
+
+    #[test]
+    fn embedded_curve_add_handles_exceptional_cases() {
+        let one = fixed_base_scalar_mul(&FieldElement::one());
+        let two = fixed_base_scalar_mul(&FieldElement::from(2u128));
+        let minus_one = (one.0, -one.1);
+        let infinity = (FieldElement::zero(), FieldElement::zero());
+
+        let add = |lhs: (FieldElement, FieldElement), rhs: (FieldElement, FieldElement)| {
+            embedded_curve_add((&lhs.0, &lhs.1), (&rhs.0, &rhs.1)).unwrap()
+        };
+        assert_eq!(add(one, one), two);
+        assert_eq!(add(one, minus_one), infinity);
+        assert_eq!(add(one, infinity), one);
+        assert_eq!(add(infinity, one), one);
+        assert_eq!(add(infinity, infinity), infinity);
+    }
+
+    #[test]
+    fn variable_base_scalar_mul_matches_fixed_base_scalar_mul() {
+        let (x, y) = fixed_base_scalar_mul(&FieldElement::one());
+        let scalar = FieldElement::from_hex(
+            "0x2c8f3f3e2b0c36a5e5f9cba7b4d1a24f1a6bba3b7c1a0e89e8e54a43e1e1c0de",
+        )
+        .unwrap();
+        assert_eq!(
+            variable_base_scalar_mul((&x, &y), &scalar).unwrap(),
+            fixed_base_scalar_mul(&scalar)
+        );
+    }
+
+    #[test]
+    fn rejects_points_not_on_curve() {
+        let (x, y) = (FieldElement::one(), FieldElement::one());
+        assert!(variable_base_scalar_mul((&x, &y), &FieldElement::one()).is_err());
+        assert!(embedded_curve_add((&x, &y), (&x, &y)).is_err());
+    }
+}
@@ -8,14 +8,51 @@
 use acir::{BlackBoxFunc, FieldElement};
 use blake2::digest::generic_array::GenericArray;
 use blake2::{Blake2s256, Digest};
-use sha2::Sha256;
+use sha2::{Sha256, Sha512};
 use sha3::Keccak256;
 use thiserror::Error;
 
+mod aes128;
+#[cfg(feature = "bls12_381")]
+mod bandersnatch;
+#[cfg(feature = "barretenberg")]
 mod barretenberg;
-
+mod bigint;
+#[cfg(feature = "bn254")]
+mod eddsa;
+#[cfg(feature = "bn254")]
+mod grumpkin;
+mod message;
+#[cfg(feature = "bn254")]
+mod poseidon;
+#[cfg(feature = "bn254")]
+mod poseidon2;
+mod recursion;
+mod registry;
+
+pub use aes128::{aes128_cbc_encrypt, aes128_ctr_encrypt, AES128_BLOCK_SIZE};
+#[cfg(feature = "bls12_381")]
+pub use bandersnatch::BandersnatchSolver;
+#[cfg(feature = "barretenberg")]
 #[allow(deprecated)]
 pub use barretenberg::BarretenbergSolver;
+#[cfg(all(feature = "barretenberg", not(target_arch = "wasm32")))]
+#[allow(deprecated)]
+pub use barretenberg::BarretenbergSolverPool;
+pub use bigint::{bigint_add, bigint_div_mod, bigint_mod, bigint_mul};
+#[cfg(feature = "bn254")]
+pub use eddsa::{baby_jubjub_subgroup_order, BabyJubJubPoint, BABY_JUBJUB_A, BABY_JUBJUB_D};
+#[cfg(feature = "bn254")]
+pub use grumpkin::{GrumpkinAffine, GrumpkinParameters, GrumpkinProjective, GrumpkinSolver};
+#[cfg(feature = "bn254")]
+pub use poseidon::{PoseidonConfig, POSEIDON_WIDTH};
+#[cfg(feature = "bn254")]
+pub use poseidon2::{Poseidon2Config, POSEIDON2_WIDTH};
+pub use recursion::{mock_key_hash, MockRecursiveVerifier};
+pub use registry::{
+    Arity, BlackBoxCost, BlackBoxDefinition, BlackBoxInput, BlackBoxInputs, BlackBoxRegistry,
+    FallbackContext, FallbackGenerator, NativeImplementation, OperandSpec, BIGINT_DIV_MOD,
+};
 
 #[derive(Clone, PartialEq, Eq, Debug, Error)]
 pub enum BlackBoxResolutionError {
@@ -23,6 +60,32 @@ pub enum BlackBoxResolutionError {
     Unsupported(BlackBoxFunc),
     #[error("failed to solve blackbox function: {0}, reason: {1}")]
     Failed(BlackBoxFunc, String),
+    #[error("unsatisfied constraint in blackbox function: {0}")]
+    Unsatisfied(BlackBoxFunc),
+    /// A function registered under a name which is not that of a [`BlackBoxFunc`] has no definition.
+    #[error("unsupported blackbox function: {0}")]
+    UnsupportedExtension(String),
+    /// A function registered under a name which is not that of a [`BlackBoxFunc`] failed.
+    #[error("failed to solve blackbox function: {0}, reason: {1}")]
+    ExtensionFailed(String, String),
+}
+
+impl BlackBoxResolutionError {
+    /// Returns the error for the black box function registered as `name`, which has no definition.
+    pub fn unsupported(name: &str) -> Self {
+        match BlackBoxFunc::lookup(name) {
+            Some(func) => BlackBoxResolutionError::Unsupported(func),
+            None => BlackBoxResolutionError::UnsupportedExtension(name.to_string()),
+        }
+    }
+
+    /// Returns the error for the black box function registered as `name`, which failed for `reason`.
+    pub fn failed(name: &str, reason: String) -> Self {
+        match BlackBoxFunc::lookup(name) {
+            Some(func) => BlackBoxResolutionError::Failed(func, reason),
+            None => BlackBoxResolutionError::ExtensionFailed(name.to_string(), reason),
+        }
+    }
 }
 
 /// This component will generate outputs for Blackbox function calls where the underlying [`acir::BlackBoxFunc`]
@@ -46,6 +109,50 @@ pub trait BlackBoxFunctionSolver {
         &self,
         input: &FieldElement,
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError>;
+
+    /// Adds two points on the embedded curve.
+    ///
+    /// Backends which do not support embedded curve operations may rely upon the default implementation,
+    /// which reports the function as unsupported so that the compiler replaces it with its fallback.
+    fn embedded_curve_add(
+        &self,
+        _input1_x: &FieldElement,
+        _input1_y: &FieldElement,
+        _input2_x: &FieldElement,
+        _input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::EmbeddedCurveAdd))
+    }
+
+    /// Multiplies a point on the embedded curve by a scalar.
+    ///
+    /// As with [`embedded_curve_add`][Self::embedded_curve_add], the default implementation reports the function
+    /// as unsupported.
+    fn variable_base_scalar_mul(
+        &self,
+        _point_x: &FieldElement,
+        _point_y: &FieldElement,
+        _scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::VariableBaseScalarMul))
+    }
+
+    /// Computes the output aggregation object of a [`RecursiveAggregation`][BlackBoxFunc::RecursiveAggregation] call,
+    /// which must contain exactly `output_size` elements.
+    ///
+    /// Backends which cannot aggregate proofs during witness generation may rely upon the default implementation,
+    /// which assigns zero to every element so that the output witnesses are placeholders until proving.
+    fn recursive_aggregation(
+        &self,
+        _verification_key: &[FieldElement],
+        _proof: &[FieldElement],
+        _public_inputs: &[FieldElement],
+        _key_hash: &FieldElement,
+        _input_aggregation_object: Option<&[FieldElement]>,
+        output_size: usize,
+    ) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+        Ok(vec![FieldElement::zero(); output_size])
+    }
 }
 
 pub fn sha256(inputs: &[u8]) -> Result<[u8; 32], BlackBoxResolutionError> {
@@ -58,6 +165,15 @@ pub fn blake2s(inputs: &[u8]) -> Result<[u8; 32], BlackBoxResolutionError> {
         .map_err(|err| BlackBoxResolutionError::Failed(BlackBoxFunc::Blake2s, err))
 }
 
+pub fn sha512(inputs: &[u8]) -> Result<[u8; 64], BlackBoxResolutionError> {
+    generic_hash_512::<Sha512>(inputs)
+        .map_err(|err| BlackBoxResolutionError::Failed(BlackBoxFunc::SHA512, err))
+}
+
+pub fn blake3(inputs: &[u8]) -> Result<[u8; 32], BlackBoxResolutionError> {
+    Ok(blake3::hash(inputs).into())
+}
+
 pub fn keccak256(inputs: &[u8]) -> Result<[u8; 32], BlackBoxResolutionError> {
     generic_hash_256::<Keccak256>(inputs)
         .map_err(|err| BlackBoxResolutionError::Failed(BlackBoxFunc::Keccak256, err))
@@ -68,6 +184,62 @@ pub fn hash_to_field_128_security(inputs: &[u8]) -> Result<FieldElement, BlackBo
         .map_err(|err| BlackBoxResolutionError::Failed(BlackBoxFunc::HashToField128Security, err))
 }
 
+/// Applies the Poseidon2 permutation to `inputs`, which must hold a state of `len` elements.
+///
+/// Only the parameters for the BN254 scalar field with a state width of 4 are currently supported.
+pub fn poseidon2_permutation(
+    inputs: &[FieldElement],
+    len: u32,
+) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+    if inputs.len() != len as usize {
+        return Err(BlackBoxResolutionError::Failed(
+            BlackBoxFunc::Poseidon2Permutation,
+            format!(
+                "the number of inputs does not match specified length. {} != {len}",
+                inputs.len()
+            ),
+        ));
+    }
+
+    #[cfg(feature = "bn254")]
+    {
+        let mut state: [FieldElement; POSEIDON2_WIDTH] = inputs.try_into().map_err(|_| {
+            BlackBoxResolutionError::Failed(
+                BlackBoxFunc::Poseidon2Permutation,
+                format!("expected a state of {POSEIDON2_WIDTH} elements, found {len}"),
+            )
+        })?;
+        Poseidon2Config::bn254().permute(&mut state);
+        Ok(state.to_vec())
+    }
+    #[cfg(not(feature = "bn254"))]
+    Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::Poseidon2Permutation))
+}
+
+/// Verifies an EdDSA signature over Baby JubJub, using the Poseidon hash as in circomlib.
+///
+/// This is only supported over the BN254 scalar field, in which Baby JubJub is defined.
+pub fn eddsa_verify(
+    public_key_x: &FieldElement,
+    public_key_y: &FieldElement,
+    signature_s: &FieldElement,
+    signature_r8_x: &FieldElement,
+    signature_r8_y: &FieldElement,
+    message: &FieldElement,
+) -> Result<bool, BlackBoxResolutionError> {
+    #[cfg(feature = "bn254")]
+    {
+        let public_key = BabyJubJubPoint { x: *public_key_x, y: *public_key_y };
+        let signature_r8 = BabyJubJubPoint { x: *signature_r8_x, y: *signature_r8_y };
+        Ok(eddsa::verify_eddsa_signature(public_key, signature_s, signature_r8, message))
+    }
+    #[cfg(not(feature = "bn254"))]
+    {
+        let _ = (public_key_x, public_key_y, signature_s, signature_r8_x, signature_r8_y, message);
+        Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::EddsaVerify))
+    }
+}
+
 pub fn ecdsa_secp256k1_verify(
     hashed_msg: &[u8],
     public_key_x: &[u8; 32],
@@ -77,6 +249,46 @@ pub fn ecdsa_secp256k1_verify(
     Ok(verify_secp256k1_ecdsa_signature(hashed_msg, public_key_x, public_key_y, signature))
 }
 
+/// Recovers the public key which produced `signature` over `hashed_msg`, where `recovery_id` is the parity of the
+/// y coordinate of the signature's nonce point.
+///
+/// As with [`ecdsa_secp256k1_verify`], the signature must be "low S" normalized.
+pub fn ecdsa_secp256k1_recover(
+    hashed_msg: &[u8],
+    signature: &[u8; 64],
+    recovery_id: u8,
+) -> Result<([u8; 32], [u8; 32]), BlackBoxResolutionError> {
+    use k256::ecdsa::{recoverable, Signature};
+    use k256::elliptic_curve::{
+        sec1::{Coordinates, ToEncodedPoint},
+        IsHigh,
+    };
+
+    let failure = |reason: &str| {
+        BlackBoxResolutionError::Failed(BlackBoxFunc::EcdsaSecp256k1Recover, reason.to_string())
+    };
+
+    let hashed_msg: &[u8; 32] =
+        hashed_msg.try_into().map_err(|_| failure("expected a hashed message of 32 bytes"))?;
+    let signature =
+        Signature::try_from(signature.as_slice()).map_err(|_| failure("invalid signature"))?;
+    if signature.s().is_high().into() {
+        return Err(failure("signature is not low S normalized"));
+    }
+    let recovery_id =
+        recoverable::Id::new(recovery_id).map_err(|_| failure("recovery id must be 0 or 1"))?;
+    let public_key = recoverable::Signature::new(&signature, recovery_id)
+        .and_then(|signature| {
+            signature.recover_verifying_key_from_digest_bytes(GenericArray::from_slice(hashed_msg))
+        })
+        .map_err(|_| failure("failed to recover public key"))?;
+
+    match public_key.to_encoded_point(false).coordinates() {
+        Coordinates::Uncompressed { x, y } => Ok(((*x).into(), (*y).into())),
+        _ => unreachable!("uncompressed points should have both coordinates"),
+    }
+}
+
 pub fn ecdsa_secp256r1_verify(
     hashed_msg: &[u8],
     public_key_x: &[u8; 32],
@@ -94,6 +306,14 @@ fn generic_hash_256<D: Digest>(message: &[u8]) -> Result<[u8; 32], String> {
     Ok(output_bytes)
 }
 
+/// Does a generic hash of the inputs returning the resulting 64 bytes separately.
+fn generic_hash_512<D: Digest>(message: &[u8]) -> Result<[u8; 64], String> {
+    let output_bytes: [u8; 64] =
+        D::digest(message).as_slice().try_into().map_err(|_| "digest should be 512 bits")?;
+
+    Ok(output_bytes)
+}
+
 /// Does a generic hash of the entire inputs converting the resulting hash into a single output field.
 fn generic_hash_to_field<D: Digest>(message: &[u8]) -> Result<FieldElement, String> {
     let output_bytes: [u8; 32] =
@@ -215,7 +435,9 @@ fn verify_secp256r1_ecdsa_signature(
 
 #[cfg(test)]
 mod test {
-    use super::{verify_secp256k1_ecdsa_signature, verify_secp256r1_ecdsa_signature};
+    use super::{
+        ecdsa_secp256k1_recover, verify_secp256k1_ecdsa_signature, verify_secp256r1_ecdsa_signature,
+    };
 
     #[test]
     fn verifies_valid_k1_signature_with_low_s_value() {
@@ -255,6 +477,46 @@ mod test {
         assert!(valid)
     }
 
+    #[test]
+    fn recovers_k1_public_key() {
+        // The SHA256 hash of "hello" signed by the private key 1, whose public key is the generator.
+        let hashed_message: [u8; 32] = [
+            0x2c, 0xf2, 0x4d, 0xba, 0x5f, 0xb0, 0xa3, 0x0e, 0x26, 0xe8, 0x3b, 0x2a, 0xc5, 0xb9,
+            0xe2, 0x9e, 0x1b, 0x16, 0x1e, 0x5c, 0x1f, 0xa7, 0x42, 0x5e, 0x73, 0x04, 0x33, 0x62,
+            0x93, 0x8b, 0x98, 0x24,
+        ];
+        let signature: [u8; 64] = [
+            0x0f, 0x2f, 0xff, 0x86, 0x20, 0xd8, 0xff, 0xe9, 0x70, 0x40, 0xf8, 0xcf, 0x72, 0xae,
+            0x47, 0x6e, 0xf8, 0xff, 0x44, 0x12, 0x37, 0x39, 0x29, 0xc0, 0x32, 0x4c, 0xe8, 0x42,
+            0x8d, 0x33, 0x52, 0xe7, 0x18, 0x45, 0xae, 0x49, 0x03, 0x02, 0x76, 0x67, 0x00, 0x58,
+            0x46, 0xf8, 0xf0, 0xbe, 0x3e, 0x5e, 0xd2, 0xdb, 0x5c, 0x38, 0x26, 0xba, 0x83, 0xa6,
+            0xe5, 0x42, 0xe0, 0x80, 0x79, 0x2f, 0x9a, 0x9d,
+        ];
+        // 0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798
+        let generator_x: [u8; 32] = [
+            0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87,
+            0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b,
+            0x16, 0xf8, 0x17, 0x98,
+        ];
+
+        let (pub_key_x, pub_key_y) =
+            ecdsa_secp256k1_recover(&hashed_message, &signature, 0).unwrap();
+        assert_eq!(pub_key_x, generator_x);
+        assert!(verify_secp256k1_ecdsa_signature(
+            &hashed_message,
+            &pub_key_x,
+            &pub_key_y,
+            &signature
+        ));
+
+        // The other recovery id yields a different public key.
+        let (other_x, _) = ecdsa_secp256k1_recover(&hashed_message, &signature, 1).unwrap();
+        assert_ne!(other_x, generator_x);
+
+        assert!(ecdsa_secp256k1_recover(&hashed_message, &signature, 2).is_err());
+        assert!(ecdsa_secp256k1_recover(&hashed_message[1..], &signature, 0).is_err());
+    }
+
     #[test]
     fn verifies_valid_r1_signature_with_low_s_value() {
         // 0x54705ba3baafdbdfba8c5f9a70f7a89bee98d906b53e31074da7baecdc0da9ad
@@ -0,0 +1,150 @@
+//! Streams the inputs of hash black box functions into a hasher without first collecting the whole message.
+//!
+//! Each input contributes `ceil(num_bits / 8)` bytes of the message in little-endian order, as described on
+//! [`FunctionInput`][acir::circuit::opcodes::FunctionInput].
+
+use acir::{BlackBoxFunc, FieldElement};
+
+use crate::{BlackBoxInputs, BlackBoxResolutionError};
+
+/// The number of message bytes collected before they are passed to the hasher.
+const BUFFER_SIZE: usize = 1024;
+
+/// Returns the number of bytes in the message formed by the elements of `operand`.
+pub(crate) fn message_len(inputs: &dyn BlackBoxInputs, operand: usize) -> usize {
+    inputs.operand(operand).map(|input| num_bytes(input.num_bits)).sum()
+}
+
+/// Passes the first `message_size` bytes of the message formed by the elements of `operand` to `update`, in
+/// chunks.
+///
+/// Returns an error if the value of an input does not fit in its declared number of bits, rather than silently
+/// truncating it.
+pub(crate) fn stream_message(
+    func: BlackBoxFunc,
+    inputs: &dyn BlackBoxInputs,
+    operand: usize,
+    message_size: usize,
+    mut update: impl FnMut(&[u8]),
+) -> Result<(), BlackBoxResolutionError> {
+    let mut buffer = [0u8; BUFFER_SIZE];
+    let mut len = 0;
+    let mut remaining = message_size;
+    for (index, input) in inputs.operand(operand).enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        if input.num_bits > FieldElement::max_num_bits() {
+            return Err(BlackBoxResolutionError::Failed(
+                func,
+                format!(
+                    "input {index} is declared with {} bits but field elements have at most {}",
+                    input.num_bits,
+                    FieldElement::max_num_bits()
+                ),
+            ));
+        }
+
+        let num_bytes = num_bytes(input.num_bits);
+        if len + num_bytes > BUFFER_SIZE {
+            update(&buffer[..len]);
+            len = 0;
+        }
+        let bytes = &mut buffer[len..len + num_bytes];
+        // The most significant byte may only be partially used if the width is not a whole number of bytes.
+        let excess_bits_in_last_byte = (num_bytes * 8) as u32 - input.num_bits;
+        let fits = input.value.fill_le_bytes(bytes)
+            && bytes.last().map_or(true, |byte| byte.leading_zeros() >= excess_bits_in_last_byte);
+        if !fits {
+            return Err(BlackBoxResolutionError::Failed(
+                func,
+                format!(
+                    "input {index} has value {} which does not fit in {} bits",
+                    input.value, input.num_bits
+                ),
+            ));
+        }
+
+        let num_taken = num_bytes.min(remaining);
+        remaining -= num_taken;
+        len += num_taken;
+    }
+    if len != 0 {
+        update(&buffer[..len]);
+    }
+    Ok(())
+}
+
+fn num_bytes(num_bits: u32) -> usize {
+    ((num_bits + 7) / 8) as usize
+}
+
+#[cfg(test)]
+mod test {
+    use acir::{BlackBoxFunc, FieldElement};
+
+    use super::{message_len, stream_message, BUFFER_SIZE};
+    use crate::{BlackBoxInput, BlackBoxResolutionError};
+
+    fn input(value: u128, num_bits: u32) -> BlackBoxInput {
+        BlackBoxInput { value: FieldElement::from(value), num_bits }
+    }
+
+    fn collect(
+        inputs: Vec<BlackBoxInput>,
+        message_size: usize,
+    ) -> Result<Vec<u8>, BlackBoxResolutionError> {
+        let mut message = Vec::new();
+        stream_message(BlackBoxFunc::SHA256, &[inputs], 0, message_size, |bytes| {
+            message.extend_from_slice(bytes)
+        })?;
+        Ok(message)
+    }
+
+    #[test]
+    fn packs_inputs_in_little_endian_order() {
+        let inputs =
+            vec![input(0x01, 8), input(0x0302, 16), input(0x04, 3), input(0x0807_0605, 32)];
+        assert_eq!(message_len(&vec![inputs.clone()], 0), 8);
+        assert_eq!(collect(inputs, 8).unwrap(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn truncates_message_within_an_input() {
+        let inputs = vec![input(0x0201, 16), input(0x0403, 16)];
+        assert_eq!(collect(inputs.clone(), 3).unwrap(), vec![1, 2, 3]);
+        assert_eq!(collect(inputs, 0).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn rejects_values_wider_than_their_inputs() {
+        for (value, num_bits) in [(0x100, 8), (0x8, 3), (0x1_0000, 16)] {
+            let error = collect(vec![input(1, 8), input(value, num_bits)], 3).unwrap_err();
+            assert_eq!(
+                error,
+                BlackBoxResolutionError::Failed(
+                    BlackBoxFunc::SHA256,
+                    format!(
+                        "input 1 has value {} which does not fit in {num_bits} bits",
+                        FieldElement::from(value)
+                    ),
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn streams_messages_larger_than_buffer() {
+        let expected: Vec<u8> = (0..3 * BUFFER_SIZE).map(|byte| byte as u8).collect();
+        let inputs = [expected.iter().map(|byte| input(*byte as u128, 8)).collect()];
+        let mut num_updates = 0;
+        let mut message = Vec::new();
+        stream_message(BlackBoxFunc::SHA256, &inputs, 0, expected.len(), |bytes| {
+            num_updates += 1;
+            message.extend_from_slice(bytes);
+        })
+        .unwrap();
+        assert_eq!(num_updates, 3);
+        assert_eq!(message, expected);
+    }
+}
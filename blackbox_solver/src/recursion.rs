@@ -0,0 +1,170 @@
+use acir::{BlackBoxFunc, FieldElement};
+
+use crate::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+
+/// The challenge at which the mock verifier evaluates its inputs as a polynomial.
+const MOCK_CHALLENGE: u128 = 7;
+
+/// Evaluates `values` as the coefficients of a polynomial at [`MOCK_CHALLENGE`] using Horner's method.
+fn evaluate_at_challenge<'a>(values: impl IntoIterator<Item = &'a FieldElement>) -> FieldElement {
+    let challenge = FieldElement::from(MOCK_CHALLENGE);
+    values.into_iter().fold(FieldElement::zero(), |acc, value| acc * challenge + *value)
+}
+
+/// Computes the key hash which [`MockRecursiveVerifier`] expects to accompany `verification_key`.
+pub fn mock_key_hash(verification_key: &[FieldElement]) -> FieldElement {
+    evaluate_at_challenge(verification_key)
+}
+
+/// Folds a proof into `input_aggregation_object`, or into a zeroed aggregation object if there is none.
+///
+/// Element `i` of the output is incremented by `c^(i + 1)`, where `c` commits to the verification key,
+/// proof and public inputs.
+fn mock_aggregate(
+    verification_key: &[FieldElement],
+    proof: &[FieldElement],
+    public_inputs: &[FieldElement],
+    input_aggregation_object: Option<&[FieldElement]>,
+    output_size: usize,
+) -> Vec<FieldElement> {
+    let commitment =
+        evaluate_at_challenge(verification_key.iter().chain(proof).chain(public_inputs));
+
+    let mut power = FieldElement::one();
+    (0..output_size)
+        .map(|i| {
+            power = power * commitment;
+            let accumulated = input_aggregation_object
+                .map_or(FieldElement::zero(), |aggregation_object| aggregation_object[i]);
+            accumulated + power
+        })
+        .collect()
+}
+
+/// A [`BlackBoxFunctionSolver`] which computes aggregation objects for
+/// [`RecursiveAggregation`][BlackBoxFunc::RecursiveAggregation] calls without a prover, deferring all other
+/// functions to the wrapped solver.
+///
+/// The aggregation objects it produces have no cryptographic meaning. This solver exists so that the flow of
+/// aggregation objects through a circuit can be tested: it checks that the key hash matches [`mock_key_hash`]
+/// of the verification key and then folds the proof into the input aggregation object.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MockRecursiveVerifier<B> {
+    inner: B,
+}
+
+impl<B> MockRecursiveVerifier<B> {
+    pub fn new(inner: B) -> Self {
+        Self { inner }
+    }
+}
+
+impl<B: BlackBoxFunctionSolver> BlackBoxFunctionSolver for MockRecursiveVerifier<B> {
+    fn schnorr_verify(
+        &self,
+        public_key_x: &FieldElement,
+        public_key_y: &FieldElement,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        self.inner.schnorr_verify(public_key_x, public_key_y, signature, message)
+    }
+
+    fn pedersen(
+        &self,
+        inputs: &[FieldElement],
+        domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        self.inner.pedersen(inputs, domain_separator)
+    }
+
+    fn fixed_base_scalar_mul(
+        &self,
+        input: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        self.inner.fixed_base_scalar_mul(input)
+    }
+
+    fn embedded_curve_add(
+        &self,
+        input1_x: &FieldElement,
+        input1_y: &FieldElement,
+        input2_x: &FieldElement,
+        input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        self.inner.embedded_curve_add(input1_x, input1_y, input2_x, input2_y)
+    }
+
+    fn variable_base_scalar_mul(
+        &self,
+        point_x: &FieldElement,
+        point_y: &FieldElement,
+        scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        self.inner.variable_base_scalar_mul(point_x, point_y, scalar)
+    }
+
+    fn recursive_aggregation(
+        &self,
+        verification_key: &[FieldElement],
+        proof: &[FieldElement],
+        public_inputs: &[FieldElement],
+        key_hash: &FieldElement,
+        input_aggregation_object: Option<&[FieldElement]>,
+        output_size: usize,
+    ) -> Result<Vec<FieldElement>, BlackBoxResolutionError> {
+        if *key_hash != mock_key_hash(verification_key) {
+            return Err(BlackBoxResolutionError::Failed(
+                BlackBoxFunc::RecursiveAggregation,
+                "key hash does not match the verification key".to_string(),
+            ));
+        }
+        if let Some(aggregation_object) = input_aggregation_object {
+            if aggregation_object.len() != output_size {
+                return Err(BlackBoxResolutionError::Failed(
+                    BlackBoxFunc::RecursiveAggregation,
+                    format!(
+                        "expected an input aggregation object of {output_size} elements but got {}",
+                        aggregation_object.len()
+                    ),
+                ));
+            }
+        }
+
+        Ok(mock_aggregate(
+            verification_key,
+            proof,
+            public_inputs,
+            input_aggregation_object,
+            output_size,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use acir::FieldElement;
+
+    use super::{mock_aggregate, mock_key_hash};
+
+    #[test]
+    fn key_hash_evaluates_key_at_challenge() {
+        let verification_key = [FieldElement::from(1u128), FieldElement::from(2u128)];
+        assert_eq!(mock_key_hash(&verification_key), FieldElement::from(9u128));
+        assert_eq!(mock_key_hash(&[]), FieldElement::zero());
+    }
+
+    #[test]
+    fn aggregation_accumulates_into_input_object() {
+        let verification_key = [FieldElement::from(1u128)];
+        let proof = [FieldElement::from(2u128)];
+        let public_inputs = [FieldElement::from(3u128)];
+
+        // The commitment is 1 * 7^2 + 2 * 7 + 3 = 66.
+        let first = mock_aggregate(&verification_key, &proof, &public_inputs, None, 2);
+        assert_eq!(first, vec![FieldElement::from(66u128), FieldElement::from(66u128 * 66)]);
+
+        let second = mock_aggregate(&verification_key, &proof, &public_inputs, Some(&first), 2);
+        assert_eq!(second, vec![FieldElement::from(132u128), FieldElement::from(2 * 66u128 * 66)]);
+    }
+}
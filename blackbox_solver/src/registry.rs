@@ -0,0 +1,1067 @@
+//! A registry describing each black box function once, so that it may be solved by the ACVM and the Brillig VM
+//! and replaced with a fallback by the ACVM compiler.
+//!
+//! ACIR opcodes and Brillig instructions lay out the operands of a black box function differently, so each describes
+//! its operands as lists of values in the order given by the function's [`BlackBoxDefinition`].
+
+use acir::{
+    circuit::{
+        opcodes::{BlockId, FunctionInput},
+        Opcode,
+    },
+    native_types::Witness,
+    BlackBoxFunc, FieldElement,
+};
+
+use blake2::{Blake2s256, Digest};
+use sha2::{Sha256, Sha512};
+use sha3::Keccak256;
+
+use crate::message::{message_len, stream_message};
+use crate::{
+    aes128_cbc_encrypt, aes128_ctr_encrypt, bigint_add, bigint_div_mod, bigint_mod, bigint_mul,
+    ecdsa_secp256k1_recover, ecdsa_secp256k1_verify, ecdsa_secp256r1_verify, eddsa_verify,
+    poseidon2_permutation, BlackBoxFunctionSolver, BlackBoxResolutionError,
+};
+
+/// The number of elements in an operand of a black box function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arity {
+    /// The operand must contain exactly this many elements.
+    Fixed(usize),
+    /// The operand may contain any number of elements, including none if it is optional.
+    Variable,
+}
+
+/// Describes an input or output operand of a black box function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OperandSpec {
+    pub name: &'static str,
+    pub arity: Arity,
+    /// The number of bits in each element, or `None` if each element is a whole field element.
+    ///
+    /// Brillig does not annotate its values with bit widths so reads its inputs with this width.
+    pub num_bits: Option<u32>,
+}
+
+impl OperandSpec {
+    const fn new(name: &'static str, arity: Arity, num_bits: Option<u32>) -> Self {
+        Self { name, arity, num_bits }
+    }
+
+    const fn field(name: &'static str) -> Self {
+        Self::new(name, Arity::Fixed(1), None)
+    }
+
+    const fn fields(name: &'static str) -> Self {
+        Self::new(name, Arity::Variable, None)
+    }
+
+    const fn bytes(name: &'static str, arity: Arity) -> Self {
+        Self::new(name, arity, Some(8))
+    }
+
+    const fn boolean(name: &'static str) -> Self {
+        Self::new(name, Arity::Fixed(1), Some(1))
+    }
+}
+
+/// An element of an input operand of a black box function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlackBoxInput {
+    pub value: FieldElement,
+    pub num_bits: u32,
+}
+
+/// The input operands of a black box function, in the order given by its [`BlackBoxDefinition`].
+///
+/// The ACVM and the Brillig VM read elements from the witness map or memory as they are requested, so that a large
+/// message can be streamed into a hasher without first being copied.
+pub trait BlackBoxInputs {
+    /// Returns the number of input operands.
+    fn num_operands(&self) -> usize;
+
+    /// Returns the number of elements in the input operand at index `operand`.
+    fn operand_len(&self, operand: usize) -> usize;
+
+    /// Returns the element at `index` of the input operand at index `operand`.
+    fn get(&self, operand: usize, index: usize) -> BlackBoxInput;
+}
+
+impl dyn BlackBoxInputs + '_ {
+    /// Returns an iterator over the elements of the input operand at index `operand`.
+    pub fn operand(&self, operand: usize) -> impl Iterator<Item = BlackBoxInput> + '_ {
+        (0..self.operand_len(operand)).map(move |index| self.get(operand, index))
+    }
+}
+
+impl BlackBoxInputs for Vec<Vec<BlackBoxInput>> {
+    fn num_operands(&self) -> usize {
+        self.len()
+    }
+
+    fn operand_len(&self, operand: usize) -> usize {
+        self[operand].len()
+    }
+
+    fn get(&self, operand: usize, index: usize) -> BlackBoxInput {
+        self[operand][index]
+    }
+}
+
+impl<const N: usize> BlackBoxInputs for [Vec<BlackBoxInput>; N] {
+    fn num_operands(&self) -> usize {
+        N
+    }
+
+    fn operand_len(&self, operand: usize) -> usize {
+        self[operand].len()
+    }
+
+    fn get(&self, operand: usize, index: usize) -> BlackBoxInput {
+        self[operand][index]
+    }
+}
+
+/// Computes the output operands of a black box function from its input operands.
+///
+/// `output_sizes` holds the number of elements in each output operand, for functions whose outputs vary in size.
+pub type NativeImplementation = fn(
+    solver: &dyn BlackBoxFunctionSolver,
+    inputs: &dyn BlackBoxInputs,
+    output_sizes: &[usize],
+) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError>;
+
+/// The state of the circuit into which a fallback is inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FallbackContext {
+    /// The index of the first witness which the fallback may introduce.
+    pub num_witness: u32,
+    /// A memory block which the fallback may initialize with a lookup table, if the backend supports memory opcodes.
+    pub lookup_block: Option<BlockId>,
+}
+
+/// Constrains the output operands of a black box function using opcodes which every backend supports.
+///
+/// Returns the index of the next unused witness along with the opcodes, or `None` if the call cannot be replaced.
+/// Only the input operands are passed, as no fallback depends upon the constant parameters of a call.
+pub type FallbackGenerator = fn(
+    inputs: &[Vec<FunctionInput>],
+    outputs: &[Vec<Witness>],
+    context: FallbackContext,
+) -> Option<(u32, Vec<Opcode>)>;
+
+/// The relative cost of solving a black box function natively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BlackBoxCost {
+    /// Comparable to solving an arithmetic opcode.
+    Cheap,
+    /// Expensive enough to be worth solving on another thread.
+    Expensive,
+}
+
+/// The name of the Brillig black box function which divides one big integer by another, returning both the quotient
+/// and the remainder.
+///
+/// It has no ACIR counterpart, so is not a [`BlackBoxFunc`].
+pub const BIGINT_DIV_MOD: &str = "bigint_div_mod";
+
+/// Describes a black box function and how it may be solved or replaced.
+#[derive(Clone, Copy, Debug)]
+pub struct BlackBoxDefinition {
+    /// The name which identifies the function in a [`BlackBoxRegistry`].
+    ///
+    /// This is [`BlackBoxFunc::name`] for the standard black box functions. Backends may register functions under
+    /// other names, which the Brillig VM looks up in the same way.
+    pub name: &'static str,
+    /// The input operands, followed by any constant parameters of the ACIR opcode.
+    pub inputs: &'static [OperandSpec],
+    pub outputs: &'static [OperandSpec],
+    pub native: Option<NativeImplementation>,
+    pub fallback: Option<FallbackGenerator>,
+    pub cost: BlackBoxCost,
+}
+
+// Implementations are compared by address, so two definitions are only equal if they share the same functions.
+impl PartialEq for BlackBoxDefinition {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.inputs == other.inputs
+            && self.outputs == other.outputs
+            && self.native.map(|native| native as usize)
+                == other.native.map(|native| native as usize)
+            && self.fallback.map(|fallback| fallback as usize)
+                == other.fallback.map(|fallback| fallback as usize)
+            && self.cost == other.cost
+    }
+}
+
+impl Eq for BlackBoxDefinition {}
+
+/// A set of [black box definitions][BlackBoxDefinition], consisting of those of the standard black box functions
+/// along with any which have been registered in their place.
+///
+/// Backends may register definitions to provide native implementations or fallbacks for black box functions which
+/// lack them, such as those which rely upon the [`BlackBoxFunctionSolver`], or to define further black box functions
+/// under names of their own.
+///
+/// A single registry serves both to compile and to solve a circuit: the compiler reads the fallbacks of its
+/// definitions while the ACVM and the Brillig VM read their native implementations. The standard definitions carry
+/// no fallbacks, so backends should start from `acvm::compiler::black_box_registry`, which adds those of the ACVM,
+/// and pass the extended registry to both.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlackBoxRegistry {
+    registered: Vec<BlackBoxDefinition>,
+}
+
+impl BlackBoxRegistry {
+    /// Creates a registry containing the definitions of the standard black box functions.
+    pub const fn new() -> Self {
+        Self { registered: Vec::new() }
+    }
+
+    /// Returns a registry containing the definitions of the standard black box functions, without any fallbacks.
+    ///
+    /// This is the registry which the ACVM and the Brillig VM solve with unless given another.
+    pub fn standard() -> &'static Self {
+        static STANDARD: BlackBoxRegistry = BlackBoxRegistry::new();
+        &STANDARD
+    }
+
+    /// Registers `definition`, replacing any existing definition with the same name.
+    pub fn register(&mut self, definition: BlackBoxDefinition) {
+        self.registered.retain(|registered| registered.name != definition.name);
+        self.registered.push(definition);
+    }
+
+    /// Returns the definition of the black box function named `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&BlackBoxDefinition> {
+        self.registered
+            .iter()
+            .find(|definition| definition.name == name)
+            .or_else(|| STANDARD_DEFINITIONS.iter().find(|definition| definition.name == name))
+    }
+
+    /// Solves the black box function named `name` natively, checking that its operands match its definition.
+    pub fn solve(
+        &self,
+        name: &str,
+        solver: &dyn BlackBoxFunctionSolver,
+        inputs: &dyn BlackBoxInputs,
+        output_sizes: &[usize],
+    ) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError> {
+        self.get(name).ok_or_else(|| BlackBoxResolutionError::unsupported(name))?.solve(
+            solver,
+            inputs,
+            output_sizes,
+        )
+    }
+}
+
+impl BlackBoxDefinition {
+    /// Solves this black box function natively, checking that its operands match this definition.
+    pub fn solve(
+        &self,
+        solver: &dyn BlackBoxFunctionSolver,
+        inputs: &dyn BlackBoxInputs,
+        output_sizes: &[usize],
+    ) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError> {
+        let name = self.name;
+        let native = self.native.ok_or_else(|| BlackBoxResolutionError::unsupported(name))?;
+
+        check_operand_sizes(
+            name,
+            self.inputs,
+            (0..inputs.num_operands()).map(|operand| inputs.operand_len(operand)),
+        )?;
+        check_operand_sizes(name, self.outputs, output_sizes.iter().copied())?;
+
+        let outputs = native(solver, inputs, output_sizes)?;
+        if outputs.len() != output_sizes.len() {
+            return Err(BlackBoxResolutionError::failed(
+                name,
+                format!(
+                    "Expected {} output operands but encountered {}",
+                    output_sizes.len(),
+                    outputs.len()
+                ),
+            ));
+        }
+        for (output, &size) in outputs.iter().zip(output_sizes) {
+            if output.len() != size {
+                return Err(BlackBoxResolutionError::failed(
+                    name,
+                    format!("Expected {size} outputs but encountered {}", output.len()),
+                ));
+            }
+        }
+        Ok(outputs)
+    }
+}
+
+fn check_operand_sizes(
+    name: &str,
+    specs: &[OperandSpec],
+    sizes: impl ExactSizeIterator<Item = usize>,
+) -> Result<(), BlackBoxResolutionError> {
+    if specs.len() != sizes.len() {
+        return Err(BlackBoxResolutionError::failed(
+            name,
+            format!("expected {} operands but received {}", specs.len(), sizes.len()),
+        ));
+    }
+    for (spec, size) in specs.iter().zip(sizes) {
+        match spec.arity {
+            Arity::Fixed(expected) if expected != size => {
+                return Err(BlackBoxResolutionError::failed(
+                    name,
+                    format!("expected {} size {expected} but received {size}", spec.name),
+                ));
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Extracts the bytes held by the elements of `operand`, failing if any element does not fit in a byte.
+fn to_bytes(
+    name: &str,
+    inputs: &dyn BlackBoxInputs,
+    operand: usize,
+) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    inputs
+        .operand(operand)
+        .enumerate()
+        .map(|(index, input)| {
+            let mut byte = [0];
+            if input.value.fill_le_bytes(&mut byte) {
+                Ok(byte[0])
+            } else {
+                Err(BlackBoxResolutionError::failed(
+                    name,
+                    format!(
+                        "element {index} of operand {operand} has value {} which does not fit in a byte",
+                        input.value
+                    ),
+                ))
+            }
+        })
+        .collect()
+}
+
+fn to_fields(inputs: &dyn BlackBoxInputs, operand: usize) -> Vec<FieldElement> {
+    inputs.operand(operand).map(|input| input.value).collect()
+}
+
+/// Returns the value of the first element of `operand`, for operands holding a single field element.
+fn field(inputs: &dyn BlackBoxInputs, operand: usize) -> FieldElement {
+    inputs.get(operand, 0).value
+}
+
+fn from_bytes(bytes: &[u8]) -> Vec<FieldElement> {
+    bytes.iter().map(|byte| FieldElement::from(*byte as u128)).collect()
+}
+
+fn to_u32(input: &BlackBoxInput, func: BlackBoxFunc) -> Result<u32, BlackBoxResolutionError> {
+    input.value.to_u128().try_into().map_err(|_| {
+        BlackBoxResolutionError::Failed(func, format!("{} does not fit in 32 bits", input.value))
+    })
+}
+
+fn solve_logic(
+    inputs: &dyn BlackBoxInputs,
+    logic_op: fn(&FieldElement, &FieldElement, u32) -> FieldElement,
+) -> Vec<Vec<FieldElement>> {
+    let (lhs, rhs) = (inputs.get(0, 0), inputs.get(1, 0));
+    assert_eq!(
+        lhs.num_bits, rhs.num_bits,
+        "number of bits specified for each input must be the same"
+    );
+    vec![vec![logic_op(&lhs.value, &rhs.value, lhs.num_bits)]]
+}
+
+/// Hashes the message formed by the first input operand, streaming it into the hasher.
+fn digest<D: Digest>(
+    func: BlackBoxFunc,
+    inputs: &dyn BlackBoxInputs,
+) -> Result<Vec<u8>, BlackBoxResolutionError> {
+    let mut hasher = D::new();
+    stream_message(func, inputs, 0, message_len(inputs, 0), |bytes| hasher.update(bytes))?;
+    Ok(hasher.finalize().to_vec())
+}
+
+fn solve_hash<D: Digest>(
+    func: BlackBoxFunc,
+    inputs: &dyn BlackBoxInputs,
+) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError> {
+    Ok(vec![from_bytes(&digest::<D>(func, inputs)?)])
+}
+
+type EcdsaVerify =
+    fn(&[u8], &[u8; 32], &[u8; 32], &[u8; 64]) -> Result<bool, BlackBoxResolutionError>;
+
+/// A function of three byte arrays, such as an encryption or a modular operation on big integers.
+type TernaryByteFunction = fn(&[u8], &[u8], &[u8]) -> Result<Vec<u8>, BlackBoxResolutionError>;
+
+fn solve_ecdsa_verify(
+    name: &str,
+    inputs: &dyn BlackBoxInputs,
+    verify: EcdsaVerify,
+) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError> {
+    // Operand sizes have already been checked against the definition.
+    let public_key_x: [u8; 32] = to_bytes(name, inputs, 0)?.try_into().unwrap();
+    let public_key_y: [u8; 32] = to_bytes(name, inputs, 1)?.try_into().unwrap();
+    let signature: [u8; 64] = to_bytes(name, inputs, 2)?.try_into().unwrap();
+    let hashed_message = to_bytes(name, inputs, 3)?;
+    let is_valid = verify(&hashed_message, &public_key_x, &public_key_y, &signature)?;
+    Ok(vec![vec![FieldElement::from(is_valid)]])
+}
+
+/// Solves a [`TernaryByteFunction`] of the three input operands, which hold bytes.
+fn solve_ternary_bytes(
+    name: &str,
+    inputs: &dyn BlackBoxInputs,
+    function: TernaryByteFunction,
+) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError> {
+    let result = function(
+        &to_bytes(name, inputs, 0)?,
+        &to_bytes(name, inputs, 1)?,
+        &to_bytes(name, inputs, 2)?,
+    )?;
+    Ok(vec![from_bytes(&result)])
+}
+
+fn solve_curve_operation(
+    result: Result<(FieldElement, FieldElement), BlackBoxResolutionError>,
+) -> Result<Vec<Vec<FieldElement>>, BlackBoxResolutionError> {
+    let (x, y) = result?;
+    Ok(vec![vec![x, y]])
+}
+
+const BYTE_HASH_INPUTS: &[OperandSpec] = &[OperandSpec::bytes("inputs", Arity::Variable)];
+const DIGEST_32_OUTPUTS: &[OperandSpec] = &[OperandSpec::bytes("outputs", Arity::Fixed(32))];
+const DIGEST_64_OUTPUTS: &[OperandSpec] = &[OperandSpec::bytes("outputs", Arity::Fixed(64))];
+const LOGIC_INPUTS: &[OperandSpec] = &[OperandSpec::field("lhs"), OperandSpec::field("rhs")];
+const LOGIC_OUTPUTS: &[OperandSpec] = &[OperandSpec::field("output")];
+const ECDSA_INPUTS: &[OperandSpec] = &[
+    OperandSpec::bytes("public_key_x", Arity::Fixed(32)),
+    OperandSpec::bytes("public_key_y", Arity::Fixed(32)),
+    OperandSpec::bytes("signature", Arity::Fixed(64)),
+    OperandSpec::bytes("hashed_message", Arity::Variable),
+];
+const VERIFICATION_OUTPUTS: &[OperandSpec] = &[OperandSpec::boolean("output")];
+const POINT_OUTPUTS: &[OperandSpec] = &[OperandSpec::new("outputs", Arity::Fixed(2), None)];
+const AES128_INPUTS: &[OperandSpec] = &[
+    OperandSpec::bytes("inputs", Arity::Variable),
+    OperandSpec::bytes("iv", Arity::Fixed(16)),
+    OperandSpec::bytes("key", Arity::Fixed(16)),
+];
+const BIGINT_BINARY_INPUTS: &[OperandSpec] = &[
+    OperandSpec::bytes("lhs", Arity::Variable),
+    OperandSpec::bytes("rhs", Arity::Variable),
+    OperandSpec::bytes("modulus", Arity::Variable),
+];
+const BYTES_OUTPUTS: &[OperandSpec] = &[OperandSpec::bytes("outputs", Arity::Variable)];
+
+/// The definitions of the standard black box functions and of [`BIGINT_DIV_MOD`], none of which have fallbacks.
+///
+/// The fallbacks of the standard black box functions are implemented by the ACVM stdlib, which is not a
+/// dependency of this crate, so are registered by the ACVM compiler.
+static STANDARD_DEFINITIONS: &[BlackBoxDefinition] = &[
+    BlackBoxDefinition {
+        name: BlackBoxFunc::AND.name(),
+        inputs: LOGIC_INPUTS,
+        outputs: LOGIC_OUTPUTS,
+        native: Some(|_, inputs, _| Ok(solve_logic(inputs, FieldElement::and))),
+        fallback: None,
+        cost: BlackBoxCost::Cheap,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::XOR.name(),
+        inputs: LOGIC_INPUTS,
+        outputs: LOGIC_OUTPUTS,
+        native: Some(|_, inputs, _| Ok(solve_logic(inputs, FieldElement::xor))),
+        fallback: None,
+        cost: BlackBoxCost::Cheap,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::RANGE.name(),
+        inputs: &[OperandSpec::field("input")],
+        outputs: &[],
+        native: Some(|_, inputs, _| {
+            let input = inputs.get(0, 0);
+            if input.value.num_bits() > input.num_bits {
+                return Err(BlackBoxResolutionError::Unsatisfied(BlackBoxFunc::RANGE));
+            }
+            Ok(Vec::new())
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Cheap,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::SHA256.name(),
+        inputs: BYTE_HASH_INPUTS,
+        outputs: DIGEST_32_OUTPUTS,
+        native: Some(|_, inputs, _| solve_hash::<Sha256>(BlackBoxFunc::SHA256, inputs)),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::Blake2s.name(),
+        inputs: BYTE_HASH_INPUTS,
+        outputs: DIGEST_32_OUTPUTS,
+        native: Some(|_, inputs, _| solve_hash::<Blake2s256>(BlackBoxFunc::Blake2s, inputs)),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::SHA512.name(),
+        inputs: BYTE_HASH_INPUTS,
+        outputs: DIGEST_64_OUTPUTS,
+        native: Some(|_, inputs, _| solve_hash::<Sha512>(BlackBoxFunc::SHA512, inputs)),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::Blake3.name(),
+        inputs: BYTE_HASH_INPUTS,
+        outputs: DIGEST_32_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            let mut hasher = blake3::Hasher::new();
+            stream_message(BlackBoxFunc::Blake3, inputs, 0, message_len(inputs, 0), |bytes| {
+                hasher.update(bytes);
+            })?;
+            Ok(vec![from_bytes(hasher.finalize().as_bytes())])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::Keccak256.name(),
+        inputs: &[
+            OperandSpec::bytes("inputs", Arity::Variable),
+            // Only present for variable length messages.
+            OperandSpec::new("var_message_size", Arity::Variable, Some(32)),
+        ],
+        outputs: DIGEST_32_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            let message_len = message_len(inputs, 0);
+            let mut num_bytes_to_take = message_len;
+            if let Some(message_size) = inputs.operand(1).next() {
+                num_bytes_to_take = message_size.value.to_u128() as usize;
+                // If the number of bytes to take is more than the amount of bytes available
+                // in the message, then we error.
+                if num_bytes_to_take > message_len {
+                    return Err(BlackBoxResolutionError::Failed(
+                        BlackBoxFunc::Keccak256,
+                        format!("the number of bytes to take from the message is more than the number of bytes in the message. {} > {}", num_bytes_to_take, message_len),
+                    ));
+                }
+            }
+            let mut hasher = Keccak256::new();
+            stream_message(BlackBoxFunc::Keccak256, inputs, 0, num_bytes_to_take, |bytes| {
+                hasher.update(bytes)
+            })?;
+            Ok(vec![from_bytes(&hasher.finalize())])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::HashToField128Security.name(),
+        inputs: BYTE_HASH_INPUTS,
+        outputs: &[OperandSpec::field("output")],
+        native: Some(|_, inputs, _| {
+            let digest = digest::<Blake2s256>(BlackBoxFunc::HashToField128Security, inputs)?;
+            Ok(vec![vec![FieldElement::from_be_bytes_reduce(&digest)]])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::SchnorrVerify.name(),
+        inputs: &[
+            OperandSpec::field("public_key_x"),
+            OperandSpec::field("public_key_y"),
+            OperandSpec::bytes("signature", Arity::Variable),
+            OperandSpec::bytes("message", Arity::Variable),
+        ],
+        outputs: VERIFICATION_OUTPUTS,
+        native: Some(|solver, inputs, _| {
+            let is_valid = solver.schnorr_verify(
+                &field(inputs, 0),
+                &field(inputs, 1),
+                &to_bytes(BlackBoxFunc::SchnorrVerify.name(), inputs, 2)?,
+                &to_bytes(BlackBoxFunc::SchnorrVerify.name(), inputs, 3)?,
+            )?;
+            Ok(vec![vec![FieldElement::from(is_valid)]])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::EddsaVerify.name(),
+        inputs: &[
+            OperandSpec::field("public_key_x"),
+            OperandSpec::field("public_key_y"),
+            OperandSpec::field("signature_s"),
+            OperandSpec::field("signature_r8_x"),
+            OperandSpec::field("signature_r8_y"),
+            OperandSpec::field("message"),
+        ],
+        outputs: VERIFICATION_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            let [public_key_x, public_key_y, signature_s, signature_r8_x, signature_r8_y, message] =
+                [0, 1, 2, 3, 4, 5].map(|operand| field(inputs, operand));
+            let is_valid = eddsa_verify(
+                &public_key_x,
+                &public_key_y,
+                &signature_s,
+                &signature_r8_x,
+                &signature_r8_y,
+                &message,
+            )?;
+            Ok(vec![vec![FieldElement::from(is_valid)]])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::Pedersen.name(),
+        inputs: &[
+            OperandSpec::fields("inputs"),
+            OperandSpec::new("domain_separator", Arity::Fixed(1), Some(32)),
+        ],
+        outputs: POINT_OUTPUTS,
+        native: Some(|solver, inputs, _| {
+            let domain_separator = to_u32(&inputs.get(1, 0), BlackBoxFunc::Pedersen)?;
+            solve_curve_operation(solver.pedersen(&to_fields(inputs, 0), domain_separator))
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::EcdsaSecp256k1.name(),
+        inputs: ECDSA_INPUTS,
+        outputs: VERIFICATION_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            solve_ecdsa_verify(BlackBoxFunc::EcdsaSecp256k1.name(), inputs, ecdsa_secp256k1_verify)
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::EcdsaSecp256r1.name(),
+        inputs: ECDSA_INPUTS,
+        outputs: VERIFICATION_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            solve_ecdsa_verify(BlackBoxFunc::EcdsaSecp256r1.name(), inputs, ecdsa_secp256r1_verify)
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::EcdsaSecp256k1Recover.name(),
+        inputs: &[
+            OperandSpec::bytes("hashed_message", Arity::Variable),
+            OperandSpec::bytes("signature", Arity::Fixed(64)),
+            OperandSpec::bytes("recovery_id", Arity::Fixed(1)),
+        ],
+        outputs: &[
+            OperandSpec::bytes("public_key_x", Arity::Fixed(32)),
+            OperandSpec::bytes("public_key_y", Arity::Fixed(32)),
+        ],
+        native: Some(|_, inputs, _| {
+            let name = BlackBoxFunc::EcdsaSecp256k1Recover.name();
+            let signature: [u8; 64] = to_bytes(name, inputs, 1)?.try_into().unwrap();
+            let recovery_id = to_bytes(name, inputs, 2)?[0];
+            let (public_key_x, public_key_y) =
+                ecdsa_secp256k1_recover(&to_bytes(name, inputs, 0)?, &signature, recovery_id)?;
+            Ok(vec![from_bytes(&public_key_x), from_bytes(&public_key_y)])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::FixedBaseScalarMul.name(),
+        inputs: &[OperandSpec::field("input")],
+        outputs: POINT_OUTPUTS,
+        native: Some(|solver, inputs, _| {
+            solve_curve_operation(solver.fixed_base_scalar_mul(&field(inputs, 0)))
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::EmbeddedCurveAdd.name(),
+        inputs: &[
+            OperandSpec::field("input1_x"),
+            OperandSpec::field("input1_y"),
+            OperandSpec::field("input2_x"),
+            OperandSpec::field("input2_y"),
+        ],
+        outputs: POINT_OUTPUTS,
+        native: Some(|solver, inputs, _| {
+            solve_curve_operation(solver.embedded_curve_add(
+                &field(inputs, 0),
+                &field(inputs, 1),
+                &field(inputs, 2),
+                &field(inputs, 3),
+            ))
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::VariableBaseScalarMul.name(),
+        inputs: &[
+            OperandSpec::field("point_x"),
+            OperandSpec::field("point_y"),
+            OperandSpec::field("scalar"),
+        ],
+        outputs: POINT_OUTPUTS,
+        native: Some(|solver, inputs, _| {
+            solve_curve_operation(solver.variable_base_scalar_mul(
+                &field(inputs, 0),
+                &field(inputs, 1),
+                &field(inputs, 2),
+            ))
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::BigIntAdd.name(),
+        inputs: BIGINT_BINARY_INPUTS,
+        outputs: BYTES_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            solve_ternary_bytes(BlackBoxFunc::BigIntAdd.name(), inputs, bigint_add)
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::BigIntMul.name(),
+        inputs: BIGINT_BINARY_INPUTS,
+        outputs: BYTES_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            solve_ternary_bytes(BlackBoxFunc::BigIntMul.name(), inputs, bigint_mul)
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::BigIntMod.name(),
+        inputs: &[
+            OperandSpec::bytes("input", Arity::Variable),
+            OperandSpec::bytes("modulus", Arity::Variable),
+        ],
+        outputs: BYTES_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            let name = BlackBoxFunc::BigIntMod.name();
+            let input = to_bytes(name, inputs, 0)?;
+            Ok(vec![from_bytes(&bigint_mod(&input, &to_bytes(name, inputs, 1)?)?)])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BIGINT_DIV_MOD,
+        inputs: &[
+            OperandSpec::bytes("lhs", Arity::Variable),
+            OperandSpec::bytes("rhs", Arity::Variable),
+        ],
+        outputs: &[
+            OperandSpec::bytes("quotient", Arity::Variable),
+            OperandSpec::bytes("remainder", Arity::Variable),
+        ],
+        native: Some(|_, inputs, _| {
+            let (lhs, rhs) =
+                (to_bytes(BIGINT_DIV_MOD, inputs, 0)?, to_bytes(BIGINT_DIV_MOD, inputs, 1)?);
+            let (quotient, remainder) = bigint_div_mod(&lhs, &rhs)?;
+            Ok(vec![from_bytes(&quotient), from_bytes(&remainder)])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::AES128CbcEncrypt.name(),
+        inputs: AES128_INPUTS,
+        outputs: BYTES_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            solve_ternary_bytes(BlackBoxFunc::AES128CbcEncrypt.name(), inputs, aes128_cbc_encrypt)
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::AES128CtrEncrypt.name(),
+        inputs: AES128_INPUTS,
+        outputs: BYTES_OUTPUTS,
+        native: Some(|_, inputs, _| {
+            solve_ternary_bytes(BlackBoxFunc::AES128CtrEncrypt.name(), inputs, aes128_ctr_encrypt)
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::Poseidon2Permutation.name(),
+        inputs: &[
+            OperandSpec::fields("inputs"),
+            OperandSpec::new("len", Arity::Fixed(1), Some(32)),
+        ],
+        outputs: &[OperandSpec::fields("outputs")],
+        native: Some(|_, inputs, _| {
+            let len = to_u32(&inputs.get(1, 0), BlackBoxFunc::Poseidon2Permutation)?;
+            Ok(vec![poseidon2_permutation(&to_fields(inputs, 0), len)?])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+    BlackBoxDefinition {
+        name: BlackBoxFunc::RecursiveAggregation.name(),
+        inputs: &[
+            OperandSpec::fields("verification_key"),
+            OperandSpec::fields("proof"),
+            OperandSpec::fields("public_inputs"),
+            OperandSpec::field("key_hash"),
+            // Only present if a previous recursive aggregation is being verified.
+            OperandSpec::fields("input_aggregation_object"),
+        ],
+        outputs: &[OperandSpec::fields("output_aggregation_object")],
+        native: Some(|solver, inputs, output_sizes| {
+            let input_aggregation_object =
+                (inputs.operand_len(4) != 0).then(|| to_fields(inputs, 4));
+            let output_aggregation_object = solver.recursive_aggregation(
+                &to_fields(inputs, 0),
+                &to_fields(inputs, 1),
+                &to_fields(inputs, 2),
+                &field(inputs, 3),
+                input_aggregation_object.as_deref(),
+                output_sizes[0],
+            )?;
+            Ok(vec![output_aggregation_object])
+        }),
+        fallback: None,
+        cost: BlackBoxCost::Expensive,
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use acir::{BlackBoxFunc, FieldElement};
+
+    use super::{
+        Arity, BlackBoxCost, BlackBoxDefinition, BlackBoxInput, BlackBoxRegistry, OperandSpec,
+        STANDARD_DEFINITIONS,
+    };
+    use crate::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+
+    struct UnsupportedSolver;
+
+    impl BlackBoxFunctionSolver for UnsupportedSolver {
+        fn schnorr_verify(
+            &self,
+            _public_key_x: &FieldElement,
+            _public_key_y: &FieldElement,
+            _signature: &[u8],
+            _message: &[u8],
+        ) -> Result<bool, BlackBoxResolutionError> {
+            Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::SchnorrVerify))
+        }
+        fn pedersen(
+            &self,
+            _inputs: &[FieldElement],
+            _domain_separator: u32,
+        ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+            Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::Pedersen))
+        }
+        fn fixed_base_scalar_mul(
+            &self,
+            _input: &FieldElement,
+        ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+            Err(BlackBoxResolutionError::Unsupported(BlackBoxFunc::FixedBaseScalarMul))
+        }
+    }
+
+    fn bytes(bytes: &[u8]) -> Vec<BlackBoxInput> {
+        bytes
+            .iter()
+            .map(|byte| BlackBoxInput { value: FieldElement::from(*byte as u128), num_bits: 8 })
+            .collect()
+    }
+
+    #[test]
+    fn each_function_is_defined_once() {
+        for (index, definition) in STANDARD_DEFINITIONS.iter().enumerate() {
+            assert!(
+                STANDARD_DEFINITIONS[index + 1..].iter().all(|other| other.name != definition.name),
+                "{} is defined more than once",
+                definition.name
+            );
+        }
+    }
+
+    #[test]
+    fn solves_standard_function() {
+        let digest = BlackBoxRegistry::standard()
+            .solve(BlackBoxFunc::SHA256.name(), &UnsupportedSolver, &[bytes(b"abc")], &[32])
+            .unwrap();
+        assert_eq!(digest, vec![super::from_bytes(&crate::sha256(b"abc").unwrap())]);
+    }
+
+    #[test]
+    fn hashes_inputs_packing_several_bytes() {
+        let registry = BlackBoxRegistry::standard();
+        let mut inputs = vec![vec![
+            BlackBoxInput { value: FieldElement::from(0x6261_u128), num_bits: 16 },
+            BlackBoxInput { value: FieldElement::from(0x63_u128), num_bits: 8 },
+        ]];
+        let digest =
+            registry.solve(BlackBoxFunc::SHA256.name(), &UnsupportedSolver, &inputs, &[32]);
+        assert_eq!(digest.unwrap(), vec![super::from_bytes(&crate::sha256(b"abc").unwrap())]);
+
+        // Variable length messages may end partway through a packed input.
+        inputs.push(vec![BlackBoxInput { value: FieldElement::one(), num_bits: 32 }]);
+        let digest =
+            registry.solve(BlackBoxFunc::Keccak256.name(), &UnsupportedSolver, &inputs, &[32]);
+        assert_eq!(digest.unwrap(), vec![super::from_bytes(&crate::keccak256(b"a").unwrap())]);
+    }
+
+    #[test]
+    fn rejects_operands_of_wrong_size() {
+        let error = BlackBoxRegistry::standard()
+            .solve(BlackBoxFunc::SHA256.name(), &UnsupportedSolver, &[bytes(b"abc")], &[31])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            BlackBoxResolutionError::Failed(
+                BlackBoxFunc::SHA256,
+                "expected outputs size 32 but received 31".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_byte_operands_which_do_not_fit_in_a_byte() {
+        let mut key = bytes(&[0; 16]);
+        key[3].value = FieldElement::from(0x100u128);
+        let inputs = [bytes(&[0; 16]), bytes(&[0; 16]), key];
+        let error = BlackBoxRegistry::standard()
+            .solve(BlackBoxFunc::AES128CbcEncrypt.name(), &UnsupportedSolver, &inputs, &[32])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            BlackBoxResolutionError::Failed(
+                BlackBoxFunc::AES128CbcEncrypt,
+                format!(
+                    "element 3 of operand 2 has value {} which does not fit in a byte",
+                    FieldElement::from(0x100u128)
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_native_results_which_do_not_match_the_outputs() {
+        let mut registry = BlackBoxRegistry::new();
+        registry.register(BlackBoxDefinition {
+            name: "pair",
+            inputs: &[],
+            outputs: &[
+                OperandSpec { name: "first", arity: Arity::Variable, num_bits: None },
+                OperandSpec { name: "second", arity: Arity::Variable, num_bits: None },
+            ],
+            native: Some(|_, _, output_sizes| {
+                Ok(vec![vec![FieldElement::zero(); output_sizes[0] + 1]; output_sizes.len() - 1])
+            }),
+            fallback: None,
+            cost: BlackBoxCost::Cheap,
+        });
+
+        let inputs: [Vec<BlackBoxInput>; 0] = [];
+        assert_eq!(
+            registry.solve("pair", &UnsupportedSolver, &inputs, &[1, 1]),
+            Err(BlackBoxResolutionError::ExtensionFailed(
+                "pair".to_string(),
+                "Expected 2 output operands but encountered 1".to_string()
+            ))
+        );
+
+        registry.register(BlackBoxDefinition {
+            native: Some(|_, _, output_sizes| {
+                Ok(output_sizes.iter().map(|size| vec![FieldElement::zero(); size + 1]).collect())
+            }),
+            ..*registry.get("pair").unwrap()
+        });
+        assert_eq!(
+            registry.solve("pair", &UnsupportedSolver, &inputs, &[1, 1]),
+            Err(BlackBoxResolutionError::ExtensionFailed(
+                "pair".to_string(),
+                "Expected 1 outputs but encountered 2".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn registered_definitions_replace_standard_definitions() {
+        let mut registry = BlackBoxRegistry::new();
+        registry.register(BlackBoxDefinition {
+            name: BlackBoxFunc::Pedersen.name(),
+            inputs: &[OperandSpec { name: "inputs", arity: Arity::Variable, num_bits: None }],
+            outputs: &[OperandSpec { name: "outputs", arity: Arity::Fixed(2), num_bits: None }],
+            native: Some(|_, inputs, _| {
+                let sum =
+                    inputs.operand(0).fold(FieldElement::zero(), |sum, input| sum + input.value);
+                Ok(vec![vec![sum, sum]])
+            }),
+            fallback: None,
+            cost: BlackBoxCost::Cheap,
+        });
+
+        let inputs = [bytes(&[1, 2])];
+        assert_eq!(
+            registry.solve(BlackBoxFunc::Pedersen.name(), &UnsupportedSolver, &inputs, &[2]),
+            Ok(vec![vec![FieldElement::from(3u128); 2]])
+        );
+        assert_eq!(
+            BlackBoxRegistry::standard().get(BlackBoxFunc::Pedersen.name()).unwrap().cost,
+            BlackBoxCost::Expensive
+        );
+    }
+
+    #[test]
+    fn registers_functions_under_new_names() {
+        let mut registry = BlackBoxRegistry::new();
+        let inputs = [bytes(&[1, 2])];
+        assert_eq!(
+            registry.solve("sum", &UnsupportedSolver, &inputs, &[1]),
+            Err(BlackBoxResolutionError::UnsupportedExtension("sum".to_string()))
+        );
+
+        registry.register(BlackBoxDefinition {
+            name: "sum",
+            inputs: &[OperandSpec { name: "inputs", arity: Arity::Variable, num_bits: None }],
+            outputs: &[OperandSpec { name: "output", arity: Arity::Fixed(1), num_bits: None }],
+            native: Some(|_, inputs, _| {
+                Ok(vec![vec![inputs
+                    .operand(0)
+                    .fold(FieldElement::zero(), |sum, input| sum + input.value)]])
+            }),
+            fallback: None,
+            cost: BlackBoxCost::Cheap,
+        });
+        assert_eq!(
+            registry.solve("sum", &UnsupportedSolver, &inputs, &[1]),
+            Ok(vec![vec![FieldElement::from(3u128)]])
+        );
+        assert_eq!(
+            registry.solve("sum", &UnsupportedSolver, &inputs, &[2]),
+            Err(BlackBoxResolutionError::ExtensionFailed(
+                "sum".to_string(),
+                "expected output size 1 but received 2".to_string()
+            ))
+        );
+    }
+}
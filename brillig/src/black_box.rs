@@ -45,4 +45,56 @@ pub enum BlackBoxOp {
     Pedersen { inputs: HeapVector, domain_separator: RegisterIndex, output: HeapArray },
     /// Performs scalar multiplication over the embedded curve on which [`FieldElement`][acir_field::FieldElement] is defined.
     FixedBaseScalarMul { input: RegisterIndex, result: HeapArray },
+    /// Applies the Poseidon2 permutation to a state of `len` field elements.
+    Poseidon2Permutation { message: HeapVector, output: HeapArray, len: RegisterIndex },
+    /// Verifies an EdDSA signature over the Baby JubJub curve, using the Poseidon hash as in circomlib.
+    EddsaVerify {
+        public_key_x: RegisterIndex,
+        public_key_y: RegisterIndex,
+        signature_s: RegisterIndex,
+        signature_r8_x: RegisterIndex,
+        signature_r8_y: RegisterIndex,
+        message: RegisterIndex,
+        result: RegisterIndex,
+    },
+    /// Adds two points on the embedded curve on which [`FieldElement`][acir_field::FieldElement] is defined.
+    EmbeddedCurveAdd {
+        input1_x: RegisterIndex,
+        input1_y: RegisterIndex,
+        input2_x: RegisterIndex,
+        input2_y: RegisterIndex,
+        result: HeapArray,
+    },
+    /// Multiplies a point on the embedded curve on which [`FieldElement`][acir_field::FieldElement] is defined by a scalar.
+    VariableBaseScalarMul {
+        point_x: RegisterIndex,
+        point_y: RegisterIndex,
+        scalar: RegisterIndex,
+        result: HeapArray,
+    },
+    /// Computes `(lhs + rhs) % modulus` over big-endian bytes, where `output` has as many bytes as `modulus`.
+    BigIntAdd { lhs: HeapVector, rhs: HeapVector, modulus: HeapVector, output: HeapArray },
+    /// Computes `(lhs * rhs) % modulus` over big-endian bytes, where `output` has as many bytes as `modulus`.
+    BigIntMul { lhs: HeapVector, rhs: HeapVector, modulus: HeapVector, output: HeapArray },
+    /// Computes `input % modulus` over big-endian bytes, where `output` has as many bytes as `modulus`.
+    BigIntMod { input: HeapVector, modulus: HeapVector, output: HeapArray },
+    /// Computes the quotient and remainder of `lhs / rhs` over big-endian bytes, where `quotient` has as many bytes
+    /// as `lhs` and `remainder` has as many bytes as `rhs`.
+    BigIntDivMod { lhs: HeapVector, rhs: HeapVector, quotient: HeapArray, remainder: HeapArray },
+    /// Calculates the SHA512 hash of the inputs.
+    Sha512 { message: HeapVector, output: HeapArray },
+    /// Calculates the Blake3 hash of the inputs.
+    Blake3 { message: HeapVector, output: HeapArray },
+    /// Encrypts the inputs using AES-128 in CBC mode, padding them to a whole number of blocks following PKCS#7.
+    Aes128CbcEncrypt { inputs: HeapVector, iv: HeapArray, key: HeapArray, outputs: HeapArray },
+    /// Encrypts the inputs using AES-128 in CTR mode, where the IV is the initial counter block.
+    Aes128CtrEncrypt { inputs: HeapVector, iv: HeapArray, key: HeapArray, outputs: HeapArray },
+    /// Recovers the public key which produced an ECDSA signature over the secp256k1 curve from its recovery id.
+    EcdsaSecp256k1Recover {
+        hashed_msg: HeapVector,
+        signature: HeapArray,
+        recovery_id: RegisterIndex,
+        public_key_x: HeapArray,
+        public_key_y: HeapArray,
+    },
 }
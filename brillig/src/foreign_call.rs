@@ -1,34 +1,64 @@
 use crate::value::Value;
 use serde::{Deserialize, Serialize};
 
-/// Single output of a [foreign call][crate::Opcode::ForeignCall].
+/// Single input or output of a [foreign call][crate::Opcode::ForeignCall].
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
-pub enum ForeignCallOutput {
+pub enum ForeignCallParam {
     Single(Value),
     Array(Vec<Value>),
 }
 
+impl ForeignCallParam {
+    /// Returns the values of the parameter, treating a single value as an array of length one.
+    pub fn values(&self) -> &[Value] {
+        match self {
+            ForeignCallParam::Single(value) => std::slice::from_ref(value),
+            ForeignCallParam::Array(values) => values,
+        }
+    }
+}
+
+impl std::ops::Index<usize> for ForeignCallParam {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        &self.values()[index]
+    }
+}
+
+impl From<Value> for ForeignCallParam {
+    fn from(value: Value) -> Self {
+        ForeignCallParam::Single(value)
+    }
+}
+
+impl From<Vec<Value>> for ForeignCallParam {
+    fn from(values: Vec<Value>) -> Self {
+        ForeignCallParam::Array(values)
+    }
+}
+
 /// Represents the full output of a [foreign call][crate::Opcode::ForeignCall].
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct ForeignCallResult {
     /// Resolved output values of the foreign call.
-    pub values: Vec<ForeignCallOutput>,
+    pub values: Vec<ForeignCallParam>,
 }
 
 impl From<Value> for ForeignCallResult {
     fn from(value: Value) -> Self {
-        ForeignCallResult { values: vec![ForeignCallOutput::Single(value)] }
+        ForeignCallResult { values: vec![ForeignCallParam::Single(value)] }
     }
 }
 
 impl From<Vec<Value>> for ForeignCallResult {
     fn from(values: Vec<Value>) -> Self {
-        ForeignCallResult { values: vec![ForeignCallOutput::Array(values)] }
+        ForeignCallResult { values: vec![ForeignCallParam::Array(values)] }
     }
 }
 
-impl From<Vec<ForeignCallOutput>> for ForeignCallResult {
-    fn from(values: Vec<ForeignCallOutput>) -> Self {
+impl From<Vec<ForeignCallParam>> for ForeignCallResult {
+    fn from(values: Vec<ForeignCallParam>) -> Self {
         ForeignCallResult { values }
     }
 }
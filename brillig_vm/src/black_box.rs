@@ -1,8 +1,8 @@
-use acir::brillig::{BlackBoxOp, HeapArray, HeapVector, Value};
+use acir::brillig::{BlackBoxOp, HeapArray, HeapVector, RegisterIndex, Value};
 use acir::{BlackBoxFunc, FieldElement};
 use blackbox_solver::{
-    blake2s, ecdsa_secp256k1_verify, ecdsa_secp256r1_verify, hash_to_field_128_security, keccak256,
-    sha256, BlackBoxFunctionSolver, BlackBoxResolutionError,
+    BlackBoxFunctionSolver, BlackBoxInput, BlackBoxInputs, BlackBoxRegistry,
+    BlackBoxResolutionError, OperandSpec, BIGINT_DIV_MOD,
 };
 
 use crate::{Memory, Registers};
@@ -24,154 +24,289 @@ fn read_heap_array<'a>(
     memory.read_slice(registers.get(array.pointer).to_usize(), array.size)
 }
 
-/// Extracts the last byte of every value
-fn to_u8_vec(inputs: &[Value]) -> Vec<u8> {
-    let mut result = Vec::with_capacity(inputs.len());
-    for input in inputs {
-        let field_bytes = input.to_field().to_be_bytes();
-        let byte = field_bytes.last().unwrap();
-        result.push(*byte);
+/// The location of an operand of a blackbox function.
+enum Operand {
+    Vector(HeapVector),
+    Array(HeapArray),
+    Register(RegisterIndex),
+    /// An optional operand which is not present.
+    Absent,
+}
+
+/// The values of an input operand, borrowed from memory where possible.
+enum OperandValues<'a> {
+    Memory(&'a [Value]),
+    Register(Value),
+}
+
+impl Operand {
+    fn values<'a>(&self, memory: &'a Memory, registers: &Registers) -> OperandValues<'a> {
+        match self {
+            Operand::Vector(vector) => {
+                OperandValues::Memory(read_heap_vector(memory, registers, vector))
+            }
+            Operand::Array(array) => {
+                OperandValues::Memory(read_heap_array(memory, registers, array))
+            }
+            Operand::Register(register) => OperandValues::Register(registers.get(*register)),
+            Operand::Absent => OperandValues::Memory(&[]),
+        }
+    }
+
+    fn size(&self, registers: &Registers) -> usize {
+        match self {
+            Operand::Vector(vector) => registers.get(vector.size).to_usize(),
+            Operand::Array(array) => array.size,
+            Operand::Register(_) => 1,
+            Operand::Absent => 0,
+        }
+    }
+
+    fn write(&self, memory: &mut Memory, registers: &mut Registers, values: Vec<FieldElement>) {
+        let values: Vec<Value> = values.into_iter().map(Value::from).collect();
+        match self {
+            Operand::Vector(HeapVector { pointer, .. })
+            | Operand::Array(HeapArray { pointer, .. }) => {
+                memory.write_slice(registers.get(*pointer).to_usize(), &values)
+            }
+            Operand::Register(register) => registers.set(*register, values[0]),
+            Operand::Absent => (),
+        }
     }
-    result
 }
 
-fn to_value_vec(input: &[u8]) -> Vec<Value> {
-    input.iter().map(|x| Value::from(*x as usize)).collect()
+/// The input operands of a blackbox function, read from memory and registers as they are requested.
+///
+/// Brillig values carry no bit widths so each operand is read with the width given by its definition.
+struct VmInputs<'a> {
+    operands: Vec<OperandValues<'a>>,
+    specs: &'static [OperandSpec],
 }
 
-pub(crate) fn evaluate_black_box<Solver: BlackBoxFunctionSolver>(
-    op: &BlackBoxOp,
-    solver: &Solver,
-    registers: &mut Registers,
-    memory: &mut Memory,
-) -> Result<(), BlackBoxResolutionError> {
-    match op {
+impl BlackBoxInputs for VmInputs<'_> {
+    fn num_operands(&self) -> usize {
+        self.operands.len()
+    }
+
+    fn operand_len(&self, operand: usize) -> usize {
+        match &self.operands[operand] {
+            OperandValues::Memory(values) => values.len(),
+            OperandValues::Register(_) => 1,
+        }
+    }
+
+    fn get(&self, operand: usize, index: usize) -> BlackBoxInput {
+        let value = match &self.operands[operand] {
+            OperandValues::Memory(values) => values[index],
+            OperandValues::Register(value) => {
+                assert_eq!(index, 0, "registers hold a single value");
+                *value
+            }
+        };
+        let num_bits = self
+            .specs
+            .get(operand)
+            .and_then(|spec| spec.num_bits)
+            .unwrap_or_else(FieldElement::max_num_bits);
+        BlackBoxInput { value: value.to_field(), num_bits }
+    }
+}
+
+/// Returns the name of the blackbox function evaluated by `op` along with the locations of its input and output
+/// operands, in the order given by the function's [definition][blackbox_solver::BlackBoxDefinition].
+fn black_box_operands(op: &BlackBoxOp) -> (&'static str, Vec<Operand>, Vec<Operand>) {
+    use Operand::{Absent, Array, Register, Vector};
+
+    match *op {
         BlackBoxOp::Sha256 { message, output } => {
-            let message = to_u8_vec(read_heap_vector(memory, registers, message));
-            let bytes = sha256(message.as_slice())?;
-            memory.write_slice(registers.get(output.pointer).to_usize(), &to_value_vec(&bytes));
-            Ok(())
+            (BlackBoxFunc::SHA256.name(), vec![Vector(message)], vec![Array(output)])
         }
         BlackBoxOp::Blake2s { message, output } => {
-            let message = to_u8_vec(read_heap_vector(memory, registers, message));
-            let bytes = blake2s(message.as_slice())?;
-            memory.write_slice(registers.get(output.pointer).to_usize(), &to_value_vec(&bytes));
-            Ok(())
+            (BlackBoxFunc::Blake2s.name(), vec![Vector(message)], vec![Array(output)])
         }
-        BlackBoxOp::Keccak256 { message, output } => {
-            let message = to_u8_vec(read_heap_vector(memory, registers, message));
-            let bytes = keccak256(message.as_slice())?;
-            memory.write_slice(registers.get(output.pointer).to_usize(), &to_value_vec(&bytes));
-            Ok(())
+        BlackBoxOp::Sha512 { message, output } => {
+            (BlackBoxFunc::SHA512.name(), vec![Vector(message)], vec![Array(output)])
         }
-        BlackBoxOp::HashToField128Security { message, output } => {
-            let field = hash_to_field_128_security(&to_u8_vec(read_heap_vector(
-                memory, registers, message,
-            )))?;
-            registers.set(*output, field.into());
-            Ok(())
+        BlackBoxOp::Blake3 { message, output } => {
+            (BlackBoxFunc::Blake3.name(), vec![Vector(message)], vec![Array(output)])
+        }
+        BlackBoxOp::Keccak256 { message, output } => {
+            (BlackBoxFunc::Keccak256.name(), vec![Vector(message), Absent], vec![Array(output)])
         }
+        BlackBoxOp::HashToField128Security { message, output } => (
+            BlackBoxFunc::HashToField128Security.name(),
+            vec![Vector(message)],
+            vec![Register(output)],
+        ),
+        BlackBoxOp::Aes128CbcEncrypt { inputs, iv, key, outputs } => (
+            BlackBoxFunc::AES128CbcEncrypt.name(),
+            vec![Vector(inputs), Array(iv), Array(key)],
+            vec![Array(outputs)],
+        ),
+        BlackBoxOp::Aes128CtrEncrypt { inputs, iv, key, outputs } => (
+            BlackBoxFunc::AES128CtrEncrypt.name(),
+            vec![Vector(inputs), Array(iv), Array(key)],
+            vec![Array(outputs)],
+        ),
         BlackBoxOp::EcdsaSecp256k1 {
             hashed_msg,
             public_key_x,
             public_key_y,
             signature,
-            result: result_register,
-        }
-        | BlackBoxOp::EcdsaSecp256r1 {
+            result,
+        } => (
+            BlackBoxFunc::EcdsaSecp256k1.name(),
+            vec![Array(public_key_x), Array(public_key_y), Array(signature), Vector(hashed_msg)],
+            vec![Register(result)],
+        ),
+        BlackBoxOp::EcdsaSecp256r1 {
             hashed_msg,
             public_key_x,
             public_key_y,
             signature,
-            result: result_register,
-        } => {
-            let bb_func = match op {
-                BlackBoxOp::EcdsaSecp256k1 { .. } => BlackBoxFunc::EcdsaSecp256k1,
-                BlackBoxOp::EcdsaSecp256r1 { .. } => BlackBoxFunc::EcdsaSecp256r1,
-                _ => unreachable!(),
-            };
-
-            let public_key_x: [u8; 32] = to_u8_vec(read_heap_array(
-                memory,
-                registers,
-                public_key_x,
-            ))
-            .try_into()
-            .map_err(|_| {
-                BlackBoxResolutionError::Failed(bb_func, "Invalid public key x length".to_string())
-            })?;
-            let public_key_y: [u8; 32] = to_u8_vec(read_heap_array(
-                memory,
-                registers,
-                public_key_y,
-            ))
-            .try_into()
-            .map_err(|_| {
-                BlackBoxResolutionError::Failed(bb_func, "Invalid public key y length".to_string())
-            })?;
-            let signature: [u8; 64] = to_u8_vec(read_heap_array(memory, registers, signature))
-                .try_into()
-                .map_err(|_| {
-                    BlackBoxResolutionError::Failed(bb_func, "Invalid signature length".to_string())
-                })?;
-
-            let hashed_msg = to_u8_vec(read_heap_vector(memory, registers, hashed_msg));
-
-            let result = match op {
-                BlackBoxOp::EcdsaSecp256k1 { .. } => {
-                    ecdsa_secp256k1_verify(&hashed_msg, &public_key_x, &public_key_y, &signature)?
-                }
-                BlackBoxOp::EcdsaSecp256r1 { .. } => {
-                    ecdsa_secp256r1_verify(&hashed_msg, &public_key_x, &public_key_y, &signature)?
-                }
-                _ => unreachable!(),
-            };
-
-            registers.set(*result_register, result.into());
-            Ok(())
-        }
-        BlackBoxOp::SchnorrVerify { public_key_x, public_key_y, message, signature, result } => {
-            let public_key_x = registers.get(*public_key_x).to_field();
-            let public_key_y = registers.get(*public_key_y).to_field();
-            let message: Vec<u8> = to_u8_vec(read_heap_vector(memory, registers, message));
-            let signature: Vec<u8> = to_u8_vec(read_heap_vector(memory, registers, signature));
-            let verified =
-                solver.schnorr_verify(&public_key_x, &public_key_y, &signature, &message)?;
-            registers.set(*result, verified.into());
-            Ok(())
-        }
+            result,
+        } => (
+            BlackBoxFunc::EcdsaSecp256r1.name(),
+            vec![Array(public_key_x), Array(public_key_y), Array(signature), Vector(hashed_msg)],
+            vec![Register(result)],
+        ),
+        BlackBoxOp::EcdsaSecp256k1Recover {
+            hashed_msg,
+            signature,
+            recovery_id,
+            public_key_x,
+            public_key_y,
+        } => (
+            BlackBoxFunc::EcdsaSecp256k1Recover.name(),
+            vec![Vector(hashed_msg), Array(signature), Register(recovery_id)],
+            vec![Array(public_key_x), Array(public_key_y)],
+        ),
+        BlackBoxOp::SchnorrVerify { public_key_x, public_key_y, message, signature, result } => (
+            BlackBoxFunc::SchnorrVerify.name(),
+            vec![
+                Register(public_key_x),
+                Register(public_key_y),
+                Vector(signature),
+                Vector(message),
+            ],
+            vec![Register(result)],
+        ),
+        BlackBoxOp::EddsaVerify {
+            public_key_x,
+            public_key_y,
+            signature_s,
+            signature_r8_x,
+            signature_r8_y,
+            message,
+            result,
+        } => (
+            BlackBoxFunc::EddsaVerify.name(),
+            vec![
+                Register(public_key_x),
+                Register(public_key_y),
+                Register(signature_s),
+                Register(signature_r8_x),
+                Register(signature_r8_y),
+                Register(message),
+            ],
+            vec![Register(result)],
+        ),
         BlackBoxOp::FixedBaseScalarMul { input, result } => {
-            let input = registers.get(*input).to_field();
-            let (x, y) = solver.fixed_base_scalar_mul(&input)?;
-            memory.write_slice(registers.get(result.pointer).to_usize(), &[x.into(), y.into()]);
-            Ok(())
-        }
-        BlackBoxOp::Pedersen { inputs, domain_separator, output } => {
-            let inputs: Vec<FieldElement> =
-                read_heap_vector(memory, registers, inputs).iter().map(|x| x.to_field()).collect();
-            let domain_separator: u32 =
-                registers.get(*domain_separator).to_u128().try_into().map_err(|_| {
-                    BlackBoxResolutionError::Failed(
-                        BlackBoxFunc::Pedersen,
-                        "Invalid signature length".to_string(),
-                    )
-                })?;
-            let (x, y) = solver.pedersen(&inputs, domain_separator)?;
-            memory.write_slice(registers.get(output.pointer).to_usize(), &[x.into(), y.into()]);
-            Ok(())
+            (BlackBoxFunc::FixedBaseScalarMul.name(), vec![Register(input)], vec![Array(result)])
         }
+        BlackBoxOp::EmbeddedCurveAdd { input1_x, input1_y, input2_x, input2_y, result } => (
+            BlackBoxFunc::EmbeddedCurveAdd.name(),
+            vec![Register(input1_x), Register(input1_y), Register(input2_x), Register(input2_y)],
+            vec![Array(result)],
+        ),
+        BlackBoxOp::VariableBaseScalarMul { point_x, point_y, scalar, result } => (
+            BlackBoxFunc::VariableBaseScalarMul.name(),
+            vec![Register(point_x), Register(point_y), Register(scalar)],
+            vec![Array(result)],
+        ),
+        BlackBoxOp::Pedersen { inputs, domain_separator, output } => (
+            BlackBoxFunc::Pedersen.name(),
+            vec![Vector(inputs), Register(domain_separator)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::BigIntAdd { lhs, rhs, modulus, output } => (
+            BlackBoxFunc::BigIntAdd.name(),
+            vec![Vector(lhs), Vector(rhs), Vector(modulus)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::BigIntMul { lhs, rhs, modulus, output } => (
+            BlackBoxFunc::BigIntMul.name(),
+            vec![Vector(lhs), Vector(rhs), Vector(modulus)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::BigIntMod { input, modulus, output } => (
+            BlackBoxFunc::BigIntMod.name(),
+            vec![Vector(input), Vector(modulus)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::Poseidon2Permutation { message, output, len } => (
+            BlackBoxFunc::Poseidon2Permutation.name(),
+            vec![Vector(message), Register(len)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::BigIntDivMod { lhs, rhs, quotient, remainder } => (
+            BIGINT_DIV_MOD,
+            vec![Vector(lhs), Vector(rhs)],
+            vec![Array(quotient), Array(remainder)],
+        ),
+    }
+}
+
+pub(crate) fn evaluate_black_box<Solver: BlackBoxFunctionSolver>(
+    op: &BlackBoxOp,
+    solver: &Solver,
+    registry: &BlackBoxRegistry,
+    registers: &mut Registers,
+    memory: &mut Memory,
+) -> Result<(), BlackBoxResolutionError> {
+    let (name, input_operands, output_operands) = black_box_operands(op);
+    let definition =
+        registry.get(name).ok_or_else(|| BlackBoxResolutionError::unsupported(name))?;
+
+    let inputs = VmInputs {
+        operands: input_operands.iter().map(|operand| operand.values(memory, registers)).collect(),
+        specs: definition.inputs,
+    };
+    let output_sizes: Vec<usize> =
+        output_operands.iter().map(|operand| operand.size(registers)).collect();
+
+    let outputs = definition.solve(solver, &inputs, &output_sizes)?;
+    for (operand, values) in output_operands.iter().zip(outputs) {
+        operand.write(memory, registers, values);
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use acir::brillig::BlackBoxOp;
+    use acir::{brillig::BlackBoxOp, FieldElement};
+    use blackbox_solver::BlackBoxRegistry;
 
     use crate::{
-        black_box::{evaluate_black_box, to_u8_vec, to_value_vec},
-        DummyBlackBoxSolver, HeapArray, HeapVector, Memory, Registers, Value,
+        black_box::evaluate_black_box, DummyBlackBoxSolver, HeapArray, HeapVector, Memory,
+        Registers, Value,
     };
 
+    /// Extracts the last byte of every value
+    fn to_u8_vec(inputs: &[Value]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let field_bytes = input.to_field().to_be_bytes();
+            let byte = field_bytes.last().unwrap();
+            result.push(*byte);
+        }
+        result
+    }
+
+    fn to_value_vec(input: &[u8]) -> Vec<Value> {
+        input.iter().map(|x| Value::from(*x as usize)).collect()
+    }
+
     #[test]
     fn sha256() {
         let message: Vec<u8> = b"hello world".to_vec();
@@ -195,7 +330,14 @@ mod test {
             output: HeapArray { pointer: 2.into(), size: 32 },
         };
 
-        evaluate_black_box(&op, &DummyBlackBoxSolver, &mut registers, &mut memory).unwrap();
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
 
         let result = memory.read_slice(result_pointer, 32);
 
@@ -207,4 +349,239 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn blake3() {
+        let message: Vec<u8> = b"hello world".to_vec();
+        let message_length = message.len();
+
+        let mut memory = Memory::from(vec![]);
+        let message_pointer = 0;
+        let result_pointer = message_pointer + message_length;
+        memory.write_slice(message_pointer, to_value_vec(&message).as_slice());
+
+        let mut registers = Registers {
+            inner: vec![
+                Value::from(message_pointer),
+                Value::from(message_length),
+                Value::from(result_pointer),
+            ],
+        };
+
+        let op = BlackBoxOp::Blake3 {
+            message: HeapVector { pointer: 0.into(), size: 1.into() },
+            output: HeapArray { pointer: 2.into(), size: 32 },
+        };
+
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
+
+        let result = memory.read_slice(result_pointer, 32);
+
+        assert_eq!(
+            to_u8_vec(result),
+            vec![
+                215, 73, 129, 239, 167, 10, 12, 136, 11, 141, 140, 25, 133, 208, 117, 219, 203,
+                246, 121, 185, 154, 95, 153, 20, 229, 170, 249, 107, 131, 26, 158, 36
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bn254")]
+    fn poseidon2_permutation() {
+        let state: Vec<Value> = (0u128..4).map(Value::from).collect();
+
+        let mut memory = Memory::from(vec![]);
+        let state_pointer = 0;
+        let result_pointer = state_pointer + state.len();
+        memory.write_slice(state_pointer, &state);
+
+        let mut registers = Registers {
+            inner: vec![
+                Value::from(state_pointer),
+                Value::from(state.len()),
+                Value::from(result_pointer),
+            ],
+        };
+
+        let op = BlackBoxOp::Poseidon2Permutation {
+            message: HeapVector { pointer: 0.into(), size: 1.into() },
+            output: HeapArray { pointer: 2.into(), size: 4 },
+            len: 1.into(),
+        };
+
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
+
+        let result = memory.read_slice(result_pointer, 4);
+        assert_eq!(
+            result[0].to_field().to_hex(),
+            "01bd538c2ee014ed5141b29e9ae240bf8db3fe5b9a38629a9647cf8d76c01737"
+        );
+    }
+
+    #[test]
+    fn bigint_mul() {
+        // 0x0123 * 0x0456 = 0x04edc2, which is 0xf1be modulo 0xff01
+        let lhs = to_value_vec(&[0x01, 0x23]);
+        let rhs = to_value_vec(&[0x04, 0x56]);
+        let modulus = to_value_vec(&[0xff, 0x01]);
+
+        let mut memory = Memory::from(vec![]);
+        memory.write_slice(0, &lhs);
+        memory.write_slice(2, &rhs);
+        memory.write_slice(4, &modulus);
+        let result_pointer = 6;
+
+        let mut registers = Registers {
+            inner: [0, 2, 4, 2, result_pointer].into_iter().map(Value::from).collect(),
+        };
+
+        let op = BlackBoxOp::BigIntMul {
+            lhs: HeapVector { pointer: 0.into(), size: 3.into() },
+            rhs: HeapVector { pointer: 1.into(), size: 3.into() },
+            modulus: HeapVector { pointer: 2.into(), size: 3.into() },
+            output: HeapArray { pointer: 4.into(), size: 2 },
+        };
+
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
+
+        assert_eq!(to_u8_vec(memory.read_slice(result_pointer, 2)), vec![0xf1, 0xbe]);
+    }
+
+    #[test]
+    fn bigint_div_mod() {
+        // 0x03e8 = 142 * 7 + 6
+        let lhs = to_value_vec(&[0x03, 0xe8]);
+        let rhs = to_value_vec(&[0x07]);
+
+        let mut memory = Memory::from(vec![]);
+        memory.write_slice(0, &lhs);
+        memory.write_slice(2, &rhs);
+        let (quotient_pointer, remainder_pointer) = (3, 5);
+
+        let mut registers = Registers {
+            inner: [0, 2, 2, 1, quotient_pointer, remainder_pointer]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+        };
+
+        let op = BlackBoxOp::BigIntDivMod {
+            lhs: HeapVector { pointer: 0.into(), size: 2.into() },
+            rhs: HeapVector { pointer: 1.into(), size: 3.into() },
+            quotient: HeapArray { pointer: 4.into(), size: 2 },
+            remainder: HeapArray { pointer: 5.into(), size: 1 },
+        };
+
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
+
+        assert_eq!(to_u8_vec(memory.read_slice(quotient_pointer, 2)), vec![0, 142]);
+        assert_eq!(to_u8_vec(memory.read_slice(remainder_pointer, 1)), vec![6]);
+    }
+
+    #[test]
+    fn aes128_ctr_encrypt() {
+        // Test vector from NIST SP 800-38A, appendix F.5.1.
+        let plaintext = to_value_vec(&[0x6b, 0xc1, 0xbe, 0xe2]);
+        let iv: Vec<u8> = (0xf0..=0xff).collect();
+        let key = [
+            0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+            0x4f, 0x3c,
+        ];
+
+        let mut memory = Memory::from(vec![]);
+        memory.write_slice(0, &plaintext);
+        memory.write_slice(4, &to_value_vec(&iv));
+        memory.write_slice(20, &to_value_vec(&key));
+        let result_pointer = 36;
+
+        let mut registers = Registers {
+            inner: [0, 4, 4, 20, result_pointer].into_iter().map(Value::from).collect(),
+        };
+
+        let op = BlackBoxOp::Aes128CtrEncrypt {
+            inputs: HeapVector { pointer: 0.into(), size: 1.into() },
+            iv: HeapArray { pointer: 2.into(), size: 16 },
+            key: HeapArray { pointer: 3.into(), size: 16 },
+            outputs: HeapArray { pointer: 4.into(), size: 4 },
+        };
+
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
+
+        assert_eq!(to_u8_vec(memory.read_slice(result_pointer, 4)), vec![0x87, 0x4d, 0x61, 0x91]);
+    }
+
+    #[test]
+    #[cfg(feature = "bn254")]
+    fn eddsa_verify() {
+        // The Poseidon signature of the message 1234 from circomlibjs' EdDSA tests.
+        let inputs = [
+            "0x1d5ac1f31407018b7d413a4f52c8f74463b30e6ac2238220ad8b254de4eaa3a2",
+            "0x1e1de8a908826c3f9ac2e0ceee929ecd0caf3b99b3ef24523aaab796a6f733c4",
+            "0x0471b36e0376770c8eb33853d39a2b24b2dd647898d65ac4fe0ae8dcf9a59cdd",
+            "0x18ceb45c7a6da96b750ae8b4eae07737f39ec4b9396f60d82c1d7797d3b3ef14",
+            "0x053bf0d678b6119c61565d2d949a0b5851344053af540908873a382612a89a4e",
+        ]
+        .map(|hex| Value::from(FieldElement::from_hex(hex).unwrap()));
+
+        let mut memory = Memory::from(vec![]);
+        let mut registers = Registers { inner: inputs.to_vec() };
+        registers.inner.extend([Value::from(1234u128), Value::from(0u128)]);
+
+        let op = BlackBoxOp::EddsaVerify {
+            public_key_x: 0.into(),
+            public_key_y: 1.into(),
+            signature_s: 2.into(),
+            signature_r8_x: 3.into(),
+            signature_r8_y: 4.into(),
+            message: 5.into(),
+            result: 6.into(),
+        };
+
+        evaluate_black_box(
+            &op,
+            &DummyBlackBoxSolver,
+            BlackBoxRegistry::standard(),
+            &mut registers,
+            &mut memory,
+        )
+        .unwrap();
+
+        assert_eq!(registers.get(6.into()), Value::from(true));
+    }
 }
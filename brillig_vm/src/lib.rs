@@ -10,8 +10,8 @@
 //! [acvm]: https://crates.io/crates/acvm
 
 use acir::brillig::{
-    BinaryFieldOp, BinaryIntOp, ForeignCallOutput, ForeignCallResult, HeapArray, HeapVector,
-    Opcode, RegisterIndex, RegisterOrMemory, Value,
+    BinaryFieldOp, BinaryIntOp, ForeignCallParam, ForeignCallResult, HeapArray, HeapVector, Opcode,
+    RegisterIndex, RegisterOrMemory, Value,
 };
 use acir::FieldElement;
 // Re-export `brillig`.
@@ -20,19 +20,54 @@ pub use acir::brillig;
 mod arithmetic;
 mod black_box;
 mod memory;
+mod profile;
 mod registers;
+mod trace;
 
 use arithmetic::{evaluate_binary_bigint_op, evaluate_binary_field_op};
 use black_box::evaluate_black_box;
-use blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
+use blackbox_solver::{BlackBoxFunctionSolver, BlackBoxRegistry, BlackBoxResolutionError};
 
 pub use memory::Memory;
 use num_bigint::BigUint;
+pub use profile::BrilligProfile;
 pub use registers::Registers;
+use std::time::Instant;
+use trace::Tracer;
+pub use trace::{BrilligStep, BrilligTrace};
 
 /// The error call stack contains the opcode indexes of the call stack at the time of failure, plus the index of the opcode that failed.
 pub type ErrorCallStack = Vec<usize>;
 
+/// The number of opcodes processed between each check of the [deadline][VMLimits::deadline].
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// Bounds on the resources which may be consumed by a Brillig process.
+///
+/// All limits are unbounded by default.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct VMLimits {
+    /// The maximum number of opcodes which may be processed.
+    pub max_steps: Option<u64>,
+    /// The maximum number of values which memory may hold.
+    pub max_memory_size: Option<usize>,
+    /// The maximum depth of nested calls.
+    pub max_call_depth: Option<usize>,
+    /// The time by which the process must have halted.
+    ///
+    /// This is not supported on targets without a system clock such as `wasm32-unknown-unknown`.
+    pub deadline: Option<Instant>,
+}
+
+/// The [limit][VMLimits] which caused a Brillig process to halt.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LimitExceeded {
+    Steps(u64),
+    MemorySize(usize),
+    CallDepth(usize),
+    Deadline,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum VMStatus {
     Finished,
@@ -51,8 +86,13 @@ pub enum VMStatus {
         /// Interpreted by simulator context
         function: String,
         /// Input values
-        /// Each input is a list of values as an input can be either a single value or a memory pointer
-        inputs: Vec<Vec<Value>>,
+        /// Each input is either a single value or the values read from a memory pointer
+        inputs: Vec<ForeignCallParam>,
+    },
+    /// The VM process was halted as it exceeded one of its [resource limits][VMLimits].
+    LimitExceeded {
+        limit: LimitExceeded,
+        call_stack: ErrorCallStack,
     },
 }
 
@@ -79,6 +119,16 @@ pub struct VM<'bb_solver, B: BlackBoxFunctionSolver> {
     call_stack: Vec<Value>,
     /// The solver for blackbox functions
     black_box_solver: &'bb_solver B,
+    /// Describes how each blackbox function is solved
+    black_box_registry: &'bb_solver BlackBoxRegistry,
+    /// Bounds on the resources which this process may consume
+    limits: VMLimits,
+    /// The number of opcodes which have been processed
+    steps: u64,
+    /// Counts of the instructions executed, if profiling is enabled
+    profile: Option<BrilligProfile>,
+    /// The changes made by each instruction executed, if tracing is enabled
+    tracer: Option<Tracer>,
 }
 
 impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
@@ -100,7 +150,60 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
             memory: memory.into(),
             call_stack: Vec::new(),
             black_box_solver,
+            black_box_registry: BlackBoxRegistry::standard(),
+            limits: VMLimits::default(),
+            steps: 0,
+            profile: None,
+            tracer: None,
+        }
+    }
+
+    /// Solves blackbox functions using `registry` rather than the [standard registry][BlackBoxRegistry::standard].
+    pub fn with_black_box_registry(mut self, registry: &'bb_solver BlackBoxRegistry) -> Self {
+        self.black_box_registry = registry;
+        self
+    }
+
+    /// Records a [`BrilligProfile`] of the instructions executed by this process.
+    pub fn with_profiling(mut self) -> Self {
+        self.profile = Some(BrilligProfile::default());
+        self
+    }
+
+    /// Returns the instructions executed so far if profiling is enabled.
+    pub fn profile(&self) -> Option<&BrilligProfile> {
+        self.profile.as_ref()
+    }
+
+    /// Records a [`BrilligTrace`] of the registers and memory modified by each instruction executed by this process.
+    pub fn with_tracing(mut self) -> Self {
+        self.memory.log_writes();
+        self.tracer = Some(Tracer::new(&self.registers, &self.memory));
+        self
+    }
+
+    /// Returns the instructions executed so far if tracing is enabled.
+    pub fn trace(&self) -> Option<&BrilligTrace> {
+        self.tracer.as_ref().map(Tracer::trace)
+    }
+
+    /// Consumes the VM, returning the instructions executed if tracing is enabled.
+    pub fn into_trace(self) -> Option<BrilligTrace> {
+        self.tracer.map(Tracer::into_trace)
+    }
+
+    /// Takes the instructions executed so far if profiling is enabled, leaving an empty profile in its place.
+    pub fn take_profile(&mut self) -> Option<BrilligProfile> {
+        self.profile.as_mut().map(std::mem::take)
+    }
+
+    /// Halts the VM with a [`VMStatus::LimitExceeded`] status if it exceeds any of `limits`.
+    pub fn with_limits(mut self, limits: VMLimits) -> Self {
+        if let Some(max_memory_size) = limits.max_memory_size {
+            self.memory.set_max_size(max_memory_size);
         }
+        self.limits = limits;
+        self
     }
 
     /// Updates the current status of the VM.
@@ -117,7 +220,11 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
 
     /// Sets the status of the VM to `ForeignCallWait`.
     /// Indicating that the VM is now waiting for a foreign call to be resolved.
-    fn wait_for_foreign_call(&mut self, function: String, inputs: Vec<Vec<Value>>) -> VMStatus {
+    fn wait_for_foreign_call(
+        &mut self,
+        function: String,
+        inputs: Vec<ForeignCallParam>,
+    ) -> VMStatus {
         self.status(VMStatus::ForeignCallWait { function, inputs })
     }
 
@@ -132,11 +239,38 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
         self.status.clone()
     }
 
+    /// Sets the current status of the VM to `LimitExceeded`.
+    /// Indicating that the VM has exceeded one of its resource limits.
+    fn exceed_limit(&mut self, limit: LimitExceeded) -> VMStatus {
+        let mut error_stack: Vec<_> =
+            self.call_stack.iter().map(|value| value.to_usize()).collect();
+        error_stack.push(self.program_counter);
+        self.status(VMStatus::LimitExceeded { limit, call_stack: error_stack })
+    }
+
+    /// Returns the limit which would be exceeded by processing another opcode, if any.
+    fn check_limits(&self) -> Option<LimitExceeded> {
+        if let Some(max_steps) = self.limits.max_steps {
+            if self.steps >= max_steps {
+                return Some(LimitExceeded::Steps(max_steps));
+            }
+        }
+        if let Some(deadline) = self.limits.deadline {
+            if self.steps % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline {
+                return Some(LimitExceeded::Deadline);
+            }
+        }
+        None
+    }
+
     /// Loop over the bytecode and update the program counter
     pub fn process_opcodes(&mut self) -> VMStatus {
         while !matches!(
             self.process_opcode(),
-            VMStatus::Finished | VMStatus::Failure { .. } | VMStatus::ForeignCallWait { .. }
+            VMStatus::Finished
+                | VMStatus::Failure { .. }
+                | VMStatus::ForeignCallWait { .. }
+                | VMStatus::LimitExceeded { .. }
         ) {}
         self.status.clone()
     }
@@ -152,6 +286,31 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
 
     /// Process a single opcode and modify the program counter.
     pub fn process_opcode(&mut self) -> VMStatus {
+        if let Some(limit) = self.check_limits() {
+            return self.exceed_limit(limit);
+        }
+        self.steps += 1;
+        if let Some(profile) = &mut self.profile {
+            profile.record(self.program_counter, &self.bytecode[self.program_counter]);
+        }
+
+        let program_counter = self.program_counter;
+        let status = self.execute_opcode();
+        if let Some(tracer) = &mut self.tracer {
+            tracer.record(program_counter, &self.registers, self.memory.take_write_log());
+        }
+        if self.memory.limit_exceeded() {
+            // Report the opcode which attempted the write rather than the one following it.
+            self.program_counter = program_counter;
+            let max_memory_size =
+                self.limits.max_memory_size.expect("memory limit can only be exceeded if set");
+            return self.exceed_limit(LimitExceeded::MemorySize(max_memory_size));
+        }
+        status
+    }
+
+    /// Executes the opcode at the current program counter.
+    fn execute_opcode(&mut self) -> VMStatus {
         let opcode = &self.bytecode[self.program_counter];
         match opcode {
             Opcode::BinaryFieldOp { op, lhs, rhs, destination: result } => {
@@ -208,7 +367,7 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
                 for (destination, output) in destinations.iter().zip(values) {
                     match destination {
                         RegisterOrMemory::RegisterIndex(value_index) => match output {
-                            ForeignCallOutput::Single(value) => {
+                            ForeignCallParam::Single(value) => {
                                 self.registers.set(*value_index, *value)
                             }
                             _ => unreachable!(
@@ -217,7 +376,7 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
                         },
                         RegisterOrMemory::HeapArray(HeapArray { pointer: pointer_index, size }) => {
                             match output {
-                                ForeignCallOutput::Array(values) => {
+                                ForeignCallParam::Array(values) => {
                                     if values.len() != *size {
                                         invalid_foreign_call_result = true;
                                         break;
@@ -234,7 +393,7 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
                         }
                         RegisterOrMemory::HeapVector(HeapVector { pointer: pointer_index, size: size_index }) => {
                             match output {
-                                ForeignCallOutput::Array(values) => {
+                                ForeignCallParam::Array(values) => {
                                     // Set our size in the size register
                                     self.registers.set(*size_index, Value::from(values.len()));
                                     // Convert the destination pointer to a usize
@@ -284,6 +443,11 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
                 self.increment_program_counter()
             }
             Opcode::Call { location } => {
+                if let Some(max_call_depth) = self.limits.max_call_depth {
+                    if self.call_stack.len() >= max_call_depth {
+                        return self.exceed_limit(LimitExceeded::CallDepth(max_call_depth));
+                    }
+                }
                 // Push a return location
                 self.call_stack.push(Value::from(self.program_counter));
                 self.set_program_counter(*location)
@@ -296,6 +460,7 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
                 match evaluate_black_box(
                     black_box_op,
                     self.black_box_solver,
+                    self.black_box_registry,
                     &mut self.registers,
                     &mut self.memory,
                 ) {
@@ -328,14 +493,12 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
         self.status.clone()
     }
 
-    fn get_register_value_or_memory_values(&self, input: RegisterOrMemory) -> Vec<Value> {
+    fn get_register_value_or_memory_values(&self, input: RegisterOrMemory) -> ForeignCallParam {
         match input {
-            RegisterOrMemory::RegisterIndex(value_index) => {
-                vec![self.registers.get(value_index)]
-            }
+            RegisterOrMemory::RegisterIndex(value_index) => self.registers.get(value_index).into(),
             RegisterOrMemory::HeapArray(HeapArray { pointer: pointer_index, size }) => {
                 let start = self.registers.get(pointer_index);
-                self.memory.read_slice(start.to_usize(), size).to_vec()
+                self.memory.read_slice(start.to_usize(), size).to_vec().into()
             }
             RegisterOrMemory::HeapVector(HeapVector {
                 pointer: pointer_index,
@@ -343,7 +506,7 @@ impl<'bb_solver, B: BlackBoxFunctionSolver> VM<'bb_solver, B> {
             }) => {
                 let start = self.registers.get(pointer_index);
                 let size = self.registers.get(size_index);
-                self.memory.read_slice(start.to_usize(), size.to_usize()).to_vec()
+                self.memory.read_slice(start.to_usize(), size.to_usize()).to_vec().into()
             }
         }
     }
@@ -414,11 +577,29 @@ impl BlackBoxFunctionSolver for DummyBlackBoxSolver {
     ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
         Ok((4_u128.into(), 5_u128.into()))
     }
+    fn embedded_curve_add(
+        &self,
+        _input1_x: &FieldElement,
+        _input1_y: &FieldElement,
+        _input2_x: &FieldElement,
+        _input2_y: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Ok((6_u128.into(), 7_u128.into()))
+    }
+    fn variable_base_scalar_mul(
+        &self,
+        _point_x: &FieldElement,
+        _point_y: &FieldElement,
+        _scalar: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        Ok((8_u128.into(), 9_u128.into()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
 
     #[test]
     fn add_single_step_smoke() {
@@ -872,6 +1053,128 @@ mod tests {
         assert_eq!(memory, expected);
     }
 
+    #[test]
+    fn step_limit_halts_infinite_loop() {
+        let opcodes = vec![Opcode::Jump { location: 0 }];
+        let limits = VMLimits { max_steps: Some(100), ..VMLimits::default() };
+        let mut vm = VM::new(empty_registers(), vec![], opcodes, vec![], &DummyBlackBoxSolver)
+            .with_limits(limits);
+
+        assert_eq!(
+            vm.process_opcodes(),
+            VMStatus::LimitExceeded { limit: LimitExceeded::Steps(100), call_stack: vec![0] }
+        );
+    }
+
+    #[test]
+    fn call_depth_limit_halts_unbounded_recursion() {
+        let opcodes = vec![Opcode::Call { location: 0 }];
+        let limits = VMLimits { max_call_depth: Some(8), ..VMLimits::default() };
+        let mut vm = VM::new(empty_registers(), vec![], opcodes, vec![], &DummyBlackBoxSolver)
+            .with_limits(limits);
+
+        let VMStatus::LimitExceeded { limit, call_stack } = vm.process_opcodes() else {
+            panic!("expected the call depth limit to be exceeded");
+        };
+        assert_eq!(limit, LimitExceeded::CallDepth(8));
+        assert_eq!(call_stack.len(), 9);
+    }
+
+    #[test]
+    fn memory_limit_halts_oversized_write() {
+        let r_pointer = RegisterIndex::from(0);
+        let opcodes = vec![
+            Opcode::Const { destination: r_pointer, value: 1_000_000u128.into() },
+            Opcode::Store { destination_pointer: r_pointer, source: r_pointer },
+        ];
+        let limits = VMLimits { max_memory_size: Some(1024), ..VMLimits::default() };
+        let mut vm = VM::new(empty_registers(), vec![], opcodes, vec![], &DummyBlackBoxSolver)
+            .with_limits(limits);
+
+        assert_eq!(
+            vm.process_opcodes(),
+            VMStatus::LimitExceeded { limit: LimitExceeded::MemorySize(1024), call_stack: vec![1] }
+        );
+        assert!(vm.get_memory().is_empty());
+    }
+
+    #[test]
+    fn deadline_halts_execution() {
+        let opcodes = vec![Opcode::Jump { location: 0 }];
+        let limits = VMLimits { deadline: Some(Instant::now()), ..VMLimits::default() };
+        let mut vm = VM::new(empty_registers(), vec![], opcodes, vec![], &DummyBlackBoxSolver)
+            .with_limits(limits);
+
+        assert!(matches!(
+            vm.process_opcodes(),
+            VMStatus::LimitExceeded { limit: LimitExceeded::Deadline, .. }
+        ));
+    }
+
+    #[test]
+    fn profile_counts_instructions_and_calls() {
+        let r_tmp = RegisterIndex::from(0);
+        let opcodes = vec![
+            Opcode::Call { location: 3 },
+            Opcode::Call { location: 3 },
+            Opcode::Jump { location: 5 },
+            // fn at location 3
+            Opcode::Const { destination: r_tmp, value: 1u128.into() },
+            Opcode::Return,
+        ];
+        let mut vm = VM::new(empty_registers(), vec![], opcodes, vec![], &DummyBlackBoxSolver)
+            .with_profiling();
+        assert_eq!(vm.process_opcodes(), VMStatus::Finished);
+
+        let profile = vm.profile().unwrap();
+        assert_eq!(
+            profile.instruction_counts,
+            BTreeMap::from([(0, 1), (1, 1), (2, 1), (3, 2), (4, 2)])
+        );
+        assert_eq!(profile.call_counts, BTreeMap::from([(3, 2)]));
+        assert_eq!(profile.function_instruction_counts, BTreeMap::from([(0, 3), (3, 4)]));
+        assert_eq!(profile.stacks, BTreeMap::from([(vec![], 3), (vec![3], 4)]));
+    }
+
+    #[test]
+    fn trace_records_register_and_memory_writes() {
+        let r_pointer = RegisterIndex::from(0);
+        let r_value = RegisterIndex::from(1);
+        let opcodes = vec![
+            Opcode::Const { destination: r_pointer, value: 2u128.into() },
+            Opcode::Const { destination: r_value, value: 7u128.into() },
+            Opcode::Store { destination_pointer: r_pointer, source: r_value },
+        ];
+        let registers = Registers::load(vec![Value::from(0u128); 2]);
+        let mut vm =
+            VM::new(registers, vec![Value::from(1u128)], opcodes, vec![], &DummyBlackBoxSolver)
+                .with_tracing();
+        assert_eq!(vm.process_opcodes(), VMStatus::Finished);
+
+        let trace = vm.into_trace().unwrap();
+        assert_eq!(trace.initial_memory, vec![Value::from(1u128)]);
+        assert_eq!(
+            trace.steps,
+            vec![
+                BrilligStep {
+                    program_counter: 0,
+                    register_writes: vec![(r_pointer, Value::from(2u128))],
+                    memory_writes: vec![],
+                },
+                BrilligStep {
+                    program_counter: 1,
+                    register_writes: vec![(r_value, Value::from(7u128))],
+                    memory_writes: vec![],
+                },
+                BrilligStep {
+                    program_counter: 2,
+                    register_writes: vec![],
+                    memory_writes: vec![(2, Value::from(7u128))],
+                },
+            ]
+        );
+    }
+
     fn empty_registers() -> Registers {
         Registers::load(vec![Value::from(0u128); 16])
     }
@@ -919,7 +1222,7 @@ mod tests {
             vm.status,
             VMStatus::ForeignCallWait {
                 function: "double".into(),
-                inputs: vec![vec![Value::from(5u128)]]
+                inputs: vec![Value::from(5u128).into()]
             }
         );
 
@@ -980,7 +1283,7 @@ mod tests {
             vm.status,
             VMStatus::ForeignCallWait {
                 function: "matrix_2x2_transpose".into(),
-                inputs: vec![initial_matrix]
+                inputs: vec![initial_matrix.into()]
             }
         );
 
@@ -1053,13 +1356,13 @@ mod tests {
             vm.status,
             VMStatus::ForeignCallWait {
                 function: "string_double".into(),
-                inputs: vec![input_string.clone()]
+                inputs: vec![input_string.clone().into()]
             }
         );
 
         // Push result we're waiting for
         vm.foreign_call_results.push(ForeignCallResult {
-            values: vec![ForeignCallOutput::Array(output_string.clone())],
+            values: vec![ForeignCallParam::Array(output_string.clone())],
         });
 
         // Resume VM
@@ -1115,7 +1418,7 @@ mod tests {
             vm.status,
             VMStatus::ForeignCallWait {
                 function: "matrix_2x2_transpose".into(),
-                inputs: vec![initial_matrix.clone()]
+                inputs: vec![initial_matrix.clone().into()]
             }
         );
 
@@ -1200,7 +1503,7 @@ mod tests {
             vm.status,
             VMStatus::ForeignCallWait {
                 function: "matrix_2x2_transpose".into(),
-                inputs: vec![matrix_a, matrix_b]
+                inputs: vec![matrix_a.into(), matrix_b.into()]
             }
         );
 
@@ -5,15 +5,44 @@ pub struct Memory {
     // Memory is a vector of values.
     // We grow the memory when values past the end are set, extending with 0s.
     inner: Vec<Value>,
+    /// The maximum number of values which memory may grow to hold.
+    max_size: Option<usize>,
+    /// Set when a write was discarded as it would have grown memory past `max_size`.
+    limit_exceeded: bool,
+    /// Records each address written along with its new value, if enabled.
+    write_log: Option<Vec<(usize, Value)>>,
 }
 
 impl From<Vec<Value>> for Memory {
     fn from(values: Vec<Value>) -> Self {
-        Memory { inner: values }
+        Memory { inner: values, max_size: None, limit_exceeded: false, write_log: None }
     }
 }
 
 impl Memory {
+    /// Prevents memory from growing to hold more than `max_size` values.
+    ///
+    /// Writes which would exceed this size are discarded and cause [`Memory::limit_exceeded`] to return `true`.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = Some(max_size);
+        self.limit_exceeded |= self.inner.len() > max_size;
+    }
+
+    /// Returns whether a write has been discarded due to exceeding the maximum memory size.
+    pub fn limit_exceeded(&self) -> bool {
+        self.limit_exceeded
+    }
+
+    /// Starts recording each write to memory, see [`Memory::take_write_log`].
+    pub(crate) fn log_writes(&mut self) {
+        self.write_log = Some(Vec::new());
+    }
+
+    /// Returns the writes recorded since the log was last taken.
+    pub(crate) fn take_write_log(&mut self) -> Vec<(usize, Value)> {
+        self.write_log.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     /// Gets the value at pointer
     pub fn read(&self, ptr: usize) -> Value {
         self.inner[ptr]
@@ -31,11 +60,18 @@ impl Memory {
     /// Sets the values after pointer `ptr` to `values`
     pub fn write_slice(&mut self, ptr: usize, values: &[Value]) {
         // Calculate new memory size
-        let new_size = std::cmp::max(self.inner.len(), ptr + values.len());
+        let new_size = std::cmp::max(self.inner.len(), ptr.saturating_add(values.len()));
+        if self.max_size.map_or(false, |max_size| new_size > max_size) {
+            self.limit_exceeded = true;
+            return;
+        }
         // Expand memory to new size with default values if needed
         self.inner.resize(new_size, Value::from(0_usize));
 
         self.inner[ptr..ptr + values.len()].copy_from_slice(values);
+        if let Some(write_log) = &mut self.write_log {
+            write_log.extend(values.iter().enumerate().map(|(i, value)| (ptr + i, *value)));
+        }
     }
 
     /// Returns the values of the memory
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use acir::brillig::Opcode;
+use serde::Serialize;
+
+/// Counts of the instructions executed by a Brillig process.
+///
+/// Functions are identified by the bytecode location of their first opcode,
+/// with instructions executed outside of any call attributed to location 0.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize)]
+pub struct BrilligProfile {
+    /// The number of times the opcode at each bytecode location was executed.
+    pub instruction_counts: BTreeMap<usize, u64>,
+    /// The number of times each function was called.
+    pub call_counts: BTreeMap<usize, u64>,
+    /// The number of instructions executed directly within each function, excluding those of the functions it calls.
+    pub function_instruction_counts: BTreeMap<usize, u64>,
+    /// The number of instructions executed under each call stack.
+    ///
+    /// Each stack lists the functions which were active, from the outermost call to the innermost.
+    #[serde(skip)]
+    pub stacks: BTreeMap<Vec<usize>, u64>,
+    /// The functions which are currently active.
+    #[serde(skip)]
+    frames: Vec<usize>,
+}
+
+impl BrilligProfile {
+    /// Records the execution of `opcode` at bytecode location `program_counter`.
+    pub(crate) fn record(&mut self, program_counter: usize, opcode: &Opcode) {
+        *self.instruction_counts.entry(program_counter).or_default() += 1;
+        let function = self.frames.last().copied().unwrap_or_default();
+        *self.function_instruction_counts.entry(function).or_default() += 1;
+        match self.stacks.get_mut(self.frames.as_slice()) {
+            Some(count) => *count += 1,
+            None => {
+                self.stacks.insert(self.frames.clone(), 1);
+            }
+        }
+
+        match opcode {
+            Opcode::Call { location } => {
+                *self.call_counts.entry(*location).or_default() += 1;
+                self.frames.push(*location);
+            }
+            Opcode::Return => {
+                self.frames.pop();
+            }
+            _ => (),
+        }
+    }
+
+    /// Adds the counts recorded in `other` to this profile.
+    pub fn merge(&mut self, other: &BrilligProfile) {
+        fn merge_counts<K: Ord + Clone>(into: &mut BTreeMap<K, u64>, from: &BTreeMap<K, u64>) {
+            for (key, count) in from {
+                *into.entry(key.clone()).or_default() += count;
+            }
+        }
+        merge_counts(&mut self.instruction_counts, &other.instruction_counts);
+        merge_counts(&mut self.call_counts, &other.call_counts);
+        merge_counts(&mut self.function_instruction_counts, &other.function_instruction_counts);
+        merge_counts(&mut self.stacks, &other.stacks);
+    }
+}
@@ -0,0 +1,70 @@
+use acir::brillig::{RegisterIndex, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::{Memory, Registers};
+
+/// The state changes caused by executing a single Brillig opcode.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct BrilligStep {
+    /// The bytecode location of the executed opcode.
+    pub program_counter: usize,
+    /// The registers written by the opcode along with their new values.
+    pub register_writes: Vec<(RegisterIndex, Value)>,
+    /// The memory addresses written by the opcode along with their new values.
+    pub memory_writes: Vec<(usize, Value)>,
+}
+
+/// A record of every opcode executed by a Brillig process along with the registers and memory it modified.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct BrilligTrace {
+    pub initial_registers: Vec<Value>,
+    pub initial_memory: Vec<Value>,
+    pub steps: Vec<BrilligStep>,
+}
+
+/// Builds a [`BrilligTrace`] as a Brillig process executes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct Tracer {
+    trace: BrilligTrace,
+    /// The register values as of the last recorded step, used to determine which registers each opcode writes.
+    registers: Vec<Value>,
+}
+
+impl Tracer {
+    pub(crate) fn new(registers: &Registers, memory: &Memory) -> Self {
+        Tracer {
+            trace: BrilligTrace {
+                initial_registers: registers.inner.clone(),
+                initial_memory: memory.values().clone(),
+                steps: Vec::new(),
+            },
+            registers: registers.inner.clone(),
+        }
+    }
+
+    /// Records the execution of the opcode at `program_counter` which resulted in `registers` and wrote `memory_writes`.
+    pub(crate) fn record(
+        &mut self,
+        program_counter: usize,
+        registers: &Registers,
+        memory_writes: Vec<(usize, Value)>,
+    ) {
+        let mut register_writes = Vec::new();
+        for (index, value) in registers.inner.iter().enumerate() {
+            if self.registers.get(index) != Some(value) {
+                register_writes.push((RegisterIndex::from(index), *value));
+            }
+        }
+        self.registers.clone_from(&registers.inner);
+
+        self.trace.steps.push(BrilligStep { program_counter, register_writes, memory_writes });
+    }
+
+    pub(crate) fn trace(&self) -> &BrilligTrace {
+        &self.trace
+    }
+
+    pub(crate) fn into_trace(self) -> BrilligTrace {
+        self.trace
+    }
+}
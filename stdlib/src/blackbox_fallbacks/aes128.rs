@@ -0,0 +1,334 @@
+//! AES128CbcEncrypt and AES128CtrEncrypt fallback functions.
+//!
+//! Every byte of the state is held in a witness. Bytes are combined with XOR opcodes, and are doubled in GF(2^8)
+//! during `MixColumns` by decomposing them into bits.
+//!
+//! The S-box is either read from a memory block which is initialized with its table, or when memory opcodes are
+//! not supported, evaluated as the polynomial of degree 255 which interpolates it over the field. The polynomial is
+//! written in the Newton forward difference form `sum_k d_k * binomial(x, k)`, so that its evaluation by Horner's
+//! method costs a single arithmetic opcode per coefficient.
+use super::utils::{bit_decomposition, byte_decomposition, expr_into_witness};
+use crate::helpers::VariableStore;
+use acir::{
+    circuit::{
+        opcodes::{BlackBoxFuncCall, BlockId, FunctionInput, MemOp},
+        Opcode,
+    },
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+
+/// The number of bytes in a block, key and IV.
+const BLOCK_SIZE: usize = 16;
+/// The number of rounds of AES-128.
+const NUM_ROUNDS: usize = 10;
+/// The round constants of the key schedule.
+const RCON: [u8; NUM_ROUNDS] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+#[rustfmt::skip]
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Constrains `outputs` to be the encryption of `inputs` using AES-128 in CBC mode, after padding them following
+/// PKCS#7.
+///
+/// The S-box is looked up in the memory block `sbox_block` if one is given, which must not be used elsewhere in
+/// the circuit.
+pub fn aes128_cbc_encrypt(
+    inputs: Vec<Witness>,
+    iv: Vec<Witness>,
+    key: Vec<Witness>,
+    outputs: Vec<Witness>,
+    sbox_block: Option<BlockId>,
+    num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    assert_eq!(iv.len(), BLOCK_SIZE, "AES-128 requires an IV of {BLOCK_SIZE} bytes");
+    let mut circuit = Aes128Circuit::new(sbox_block, num_witness);
+    let round_keys = circuit.expand_key(&key);
+
+    let padding = BLOCK_SIZE - inputs.len() % BLOCK_SIZE;
+    let mut plaintext = inputs;
+    for _ in 0..padding {
+        let pad = circuit.constant_byte(padding as u8);
+        plaintext.push(pad);
+    }
+
+    let mut chain = iv;
+    let mut ciphertext = Vec::with_capacity(plaintext.len());
+    for block in plaintext.chunks(BLOCK_SIZE) {
+        let state =
+            block.iter().zip(&chain).map(|(byte, chain_byte)| circuit.xor(*byte, *chain_byte));
+        let state: Vec<Witness> = state.collect();
+        chain = circuit.encrypt_block(&state, &round_keys);
+        ciphertext.extend(&chain);
+    }
+
+    circuit.constrain_outputs(&ciphertext, &outputs)
+}
+
+/// Constrains `outputs` to be the encryption of `inputs` using AES-128 in CTR mode, where `iv` is the initial
+/// counter block.
+///
+/// The S-box is looked up in the memory block `sbox_block` if one is given, which must not be used elsewhere in
+/// the circuit.
+pub fn aes128_ctr_encrypt(
+    inputs: Vec<Witness>,
+    iv: Vec<Witness>,
+    key: Vec<Witness>,
+    outputs: Vec<Witness>,
+    sbox_block: Option<BlockId>,
+    num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    assert_eq!(iv.len(), BLOCK_SIZE, "AES-128 requires an IV of {BLOCK_SIZE} bytes");
+    let mut circuit = Aes128Circuit::new(sbox_block, num_witness);
+    let round_keys = circuit.expand_key(&key);
+
+    let mut ciphertext = Vec::with_capacity(inputs.len());
+    for (index, block) in inputs.chunks(BLOCK_SIZE).enumerate() {
+        let counter = circuit.counter_block(&iv, index as u64);
+        let keystream = circuit.encrypt_block(&counter, &round_keys);
+        for (byte, key_byte) in block.iter().zip(keystream) {
+            let encrypted = circuit.xor(*byte, key_byte);
+            ciphertext.push(encrypted);
+        }
+    }
+
+    circuit.constrain_outputs(&ciphertext, &outputs)
+}
+
+/// How the S-box is evaluated.
+enum SBox {
+    /// Read from a memory block holding its table.
+    Lookup(BlockId),
+    /// Evaluated from the forward differences of its table at zero.
+    Polynomial(Vec<FieldElement>),
+}
+
+/// Accumulates the opcodes which constrain an encryption, along with the witness counter.
+struct Aes128Circuit {
+    opcodes: Vec<Opcode>,
+    num_witness: u32,
+    sbox: SBox,
+}
+
+impl Aes128Circuit {
+    fn new(sbox_block: Option<BlockId>, num_witness: u32) -> Self {
+        let mut circuit =
+            Aes128Circuit { opcodes: Vec::new(), num_witness, sbox: SBox::Polynomial(Vec::new()) };
+        circuit.sbox = match sbox_block {
+            Some(block_id) => {
+                let init = SBOX.iter().map(|value| circuit.constant_byte(*value)).collect();
+                circuit.opcodes.push(Opcode::MemoryInit { block_id, init });
+                SBox::Lookup(block_id)
+            }
+            None => SBox::Polynomial(forward_differences()),
+        };
+        circuit
+    }
+
+    fn new_variable(&mut self) -> Witness {
+        VariableStore::new(&mut self.num_witness).new_variable()
+    }
+
+    /// Returns a witness constrained to equal `expr`.
+    fn materialize(&mut self, expr: &Expression) -> Witness {
+        let mut variables = VariableStore::new(&mut self.num_witness);
+        expr_into_witness(expr, &mut variables, &mut self.opcodes)
+    }
+
+    fn constant_byte(&mut self, value: u8) -> Witness {
+        self.materialize(&Expression::from_field(FieldElement::from(value as u128)))
+    }
+
+    fn xor(&mut self, lhs: Witness, rhs: Witness) -> Witness {
+        let output = self.new_variable();
+        self.opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall::XOR {
+            lhs: FunctionInput { witness: lhs, num_bits: 8 },
+            rhs: FunctionInput { witness: rhs, num_bits: 8 },
+            output,
+        }));
+        output
+    }
+
+    fn xor_bytes(&mut self, lhs: &[Witness], rhs: &[Witness]) -> Vec<Witness> {
+        lhs.iter().zip(rhs).map(|(lhs, rhs)| self.xor(*lhs, *rhs)).collect()
+    }
+
+    /// Returns `2 * byte` in GF(2^8), which shifts the byte left and reduces it by `0x1b` if it overflows.
+    fn xtime(&mut self, byte: Witness) -> Witness {
+        let (extra_opcodes, bits, num_witness) =
+            bit_decomposition(byte.into(), 8, self.num_witness);
+        self.opcodes.extend(extra_opcodes);
+        self.num_witness = num_witness;
+
+        // The overflowing bit is XORed into the bits of the reduction, where `bit ^ overflow = bit + overflow - 2 * bit * overflow`.
+        let overflow = bits[7];
+        let mut result = Expression::from(overflow);
+        for (index, bit) in bits[..7].iter().enumerate() {
+            let coefficient = FieldElement::from(2_u128 << index);
+            result.push_addition_term(coefficient, *bit);
+            if 0x1b & (2 << index) != 0 {
+                result.push_addition_term(coefficient, overflow);
+                result.push_multiplication_term(-(coefficient + coefficient), *bit, overflow);
+            }
+        }
+        self.materialize(&result)
+    }
+
+    fn sub_byte(&mut self, byte: Witness) -> Witness {
+        match &self.sbox {
+            SBox::Lookup(block_id) => {
+                let block_id = *block_id;
+                let output = self.new_variable();
+                self.opcodes.push(Opcode::MemoryOp {
+                    block_id,
+                    op: MemOp::read_at_mem_index(byte.into(), output),
+                    predicate: None,
+                });
+                output
+            }
+            SBox::Polynomial(differences) => {
+                // acc_k = d_k + acc_{k + 1} * (x - k) / (k + 1), starting from acc_255 = d_255.
+                let differences = differences.clone();
+                let mut acc = Expression::from_field(differences[255]);
+                for k in (0..255).rev() {
+                    let term = Expression::from(byte) - FieldElement::from(k as u128);
+                    let product = (&acc * &term).expect("both factors should be linear");
+                    let scale = FieldElement::from(k as u128 + 1).inverse();
+                    acc = (&product * scale) + differences[k];
+                    acc = self.materialize(&acc).into();
+                }
+                acc.to_witness().expect("the S-box should be evaluated into a witness")
+            }
+        }
+    }
+
+    fn sub_bytes(&mut self, bytes: &[Witness]) -> Vec<Witness> {
+        bytes.iter().map(|byte| self.sub_byte(*byte)).collect()
+    }
+
+    /// Returns the round keys of the key schedule, the first of which is the key itself.
+    fn expand_key(&mut self, key: &[Witness]) -> Vec<Vec<Witness>> {
+        assert_eq!(key.len(), BLOCK_SIZE, "AES-128 requires a key of {BLOCK_SIZE} bytes");
+        let mut round_keys = vec![key.to_vec()];
+        for rcon in RCON {
+            let previous = round_keys.last().expect("there should be a previous round key").clone();
+
+            // SubWord(RotWord(w)) ^ Rcon applied to the last word of the previous round key.
+            let mut last_word =
+                self.sub_bytes(&[previous[13], previous[14], previous[15], previous[12]]);
+            let rcon = self.constant_byte(rcon);
+            last_word[0] = self.xor(last_word[0], rcon);
+
+            let mut round_key = Vec::with_capacity(BLOCK_SIZE);
+            for word in previous.chunks(4) {
+                last_word = self.xor_bytes(word, &last_word);
+                round_key.extend(&last_word);
+            }
+            round_keys.push(round_key);
+        }
+        round_keys
+    }
+
+    fn encrypt_block(&mut self, block: &[Witness], round_keys: &[Vec<Witness>]) -> Vec<Witness> {
+        let mut state = self.xor_bytes(block, &round_keys[0]);
+        for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+            state = self.sub_bytes(&state);
+            state = shift_rows(&state);
+            if round != NUM_ROUNDS {
+                state = self.mix_columns(&state);
+            }
+            state = self.xor_bytes(&state, round_key);
+        }
+        state
+    }
+
+    fn mix_columns(&mut self, state: &[Witness]) -> Vec<Witness> {
+        let mut mixed = Vec::with_capacity(BLOCK_SIZE);
+        for column in state.chunks(4) {
+            // s'_i = s_i ^ (s_0 ^ s_1 ^ s_2 ^ s_3) ^ 2 * (s_i ^ s_{i + 1})
+            let (sum_01, sum_23) = (self.xor(column[0], column[1]), self.xor(column[2], column[3]));
+            let sum = self.xor(sum_01, sum_23);
+            for i in 0..4 {
+                let pair = self.xor(column[i], column[(i + 1) % 4]);
+                let doubled = self.xtime(pair);
+                let partial = self.xor(column[i], sum);
+                mixed.push(self.xor(partial, doubled));
+            }
+        }
+        mixed
+    }
+
+    /// Returns the big-endian bytes of `iv + index` modulo `2^128`.
+    fn counter_block(&mut self, iv: &[Witness], index: u64) -> Vec<Witness> {
+        if index == 0 {
+            return iv.to_vec();
+        }
+
+        // The counter is incremented in halves of 64 bits, carrying from the lower half into the upper half.
+        let mut low = Expression::from_field(FieldElement::from(index as u128));
+        let mut high = Expression::zero();
+        for (position, byte) in iv.iter().enumerate() {
+            let shift = FieldElement::from(1_u128 << (8 * (7 - position % 8)));
+            if position < 8 {
+                high.push_addition_term(shift, *byte);
+            } else {
+                low.push_addition_term(shift, *byte);
+            }
+        }
+
+        let (extra_opcodes, low_bytes, num_witness) = byte_decomposition(low, 9, self.num_witness);
+        self.opcodes.extend(extra_opcodes);
+        high.push_addition_term(FieldElement::one(), low_bytes[0]);
+        let (extra_opcodes, high_bytes, num_witness) = byte_decomposition(high, 9, num_witness);
+        self.opcodes.extend(extra_opcodes);
+        self.num_witness = num_witness;
+
+        // The carry out of the upper half is dropped.
+        high_bytes[1..].iter().chain(&low_bytes[1..]).copied().collect()
+    }
+
+    fn constrain_outputs(mut self, result: &[Witness], outputs: &[Witness]) -> (u32, Vec<Opcode>) {
+        assert_eq!(result.len(), outputs.len(), "expected {} outputs", result.len());
+        for (output, byte) in outputs.iter().zip(result) {
+            let mut expr = Expression::from(*output);
+            expr.push_addition_term(-FieldElement::one(), *byte);
+            self.opcodes.push(Opcode::Arithmetic(expr));
+        }
+        (self.num_witness, self.opcodes)
+    }
+}
+
+/// Moves the byte in row `r` of column `c` to column `c - r`, where the state is laid out in columns.
+fn shift_rows(state: &[Witness]) -> Vec<Witness> {
+    (0..BLOCK_SIZE).map(|index| state[(index + 4 * (index % 4)) % BLOCK_SIZE]).collect()
+}
+
+/// Returns the forward differences `d_k` of the S-box table at zero, which are the coefficients of the polynomial
+/// interpolating the table in the Newton forward difference form.
+fn forward_differences() -> Vec<FieldElement> {
+    let mut differences: Vec<FieldElement> =
+        SBOX.iter().map(|value| FieldElement::from(*value as u128)).collect();
+    for k in 1..differences.len() {
+        for j in (k..differences.len()).rev() {
+            differences[j] = differences[j] - differences[j - 1];
+        }
+    }
+    differences
+}
@@ -0,0 +1,342 @@
+//! BigIntAdd, BigIntMul and BigIntMod fallback functions.
+//!
+//! Brillig computes the quotient and remainder of the unreduced result by the modulus, which are then constrained to
+//! satisfy `unreduced = quotient * modulus + remainder` and `remainder < modulus`.
+//!
+//! The identity is checked one byte at a time, starting from the least significant, by carrying the difference
+//! between each column of both sides into the next column. As every byte is range checked, the columns and carries
+//! are small enough that the identity holds over the integers and not only modulo the field.
+use super::utils::{expr_into_witness, is_zero, mul_into_witness};
+use crate::helpers::VariableStore;
+use acir::{
+    brillig::{self, BlackBoxOp, HeapArray, HeapVector, RegisterIndex, Value},
+    circuit::{
+        brillig::{Brillig, BrilligInputs, BrilligOutputs},
+        directives::Directive,
+        opcodes::{BlackBoxFuncCall, FunctionInput},
+        Opcode,
+    },
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+
+/// The largest value of a byte.
+const BYTE_MAX: u128 = 255;
+
+/// The operation which computes the unreduced result from the operands.
+#[derive(Clone, Copy)]
+enum Operation {
+    Add,
+    Mul,
+    Mod,
+}
+
+/// Constrains `outputs` to be the big-endian bytes of `(lhs + rhs) % modulus`.
+///
+/// Returns `None` if there are not as many `outputs` as there are bytes of the modulus.
+pub fn bigint_add(
+    lhs: Vec<Expression>,
+    rhs: Vec<Expression>,
+    modulus: Vec<Expression>,
+    outputs: Vec<Witness>,
+    num_witness: u32,
+) -> Option<(u32, Vec<Opcode>)> {
+    let (lhs_le, rhs_le) = (little_endian(&lhs), little_endian(&rhs));
+    let columns = (0..lhs.len().max(rhs.len()))
+        .map(|k| {
+            let zero = Expression::zero();
+            lhs_le.get(k).unwrap_or(&zero) + rhs_le.get(k).unwrap_or(&zero)
+        })
+        .collect();
+
+    bigint_reduce(Operation::Add, &[lhs, rhs], columns, 2 * BYTE_MAX, modulus, outputs, num_witness)
+}
+
+/// Constrains `outputs` to be the big-endian bytes of `(lhs * rhs) % modulus`.
+///
+/// Returns `None` if there are not as many `outputs` as there are bytes of the modulus.
+pub fn bigint_mul(
+    lhs: Vec<Expression>,
+    rhs: Vec<Expression>,
+    modulus: Vec<Expression>,
+    outputs: Vec<Witness>,
+    num_witness: u32,
+) -> Option<(u32, Vec<Opcode>)> {
+    let columns = mul_columns(&little_endian(&lhs), &little_endian(&rhs));
+    let max_column = lhs.len().min(rhs.len()) as u128 * BYTE_MAX * BYTE_MAX;
+
+    bigint_reduce(Operation::Mul, &[lhs, rhs], columns, max_column, modulus, outputs, num_witness)
+}
+
+/// Constrains `outputs` to be the big-endian bytes of `input % modulus`.
+///
+/// Returns `None` if there are not as many `outputs` as there are bytes of the modulus.
+pub fn bigint_mod(
+    input: Vec<Expression>,
+    modulus: Vec<Expression>,
+    outputs: Vec<Witness>,
+    num_witness: u32,
+) -> Option<(u32, Vec<Opcode>)> {
+    let columns = little_endian(&input);
+
+    bigint_reduce(Operation::Mod, &[input], columns, BYTE_MAX, modulus, outputs, num_witness)
+}
+
+/// Constrains `outputs` to be the remainder of the unreduced result of `operation` by `modulus`, where `columns` are
+/// the little-endian columns of the unreduced result, none of which exceed `max_column`.
+fn bigint_reduce(
+    operation: Operation,
+    operands: &[Vec<Expression>],
+    columns: Vec<Expression>,
+    max_column: u128,
+    modulus: Vec<Expression>,
+    outputs: Vec<Witness>,
+    mut num_witness: u32,
+) -> Option<(u32, Vec<Opcode>)> {
+    if outputs.len() != modulus.len() {
+        return None;
+    }
+    let mut new_opcodes = Vec::new();
+    let mut variables = VariableStore::new(&mut num_witness);
+
+    for input in operands.iter().chain(std::iter::once(&modulus)) {
+        constrain_bytes(input, &mut variables, &mut new_opcodes);
+    }
+
+    let quotient = unconstrained_div_mod(
+        operation,
+        operands,
+        &modulus,
+        &outputs,
+        &mut variables,
+        &mut new_opcodes,
+    );
+    let quotient: Vec<Expression> = quotient.into_iter().map(Expression::from).collect();
+    let remainder: Vec<Expression> = outputs.into_iter().map(Expression::from).collect();
+    constrain_bytes(&quotient, &mut variables, &mut new_opcodes);
+    constrain_bytes(&remainder, &mut variables, &mut new_opcodes);
+
+    // unreduced - quotient * modulus - remainder = 0
+    let product_columns = mul_columns(&little_endian(&quotient), &little_endian(&modulus));
+    let max_product_column = quotient.len().min(modulus.len()) as u128 * BYTE_MAX * BYTE_MAX;
+    let num_columns = columns.len().max(product_columns.len()).max(remainder.len());
+    let remainder_le = little_endian(&remainder);
+    let zero = Expression::zero();
+    let differences: Vec<Expression> = (0..num_columns)
+        .map(|k| {
+            let column = columns.get(k).unwrap_or(&zero);
+            let product_column = product_columns.get(k).unwrap_or(&zero);
+            &(column - product_column) - remainder_le.get(k).unwrap_or(&zero)
+        })
+        .collect();
+    let max_difference = max_column.max(max_product_column + BYTE_MAX);
+    constrain_zero(&differences, max_difference, &mut variables, &mut new_opcodes);
+
+    constrain_less_than(&remainder, &modulus, &mut variables, &mut new_opcodes);
+
+    Some((variables.finalize(), new_opcodes))
+}
+
+/// Returns witnesses which Brillig assigns the big-endian bytes of the quotient of the unreduced result of
+/// `operation` by `modulus`, assigning the bytes of the remainder to `remainder`.
+///
+/// The witnesses are not constrained, so the caller must constrain them to the expected values.
+fn unconstrained_div_mod(
+    operation: Operation,
+    operands: &[Vec<Expression>],
+    modulus: &[Expression],
+    remainder: &[Witness],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Vec<Witness> {
+    // The inputs are laid out one after another in memory, with a register pointing to each of them.
+    let mut inputs: Vec<Vec<Expression>> = operands.to_vec();
+    inputs.push(modulus.to_vec());
+    let unreduced_len = match operation {
+        Operation::Add => operands[0].len().max(operands[1].len()) + 1,
+        Operation::Mul => operands[0].len() + operands[1].len(),
+        Operation::Mod => operands[0].len(),
+    };
+    if !matches!(operation, Operation::Mod) {
+        // The unreduced result is computed modulo `256^unreduced_len`, which it is less than.
+        let mut power = vec![Expression::zero(); unreduced_len + 1];
+        power[0] = Expression::one();
+        inputs.push(power);
+    }
+    let mut free_memory: usize = inputs.iter().map(Vec::len).sum();
+
+    // The registers following the pointers to the inputs hold constants, starting with the size of each input.
+    let mut bytecode = Vec::new();
+    let mut num_registers = inputs.len();
+    let mut constant = |value: usize, bytecode: &mut Vec<brillig::Opcode>| {
+        let destination = RegisterIndex::from(num_registers);
+        num_registers += 1;
+        bytecode.push(brillig::Opcode::Const { destination, value: Value::from(value) });
+        destination
+    };
+    let vectors: Vec<HeapVector> = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| HeapVector {
+            pointer: RegisterIndex::from(index),
+            size: constant(input.len(), &mut bytecode),
+        })
+        .collect();
+
+    let modulus_vector = vectors[operands.len()];
+    let (unreduced, quotient_len) = match operation {
+        Operation::Add | Operation::Mul => {
+            let power = vectors[operands.len() + 1];
+            let output = HeapArray {
+                pointer: constant(free_memory, &mut bytecode),
+                size: unreduced_len + 1,
+            };
+            free_memory += output.size;
+            let (lhs, rhs) = (vectors[0], vectors[1]);
+            bytecode.push(brillig::Opcode::BlackBox(match operation {
+                Operation::Add => BlackBoxOp::BigIntAdd { lhs, rhs, modulus: power, output },
+                _ => BlackBoxOp::BigIntMul { lhs, rhs, modulus: power, output },
+            }));
+            (HeapVector { pointer: output.pointer, size: power.size }, output.size)
+        }
+        Operation::Mod => (vectors[0], unreduced_len),
+    };
+    let quotient_array =
+        HeapArray { pointer: constant(free_memory, &mut bytecode), size: quotient_len };
+    free_memory += quotient_len;
+    let remainder_array =
+        HeapArray { pointer: constant(free_memory, &mut bytecode), size: remainder.len() };
+    bytecode.push(brillig::Opcode::BlackBox(BlackBoxOp::BigIntDivMod {
+        lhs: unreduced,
+        rhs: modulus_vector,
+        quotient: quotient_array,
+        remainder: remainder_array,
+    }));
+    bytecode.push(brillig::Opcode::Mov {
+        destination: RegisterIndex::from(0),
+        source: quotient_array.pointer,
+    });
+    bytecode.push(brillig::Opcode::Mov {
+        destination: RegisterIndex::from(1),
+        source: remainder_array.pointer,
+    });
+
+    let quotient: Vec<Witness> = (0..quotient_len).map(|_| variables.new_variable()).collect();
+    new_opcodes.push(Opcode::Brillig(Brillig {
+        inputs: inputs.into_iter().map(BrilligInputs::Array).collect(),
+        outputs: vec![
+            BrilligOutputs::Array(quotient.clone()),
+            BrilligOutputs::Array(remainder.to_vec()),
+        ],
+        foreign_call_results: vec![],
+        bytecode,
+        predicate: None,
+    }));
+    quotient
+}
+
+/// Constrains the integer whose little-endian columns are `columns` to be zero, where no column exceeds
+/// `max_column` in absolute value.
+fn constrain_zero(
+    columns: &[Expression],
+    max_column: u128,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) {
+    // Each carry is at most `max_column / 255` in absolute value, so it is offset to be non-negative when range checked.
+    let max_carry = max_column / BYTE_MAX + 1;
+    let offset_bits = u128::BITS - max_carry.leading_zeros();
+    let offset = FieldElement::from(1_u128 << offset_bits);
+    let byte_shift = FieldElement::from(BYTE_MAX + 1);
+
+    let mut carry = Expression::zero();
+    for (k, column) in columns.iter().enumerate() {
+        let total = column + &carry;
+        if k + 1 == columns.len() {
+            // The final column must not carry into the next one.
+            new_opcodes.push(Opcode::Arithmetic(total));
+        } else {
+            // 256 * carry = column + previous carry
+            let shifted_carry = variables.new_variable();
+            carry = Expression::from(shifted_carry) - offset;
+            new_opcodes.push(Opcode::Arithmetic(&(&carry * byte_shift) - &total));
+            new_opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput { witness: shifted_carry, num_bits: offset_bits + 1 },
+            }));
+        }
+    }
+}
+
+/// Constrains the integer whose big-endian bytes are `lhs` to be less than that whose big-endian bytes are `rhs`,
+/// where both have the same number of bytes.
+fn constrain_less_than(
+    lhs: &[Expression],
+    rhs: &[Expression],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) {
+    let mut less = Expression::zero();
+    let mut equal = Expression::one();
+    for (lhs_byte, rhs_byte) in lhs.iter().zip(rhs) {
+        // rhs - lhs + 255 = low + 256 * greater, where greater is one exactly when the byte of rhs is greater
+        let difference = rhs_byte - lhs_byte;
+        let shifted_difference = difference.clone() + FieldElement::from(BYTE_MAX);
+        let low = variables.new_variable();
+        let greater = variables.new_variable();
+        new_opcodes.push(Opcode::Directive(Directive::ToLeRadix {
+            a: shifted_difference.clone(),
+            b: vec![low, greater],
+            radix: 256,
+        }));
+        new_opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness: low, num_bits: 8 },
+        }));
+        let greater = Expression::from(greater);
+        let greater_squared = mul_linear(&greater, &greater);
+        new_opcodes.push(Opcode::Arithmetic(&greater_squared - &greater));
+        let decomposition = &Expression::from(low) + &(&greater * FieldElement::from(BYTE_MAX + 1));
+        new_opcodes.push(Opcode::Arithmetic(&shifted_difference - &decomposition));
+
+        let is_equal = is_zero(&difference, variables, new_opcodes);
+        less = expr_into_witness(&(&less + &mul_linear(&equal, &greater)), variables, new_opcodes)
+            .into();
+        equal = mul_into_witness(&equal, &is_equal.into(), variables, new_opcodes).into();
+    }
+    new_opcodes.push(Opcode::Arithmetic(less - FieldElement::one()));
+}
+
+/// Constrains each of `bytes` to fit within a byte.
+fn constrain_bytes(
+    bytes: &[Expression],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) {
+    for byte in bytes {
+        let witness =
+            byte.to_witness().unwrap_or_else(|| expr_into_witness(byte, variables, new_opcodes));
+        new_opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+            input: FunctionInput { witness, num_bits: 8 },
+        }));
+    }
+}
+
+/// Returns the little-endian columns of the product of two integers given by their little-endian bytes.
+fn mul_columns(lhs: &[Expression], rhs: &[Expression]) -> Vec<Expression> {
+    let mut columns = vec![Expression::zero(); (lhs.len() + rhs.len()).saturating_sub(1)];
+    for (i, lhs_byte) in lhs.iter().enumerate() {
+        for (j, rhs_byte) in rhs.iter().enumerate() {
+            columns[i + j] = &columns[i + j] + &mul_linear(lhs_byte, rhs_byte);
+        }
+    }
+    columns
+}
+
+/// Returns big-endian bytes in little-endian order.
+fn little_endian(bytes: &[Expression]) -> Vec<Expression> {
+    bytes.iter().rev().cloned().collect()
+}
+
+/// Returns `lhs * rhs`, where both expressions must be linear.
+fn mul_linear(lhs: &Expression, rhs: &Expression) -> Expression {
+    (lhs * rhs).expect("both expressions should be linear")
+}
@@ -301,8 +301,9 @@ fn blake2s_round(
     (new_opcodes, num_witness)
 }
 
+/// The mixing function `G` of Blake2s, which is shared by Blake3.
 #[allow(clippy::too_many_arguments)]
-fn g(
+pub(super) fn g(
     state: &mut [UInt32],
     a: usize,
     b: usize,
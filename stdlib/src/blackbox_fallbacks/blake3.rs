@@ -0,0 +1,272 @@
+//! Blake3 fallback function.
+//!
+//! The input is split into chunks of 1024 bytes, each of which is hashed into a chaining value by compressing its
+//! blocks of 64 bytes in turn. The chaining values then form the leaves of a binary tree whose root is the digest.
+use super::{
+    blake2s::g,
+    utils::{byte_decomposition, round_to_nearest_byte},
+    UInt32,
+};
+use acir::{
+    circuit::Opcode,
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+// Domain separation flags of the compression function.
+const CHUNK_START: u32 = 1;
+const CHUNK_END: u32 = 2;
+const PARENT: u32 = 4;
+const ROOT: u32 = 8;
+
+pub fn blake3(
+    inputs: Vec<(Expression, u32)>,
+    outputs: Vec<Witness>,
+    mut num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    let mut new_opcodes = Vec::new();
+    let mut new_inputs = Vec::new();
+
+    // Decompose the input field elements into bytes and collect the resulting witnesses.
+    for (witness, num_bits) in inputs {
+        let num_bytes = round_to_nearest_byte(num_bits);
+        let (extra_opcodes, extra_inputs, updated_witness_counter) =
+            byte_decomposition(witness, num_bytes, num_witness);
+        new_opcodes.extend(extra_opcodes);
+        new_inputs.extend(extra_inputs);
+        num_witness = updated_witness_counter;
+    }
+
+    let (result, num_witness, extra_opcodes) = create_blake3_constraint(new_inputs, num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    // constrain the outputs to be the same as the result of the circuit
+    for i in 0..outputs.len() {
+        let mut expr = Expression::from(outputs[i]);
+        expr.push_addition_term(-FieldElement::one(), result[i]);
+        new_opcodes.push(Opcode::Arithmetic(expr));
+    }
+    (num_witness, new_opcodes)
+}
+
+fn create_blake3_constraint(
+    input: Vec<Witness>,
+    num_witness: u32,
+) -> (Vec<Witness>, u32, Vec<Opcode>) {
+    let mut new_opcodes = Vec::new();
+    let (iv, extra_opcodes, mut num_witness) = load_constants(&IV, num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    // An empty input is hashed as a single empty chunk.
+    let chunks: Vec<&[Witness]> =
+        if input.is_empty() { vec![&[]] } else { input.chunks(CHUNK_LEN).collect() };
+    let is_root = chunks.len() == 1;
+    let mut chaining_values = Vec::with_capacity(chunks.len());
+    for (counter, chunk) in chunks.into_iter().enumerate() {
+        let (chaining_value, extra_opcodes, updated_witness_counter) =
+            hash_chunk(chunk, counter as u32, &iv, is_root, num_witness);
+        new_opcodes.extend(extra_opcodes);
+        chaining_values.push(chaining_value);
+        num_witness = updated_witness_counter;
+    }
+    let (root, extra_opcodes, mut num_witness) =
+        hash_subtree(&chaining_values, &iv, true, num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    // decompose the result bytes in u32 to u8
+    let mut result = Vec::new();
+    for word in root {
+        let (extra_opcodes, mut bytes, updated_witness_counter) =
+            byte_decomposition(Expression::from(word.inner), 4, num_witness);
+        new_opcodes.extend(extra_opcodes);
+        bytes.reverse();
+        result.extend(bytes);
+        num_witness = updated_witness_counter;
+    }
+
+    (result, num_witness, new_opcodes)
+}
+
+/// Returns the chaining value of a chunk, which is the root of the tree if it is the only chunk.
+fn hash_chunk(
+    chunk: &[Witness],
+    counter: u32,
+    iv: &[UInt32],
+    is_root: bool,
+    mut num_witness: u32,
+) -> (Vec<UInt32>, Vec<Opcode>, u32) {
+    let mut new_opcodes = Vec::new();
+
+    // An empty chunk is compressed as a single empty block.
+    let blocks: Vec<&[Witness]> =
+        if chunk.is_empty() { vec![&[]] } else { chunk.chunks(BLOCK_LEN).collect() };
+    let num_blocks = blocks.len();
+    let mut chaining_value = iv.to_vec();
+    for (index, block) in blocks.into_iter().enumerate() {
+        let mut flags = 0;
+        if index == 0 {
+            flags |= CHUNK_START;
+        }
+        if index == num_blocks - 1 {
+            flags |= CHUNK_END;
+            if is_root {
+                flags |= ROOT;
+            }
+        }
+
+        // pad the block with zeros
+        let mut block_bytes = block.to_vec();
+        for _ in block.len()..BLOCK_LEN {
+            let (pad, extra_opcodes, updated_witness_counter) =
+                UInt32::load_constant(0_u32, num_witness);
+            new_opcodes.extend(extra_opcodes);
+            block_bytes.push(pad.inner);
+            num_witness = updated_witness_counter;
+        }
+
+        let mut block_words = Vec::with_capacity(16);
+        for word_bytes in block_bytes.chunks(4) {
+            let mut word_bytes = word_bytes.to_vec();
+            word_bytes.reverse();
+            let (word, extra_opcodes, updated_witness_counter) =
+                UInt32::from_witnesses(&word_bytes, num_witness);
+            new_opcodes.extend(extra_opcodes);
+            block_words.push(word[0]);
+            num_witness = updated_witness_counter;
+        }
+
+        let (new_chaining_value, extra_opcodes, updated_witness_counter) = compress(
+            &chaining_value,
+            block_words,
+            iv,
+            [counter, block.len() as u32, flags],
+            num_witness,
+        );
+        new_opcodes.extend(extra_opcodes);
+        chaining_value = new_chaining_value;
+        num_witness = updated_witness_counter;
+    }
+
+    (chaining_value, new_opcodes, num_witness)
+}
+
+/// Returns the chaining value of the subtree whose leaves are the chaining values of consecutive chunks.
+///
+/// The left subtree holds the largest power of two number of chunks which leaves at least one for the right subtree.
+fn hash_subtree(
+    chaining_values: &[Vec<UInt32>],
+    iv: &[UInt32],
+    is_root: bool,
+    num_witness: u32,
+) -> (Vec<UInt32>, Vec<Opcode>, u32) {
+    if chaining_values.len() == 1 {
+        return (chaining_values[0].clone(), Vec::new(), num_witness);
+    }
+    let mut new_opcodes = Vec::new();
+
+    let left_len = 1 << (usize::BITS - (chaining_values.len() - 1).leading_zeros() - 1);
+    let (left, extra_opcodes, num_witness) =
+        hash_subtree(&chaining_values[..left_len], iv, false, num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (right, extra_opcodes, num_witness) =
+        hash_subtree(&chaining_values[left_len..], iv, false, num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    let flags = if is_root { PARENT | ROOT } else { PARENT };
+    let block_words = left.into_iter().chain(right).collect();
+    let (parent, extra_opcodes, num_witness) =
+        compress(iv, block_words, iv, [0, BLOCK_LEN as u32, flags], num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    (parent, new_opcodes, num_witness)
+}
+
+/// Compresses a block of 16 words into the chaining value, where `parameters` are the counter, the number of bytes
+/// in the block and the flags, returning the first 8 words of the output.
+fn compress(
+    chaining_value: &[UInt32],
+    mut block_words: Vec<UInt32>,
+    iv: &[UInt32],
+    parameters: [u32; 3],
+    num_witness: u32,
+) -> (Vec<UInt32>, Vec<Opcode>, u32) {
+    let mut new_opcodes = Vec::new();
+
+    // The counter is at most 32 bits, so its upper word is always zero.
+    let [counter, block_len, flags] = parameters;
+    let (constants, extra_opcodes, mut num_witness) =
+        load_constants(&[counter, 0, block_len, flags], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let mut state: Vec<UInt32> =
+        chaining_value.iter().chain(&iv[..4]).chain(&constants).copied().collect();
+
+    for round in 0..7 {
+        let (extra_opcodes, updated_witness_counter) =
+            blake3_round(&mut state, &block_words, num_witness);
+        new_opcodes.extend(extra_opcodes);
+        num_witness = updated_witness_counter;
+        if round < 6 {
+            block_words = MSG_PERMUTATION.iter().map(|index| block_words[*index]).collect();
+        }
+    }
+
+    let mut output = Vec::with_capacity(8);
+    for i in 0..8 {
+        let (word, extra_opcodes, updated_witness_counter) =
+            state[i].xor(&state[i + 8], num_witness);
+        new_opcodes.extend(extra_opcodes);
+        output.push(word);
+        num_witness = updated_witness_counter;
+    }
+
+    (output, new_opcodes, num_witness)
+}
+
+fn blake3_round(state: &mut [UInt32], msg: &[UInt32], num_witness: u32) -> (Vec<Opcode>, u32) {
+    let mut new_opcodes = Vec::new();
+
+    // Mix the columns.
+    let (extra_opcodes, num_witness) = g(state, 0, 4, 8, 12, msg[0], msg[1], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (extra_opcodes, num_witness) = g(state, 1, 5, 9, 13, msg[2], msg[3], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (extra_opcodes, num_witness) = g(state, 2, 6, 10, 14, msg[4], msg[5], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (extra_opcodes, num_witness) = g(state, 3, 7, 11, 15, msg[6], msg[7], num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    // Mix the diagonals.
+    let (extra_opcodes, num_witness) = g(state, 0, 5, 10, 15, msg[8], msg[9], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (extra_opcodes, num_witness) = g(state, 1, 6, 11, 12, msg[10], msg[11], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (extra_opcodes, num_witness) = g(state, 2, 7, 8, 13, msg[12], msg[13], num_witness);
+    new_opcodes.extend(extra_opcodes);
+    let (extra_opcodes, num_witness) = g(state, 3, 4, 9, 14, msg[14], msg[15], num_witness);
+    new_opcodes.extend(extra_opcodes);
+
+    (new_opcodes, num_witness)
+}
+
+/// Load constants into the circuit
+fn load_constants(constants: &[u32], mut num_witness: u32) -> (Vec<UInt32>, Vec<Opcode>, u32) {
+    let mut new_opcodes = Vec::new();
+    let mut new_witnesses = Vec::new();
+
+    for constant in constants {
+        let (new_witness, extra_opcodes, updated_witness_counter) =
+            UInt32::load_constant(*constant, num_witness);
+        new_opcodes.extend(extra_opcodes);
+        new_witnesses.push(new_witness);
+        num_witness = updated_witness_counter;
+    }
+
+    (new_witnesses, new_opcodes, num_witness)
+}
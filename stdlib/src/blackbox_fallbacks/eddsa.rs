@@ -0,0 +1,251 @@
+//! EddsaVerify fallback function.
+use super::utils::{
+    bit_decomposition, expr_into_witness, is_zero, less_than_constant, materialize,
+    mul_into_witness, sbox,
+};
+use crate::helpers::VariableStore;
+use acir::{
+    circuit::Opcode,
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+use blackbox_solver::{
+    baby_jubjub_subgroup_order, BabyJubJubPoint, PoseidonConfig, BABY_JUBJUB_A, BABY_JUBJUB_D,
+};
+
+/// A point on Baby JubJub whose coordinates are linear expressions.
+#[derive(Clone)]
+struct Point {
+    x: Expression,
+    y: Expression,
+}
+
+impl Point {
+    fn identity() -> Point {
+        Point { x: Expression::zero(), y: Expression::one() }
+    }
+}
+
+/// Constrains `output` to be whether `(signature_r8, signature_s)` is a valid EdDSA signature over Baby JubJub
+/// of `message` by the public key, following circomlib's `verifyPoseidon`.
+#[allow(clippy::too_many_arguments)]
+pub fn eddsa_verify(
+    public_key_x: Expression,
+    public_key_y: Expression,
+    signature_s: Expression,
+    signature_r8_x: Expression,
+    signature_r8_y: Expression,
+    message: Expression,
+    output: Witness,
+    num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    let num_bits = FieldElement::max_num_bits();
+    let (mut new_opcodes, signature_s_bits, mut num_witness) =
+        bit_decomposition(signature_s, num_bits, num_witness);
+
+    // Hash the signature, public key and message as given before any of them are checked.
+    let mut variables = VariableStore::new(&mut num_witness);
+    let hash = poseidon_hash(
+        [
+            signature_r8_x.clone(),
+            signature_r8_y.clone(),
+            public_key_x.clone(),
+            public_key_y.clone(),
+            message,
+        ],
+        &mut variables,
+        &mut new_opcodes,
+    );
+    let hash = expr_into_witness(&hash, &mut variables, &mut new_opcodes);
+    let num_witness = variables.finalize();
+    let (hash_opcodes, hash_bits, mut num_witness) =
+        bit_decomposition(hash.into(), num_bits, num_witness);
+    new_opcodes.extend(hash_opcodes);
+
+    let mut variables = VariableStore::new(&mut num_witness);
+
+    // Points which are not on the curve are replaced with the identity so that the addition law remains complete,
+    // the signature is then rejected as invalid.
+    let (public_key, public_key_on_curve) = on_curve_or_identity(
+        Point { x: public_key_x, y: public_key_y },
+        &mut variables,
+        &mut new_opcodes,
+    );
+    let (signature_r8, signature_r8_on_curve) = on_curve_or_identity(
+        Point { x: signature_r8_x, y: signature_r8_y },
+        &mut variables,
+        &mut new_opcodes,
+    );
+
+    let signature_s_is_canonical = less_than_constant(
+        &signature_s_bits,
+        baby_jubjub_subgroup_order(),
+        &mut variables,
+        &mut new_opcodes,
+    );
+
+    // Public keys of small order, for which `8 * A` is the identity, are rejected.
+    let mut public_key8 = public_key;
+    for _ in 0..3 {
+        public_key8 = add(&public_key8, &public_key8, &mut variables, &mut new_opcodes);
+    }
+    let public_key8_is_identity = is_zero(&public_key8.x, &mut variables, &mut new_opcodes);
+
+    // Check that `S * B8 = R8 + H(R8, A, M) * 8 * A`.
+    let lhs = fixed_base_scalar_mul(&signature_s_bits, &mut variables, &mut new_opcodes);
+    let hash_times_key = scalar_mul(&public_key8, &hash_bits, &mut variables, &mut new_opcodes);
+    let rhs = add(&signature_r8, &hash_times_key, &mut variables, &mut new_opcodes);
+    let x_is_equal = is_zero(&(&lhs.x - &rhs.x), &mut variables, &mut new_opcodes);
+    let y_is_equal = is_zero(&(&lhs.y - &rhs.y), &mut variables, &mut new_opcodes);
+
+    let conditions = [
+        signature_r8_on_curve,
+        signature_s_is_canonical,
+        &Expression::one() - &public_key8_is_identity.into(),
+        x_is_equal.into(),
+        y_is_equal.into(),
+    ];
+    let valid = conditions.iter().fold(public_key_on_curve, |valid, condition| {
+        mul_into_witness(&valid, condition, &mut variables, &mut new_opcodes).into()
+    });
+
+    // constrain the output to be the same as the result of the circuit
+    new_opcodes.push(Opcode::Arithmetic(&valid - output));
+
+    (variables.finalize(), new_opcodes)
+}
+
+/// Returns the Poseidon hash of `inputs`, as computed by [`PoseidonConfig::hash`].
+fn poseidon_hash(
+    inputs: [Expression; 5],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Expression {
+    let config = PoseidonConfig::bn254();
+    let mut state: Vec<Expression> = std::iter::once(Expression::zero()).chain(inputs).collect();
+    for (index, constants) in config.round_constants.iter().enumerate() {
+        state =
+            state.iter().zip(constants).map(|(expr, constant)| expr.clone() + *constant).collect();
+        if config.is_full_round(index) {
+            state = state.into_iter().map(|expr| sbox(expr, variables, new_opcodes)).collect();
+        } else {
+            state[0] = sbox(state[0].clone(), variables, new_opcodes);
+        }
+
+        let mixed: Vec<Expression> = config
+            .mds_matrix
+            .iter()
+            .map(|row| {
+                row.iter().zip(&state).fold(Expression::default(), |sum, (coefficient, expr)| {
+                    &sum + &(expr * *coefficient)
+                })
+            })
+            .collect();
+        state = materialize(&mixed, variables, new_opcodes);
+    }
+    state.swap_remove(0)
+}
+
+/// Returns `point` along with whether it is on the curve, replacing it with the identity if it is not.
+fn on_curve_or_identity(
+    point: Point,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> (Point, Expression) {
+    let x2: Expression = mul_into_witness(&point.x, &point.x, variables, new_opcodes).into();
+    let y2: Expression = mul_into_witness(&point.y, &point.y, variables, new_opcodes).into();
+    let x2y2: Expression = mul_into_witness(&x2, &y2, variables, new_opcodes).into();
+    // a * x^2 + y^2 - 1 - d * x^2 * y^2
+    let equation = &(&(&x2 * FieldElement::from(BABY_JUBJUB_A)) + &y2)
+        - &(&(&x2y2 * FieldElement::from(BABY_JUBJUB_D)) + &Expression::one());
+    let on_curve: Expression = is_zero(&equation, variables, new_opcodes).into();
+    (select(&on_curve, &point, variables, new_opcodes), on_curve)
+}
+
+/// Returns `point` if `condition` is one and the identity if it is zero.
+fn select(
+    condition: &Expression,
+    point: &Point,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let x = mul_into_witness(condition, &point.x, variables, new_opcodes).into();
+    let y_minus_one = point.y.clone() - FieldElement::one();
+    let y = mul_into_witness(condition, &y_minus_one, variables, new_opcodes);
+    Point { x, y: Expression::from(y) + FieldElement::one() }
+}
+
+/// Adds two points on the curve using the twisted Edwards addition law.
+fn add(
+    lhs: &Point,
+    rhs: &Point,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let x1x2 = mul_into_witness(&lhs.x, &rhs.x, variables, new_opcodes);
+    let y1y2 = mul_into_witness(&lhs.y, &rhs.y, variables, new_opcodes);
+    let x1y2 = mul_into_witness(&lhs.x, &rhs.y, variables, new_opcodes);
+    let y1x2 = mul_into_witness(&lhs.y, &rhs.x, variables, new_opcodes);
+    let x1x2y1y2 = mul_into_witness(&x1x2.into(), &y1y2.into(), variables, new_opcodes);
+    let d_x1x2y1y2 = &Expression::from(x1x2y1y2) * FieldElement::from(BABY_JUBJUB_D);
+
+    // The denominators are constrained to witnesses so that each coordinate only appears within a multiplication term,
+    // which the solver requires in order to solve for it.
+    let x3_denominator =
+        expr_into_witness(&(d_x1x2y1y2.clone() + FieldElement::one()), variables, new_opcodes);
+    let y3_denominator =
+        expr_into_witness(&(&Expression::one() - &d_x1x2y1y2), variables, new_opcodes);
+
+    // x3 * (1 + d * x1 * x2 * y1 * y2) = x1 * y2 + y1 * x2
+    let x3 = variables.new_variable();
+    let mut x3_constraint = &(-&Expression::from(x1y2)) - y1x2;
+    x3_constraint.push_multiplication_term(FieldElement::one(), x3, x3_denominator);
+    new_opcodes.push(Opcode::Arithmetic(x3_constraint));
+
+    // y3 * (1 - d * x1 * x2 * y1 * y2) = y1 * y2 - a * x1 * x2
+    let y3 = variables.new_variable();
+    let mut y3_constraint = &(&Expression::from(x1x2) * FieldElement::from(BABY_JUBJUB_A)) - y1y2;
+    y3_constraint.push_multiplication_term(FieldElement::one(), y3, y3_denominator);
+    new_opcodes.push(Opcode::Arithmetic(y3_constraint));
+
+    Point { x: x3.into(), y: y3.into() }
+}
+
+/// Multiplies `point` by the scalar whose little-endian bits are `bits`.
+fn scalar_mul(
+    point: &Point,
+    bits: &[Witness],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let mut result = Point::identity();
+    for bit in bits.iter().rev() {
+        result = add(&result, &result, variables, new_opcodes);
+        let addend = select(&Expression::from(*bit), point, variables, new_opcodes);
+        result = add(&result, &addend, variables, new_opcodes);
+    }
+    result
+}
+
+/// Multiplies [`BabyJubJubPoint::base8`] by the scalar whose little-endian bits are `bits`.
+///
+/// As the base point is fixed, each bit selects between the identity and a multiple of the base point
+/// which is computed ahead of time, so no doublings need to be constrained.
+fn fixed_base_scalar_mul(
+    bits: &[Witness],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let mut result = Point::identity();
+    let mut base = BabyJubJubPoint::base8();
+    for bit in bits {
+        let bit = Expression::from(*bit);
+        let addend = Point {
+            x: &bit * base.x,
+            y: (&bit * (base.y - FieldElement::one())) + FieldElement::one(),
+        };
+        result = add(&result, &addend, variables, new_opcodes);
+        base = base.add(&base);
+    }
+    result
+}
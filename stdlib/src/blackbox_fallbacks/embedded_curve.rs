@@ -0,0 +1,264 @@
+//! EmbeddedCurveAdd and VariableBaseScalarMul fallback functions over Grumpkin.
+use super::utils::{
+    bit_decomposition, expr_into_witness, is_zero, less_than_or_equal_constant, mul_into_witness,
+    unconstrained_div,
+};
+use crate::helpers::VariableStore;
+use acir::{
+    circuit::Opcode,
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+
+/// The coefficient `b` of the short Weierstrass equation `y^2 = x^3 + b` of Grumpkin.
+const GRUMPKIN_B: i128 = -17;
+
+/// The number of bits of the scalar which are used to select a multiple of the point in each step of a scalar multiplication.
+const WINDOW_SIZE: usize = 4;
+
+/// A point on Grumpkin whose coordinates are linear expressions, along with whether it is the point at infinity.
+///
+/// The point at infinity has the coordinates `(0, 0)`, matching the representation used by the black box functions.
+#[derive(Clone)]
+struct Point {
+    x: Expression,
+    y: Expression,
+    is_infinity: Expression,
+}
+
+impl Point {
+    fn infinity() -> Point {
+        Point { x: Expression::zero(), y: Expression::zero(), is_infinity: Expression::one() }
+    }
+}
+
+/// Constrains `outputs` to be the sum of two points on Grumpkin.
+pub fn embedded_curve_add(
+    input1_x: Expression,
+    input1_y: Expression,
+    input2_x: Expression,
+    input2_y: Expression,
+    outputs: (Witness, Witness),
+    mut num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    let mut new_opcodes = Vec::new();
+    let mut variables = VariableStore::new(&mut num_witness);
+
+    let lhs = point_on_curve(input1_x, input1_y, &mut variables, &mut new_opcodes);
+    let rhs = point_on_curve(input2_x, input2_y, &mut variables, &mut new_opcodes);
+    let sum = add(&lhs, &rhs, &mut variables, &mut new_opcodes);
+
+    // constrain the outputs to be the same as the result of the circuit
+    new_opcodes.push(Opcode::Arithmetic(&sum.x - outputs.0));
+    new_opcodes.push(Opcode::Arithmetic(&sum.y - outputs.1));
+
+    (variables.finalize(), new_opcodes)
+}
+
+/// Constrains `outputs` to be the product of a point on Grumpkin and `scalar`.
+pub fn variable_base_scalar_mul(
+    point_x: Expression,
+    point_y: Expression,
+    scalar: Expression,
+    outputs: (Witness, Witness),
+    num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    let (mut new_opcodes, scalar_bits, mut num_witness) =
+        bit_decomposition(scalar, FieldElement::max_num_bits(), num_witness);
+    let mut variables = VariableStore::new(&mut num_witness);
+
+    // The order of Grumpkin is larger than the field modulus, so a decomposition of the scalar which overflows the
+    // field would multiply the point by a different scalar.
+    let is_canonical = less_than_or_equal_constant(
+        &scalar_bits,
+        -FieldElement::one(),
+        &mut variables,
+        &mut new_opcodes,
+    );
+    new_opcodes.push(Opcode::Arithmetic(&is_canonical - &Expression::one()));
+
+    let point = point_on_curve(point_x, point_y, &mut variables, &mut new_opcodes);
+    let product = scalar_mul(&point, &scalar_bits, &mut variables, &mut new_opcodes);
+
+    // constrain the outputs to be the same as the result of the circuit
+    new_opcodes.push(Opcode::Arithmetic(&product.x - outputs.0));
+    new_opcodes.push(Opcode::Arithmetic(&product.y - outputs.1));
+
+    (variables.finalize(), new_opcodes)
+}
+
+/// Returns the point with coordinates `(x, y)`, constraining it to be on the curve unless it is `(0, 0)`.
+fn point_on_curve(
+    x: Expression,
+    y: Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let x_is_zero = is_zero(&x, variables, new_opcodes);
+    let y_is_zero = is_zero(&y, variables, new_opcodes);
+    let is_infinity: Expression =
+        mul_into_witness(&x_is_zero.into(), &y_is_zero.into(), variables, new_opcodes).into();
+
+    // (1 - is_infinity) * (y^2 - x^3 - b) = 0
+    let x_squared = mul_into_witness(&x, &x, variables, new_opcodes);
+    let x_cubed = mul_into_witness(&x_squared.into(), &x, variables, new_opcodes);
+    let y_squared = mul_into_witness(&y, &y, variables, new_opcodes);
+    let equation = (&Expression::from(y_squared) - x_cubed) - FieldElement::from(GRUMPKIN_B);
+    let constraint = (&(&Expression::one() - &is_infinity) * &equation)
+        .expect("both expressions should be linear");
+    new_opcodes.push(Opcode::Arithmetic(constraint));
+
+    Point { x, y, is_infinity }
+}
+
+/// Adds two points on the curve, handling the point at infinity, doubling and the sum of a point and its inverse.
+fn add(
+    lhs: &Point,
+    rhs: &Point,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let x_difference = &rhs.x - &lhs.x;
+    let y_difference = &rhs.y - &lhs.y;
+    let x_is_equal: Expression = is_zero(&x_difference, variables, new_opcodes).into();
+    let y_is_equal: Expression = is_zero(&y_difference, variables, new_opcodes).into();
+    let is_doubling: Expression =
+        mul_into_witness(&x_is_equal, &y_is_equal, variables, new_opcodes).into();
+    let is_inverse = &x_is_equal - &is_doubling;
+    let is_not_doubling = &Expression::one() - &is_doubling;
+
+    // The slope of the line through both points, or of the tangent when doubling.
+    let x1_squared: Expression = mul_into_witness(&lhs.x, &lhs.x, variables, new_opcodes).into();
+    let three_x1_squared = &x1_squared * FieldElement::from(3_i128);
+    let two_y1 = &lhs.y * FieldElement::from(2_i128);
+    let numerator =
+        &mul_linear(&is_not_doubling, &y_difference) + &mul_linear(&is_doubling, &three_x1_squared);
+    let denominator =
+        &mul_linear(&is_not_doubling, &x_difference) + &mul_linear(&is_doubling, &two_y1);
+    let slope: Expression =
+        unconstrained_div(&numerator, &denominator, variables, new_opcodes).into();
+
+    // (1 - x_is_equal) * (slope * (x2 - x1) - (y2 - y1)) = 0
+    let slope_times_x_difference = mul_into_witness(&slope, &x_difference, variables, new_opcodes);
+    new_opcodes.push(Opcode::Arithmetic(mul_linear(
+        &(&Expression::one() - &x_is_equal),
+        &(&Expression::from(slope_times_x_difference) - &y_difference),
+    )));
+    // is_doubling * (slope * 2 * y1 - 3 * x1^2) = 0
+    let slope_times_two_y1 = mul_into_witness(&slope, &two_y1, variables, new_opcodes);
+    new_opcodes.push(Opcode::Arithmetic(mul_linear(
+        &is_doubling,
+        &(&Expression::from(slope_times_two_y1) - &three_x1_squared),
+    )));
+
+    // The slope is unconstrained in the exceptional cases, in which the result is selected instead.
+    let sum = point_on_line(&slope, lhs, &rhs.x, variables, new_opcodes);
+    let sum = select(&is_inverse, &Point::infinity(), &sum, variables, new_opcodes);
+    let sum = select(&rhs.is_infinity, lhs, &sum, variables, new_opcodes);
+    select(&lhs.is_infinity, rhs, &sum, variables, new_opcodes)
+}
+
+/// Doubles a point on the curve.
+fn double(point: &Point, variables: &mut VariableStore, new_opcodes: &mut Vec<Opcode>) -> Point {
+    let x_squared: Expression = mul_into_witness(&point.x, &point.x, variables, new_opcodes).into();
+    let three_x_squared = &x_squared * FieldElement::from(3_i128);
+    let two_y = &point.y * FieldElement::from(2_i128);
+    let slope: Expression =
+        unconstrained_div(&three_x_squared, &two_y, variables, new_opcodes).into();
+
+    // slope * 2 * y - 3 * x^2 = 0, which holds for any slope at the point at infinity.
+    let slope_times_two_y = mul_linear(&slope, &two_y);
+    new_opcodes.push(Opcode::Arithmetic(&slope_times_two_y - &three_x_squared));
+
+    let doubled = point_on_line(&slope, point, &point.x, variables, new_opcodes);
+    select(&point.is_infinity, &Point::infinity(), &doubled, variables, new_opcodes)
+}
+
+/// Returns the third point on the line through `point` with gradient `slope` and the point with x-coordinate `other_x`,
+/// reflected in the x-axis.
+fn point_on_line(
+    slope: &Expression,
+    point: &Point,
+    other_x: &Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    // x3 = slope^2 - x1 - x2
+    let slope_squared = mul_linear(slope, slope);
+    let x3 = expr_into_witness(&(&(&slope_squared - &point.x) - other_x), variables, new_opcodes);
+    // y3 = slope * (x1 - x3) - y1
+    let slope_times_x_difference = mul_linear(slope, &(&point.x - x3));
+    let y3 = expr_into_witness(&(&slope_times_x_difference - &point.y), variables, new_opcodes);
+    Point { x: x3.into(), y: y3.into(), is_infinity: Expression::zero() }
+}
+
+/// Multiplies `point` by the scalar whose little-endian bits are `bits`.
+///
+/// The multiples `0 * point` to `15 * point` are computed ahead of time, then each window of four bits of the scalar,
+/// starting from the most significant, selects a multiple to add to the result after it has been doubled four times.
+fn scalar_mul(
+    point: &Point,
+    bits: &[Witness],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let mut table = vec![Point::infinity(), point.clone()];
+    while table.len() < 1 << WINDOW_SIZE {
+        let multiple = add(&table[table.len() - 1], point, variables, new_opcodes);
+        table.push(multiple);
+    }
+
+    let mut windows = bits.chunks(WINDOW_SIZE).rev();
+    let most_significant = windows.next().expect("scalar should have at least one bit");
+    let mut result = lookup(&table, most_significant, variables, new_opcodes);
+    for window in windows {
+        for _ in 0..window.len() {
+            result = double(&result, variables, new_opcodes);
+        }
+        let multiple = lookup(&table, window, variables, new_opcodes);
+        result = add(&result, &multiple, variables, new_opcodes);
+    }
+    result
+}
+
+/// Returns the entry of `table` at the index whose little-endian bits are `bits`.
+fn lookup(
+    table: &[Point],
+    bits: &[Witness],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let mut entries = table[..1 << bits.len()].to_vec();
+    for bit in bits {
+        let bit = Expression::from(*bit);
+        entries = entries
+            .chunks(2)
+            .map(|pair| select(&bit, &pair[1], &pair[0], variables, new_opcodes))
+            .collect();
+    }
+    entries.swap_remove(0)
+}
+
+/// Returns `if_true` if `condition` is one and `if_false` if it is zero.
+fn select(
+    condition: &Expression,
+    if_true: &Point,
+    if_false: &Point,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Point {
+    let mut select_expr = |if_true: &Expression, if_false: &Expression| {
+        let difference = mul_into_witness(condition, &(if_true - if_false), variables, new_opcodes);
+        if_false + &Expression::from(difference)
+    };
+    Point {
+        x: select_expr(&if_true.x, &if_false.x),
+        y: select_expr(&if_true.y, &if_false.y),
+        is_infinity: select_expr(&if_true.is_infinity, &if_false.is_infinity),
+    }
+}
+
+/// Returns `lhs * rhs`, where both expressions must be linear.
+fn mul_linear(lhs: &Expression, rhs: &Expression) -> Expression {
+    (lhs * rhs).expect("both expressions should be linear")
+}
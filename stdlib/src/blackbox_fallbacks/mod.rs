@@ -1,19 +1,39 @@
+mod aes128;
+mod bigint;
 mod blake2s;
+mod blake3;
+#[cfg(feature = "bn254")]
+mod eddsa;
+#[cfg(feature = "bn254")]
+mod embedded_curve;
 mod hash_to_field;
 mod keccak256;
 mod logic_fallbacks;
+#[cfg(feature = "bn254")]
+mod poseidon2;
 mod sha256;
+mod sha512;
 #[macro_use]
 mod uint;
 mod uint32;
 mod uint64;
 mod uint8;
 mod utils;
+pub use aes128::{aes128_cbc_encrypt, aes128_ctr_encrypt};
+pub use bigint::{bigint_add, bigint_mod, bigint_mul};
 pub use blake2s::blake2s;
+pub use blake3::blake3;
+#[cfg(feature = "bn254")]
+pub use eddsa::eddsa_verify;
+#[cfg(feature = "bn254")]
+pub use embedded_curve::{embedded_curve_add, variable_base_scalar_mul};
 pub use hash_to_field::hash_to_field;
 pub use keccak256::keccak256;
 pub use logic_fallbacks::{and, range, xor};
+#[cfg(feature = "bn254")]
+pub use poseidon2::poseidon2_permutation;
 pub use sha256::sha256;
+pub use sha512::sha512;
 pub use uint32::UInt32;
 pub use uint64::UInt64;
 pub use uint8::UInt8;
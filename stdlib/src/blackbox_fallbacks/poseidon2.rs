@@ -0,0 +1,88 @@
+//! Poseidon2 permutation fallback function.
+use super::utils::{materialize, sbox};
+use crate::helpers::VariableStore;
+use acir::{
+    circuit::Opcode,
+    native_types::{Expression, Witness},
+    FieldElement,
+};
+use blackbox_solver::{Poseidon2Config, POSEIDON2_WIDTH};
+
+/// Constrains `outputs` to be the result of applying the Poseidon2 permutation to the state `inputs`.
+///
+/// Returns `None` if either `inputs` or `outputs` is not a state of [`POSEIDON2_WIDTH`] elements.
+pub fn poseidon2_permutation(
+    inputs: Vec<Expression>,
+    outputs: Vec<Witness>,
+    mut num_witness: u32,
+) -> Option<(u32, Vec<Opcode>)> {
+    if inputs.len() != POSEIDON2_WIDTH || outputs.len() != POSEIDON2_WIDTH {
+        return None;
+    }
+
+    let config = Poseidon2Config::bn254();
+    let mut new_opcodes = Vec::new();
+    let mut variables = VariableStore::new(&mut num_witness);
+
+    let (first_full_rounds, last_full_rounds) =
+        config.full_round_constants.split_at(config.full_round_constants.len() / 2);
+
+    let mut state = external_round(&config, &inputs);
+    for constants in first_full_rounds {
+        state = full_round(&config, &state, constants, &mut variables, &mut new_opcodes);
+    }
+    for constant in &config.partial_round_constants {
+        state = materialize(&state, &mut variables, &mut new_opcodes);
+        state[0] = sbox(state[0].clone() + *constant, &mut variables, &mut new_opcodes);
+        state = internal_round(&config, &state);
+    }
+    for constants in last_full_rounds {
+        state = materialize(&state, &mut variables, &mut new_opcodes);
+        state = full_round(&config, &state, constants, &mut variables, &mut new_opcodes);
+    }
+
+    // constrain the outputs to be the same as the result of the circuit
+    for (output, expr) in outputs.into_iter().zip(state) {
+        new_opcodes.push(Opcode::Arithmetic(&expr - output));
+    }
+
+    Some((variables.finalize(), new_opcodes))
+}
+
+fn full_round(
+    config: &Poseidon2Config,
+    state: &[Expression],
+    constants: &[FieldElement; POSEIDON2_WIDTH],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Vec<Expression> {
+    let state: Vec<Expression> = state
+        .iter()
+        .zip(constants)
+        .map(|(expr, constant)| sbox(expr.clone() + *constant, variables, new_opcodes))
+        .collect();
+    external_round(config, &state)
+}
+
+/// Multiplies the state by the matrix used in full rounds.
+fn external_round(config: &Poseidon2Config, state: &[Expression]) -> Vec<Expression> {
+    config
+        .external_matrix
+        .iter()
+        .map(|row| {
+            row.iter().zip(state).fold(Expression::default(), |sum, (coefficient, expr)| {
+                &sum + &(expr * *coefficient)
+            })
+        })
+        .collect()
+}
+
+/// Multiplies the state by the matrix used in partial rounds.
+fn internal_round(config: &Poseidon2Config, state: &[Expression]) -> Vec<Expression> {
+    let sum = state.iter().fold(Expression::default(), |sum, expr| &sum + expr);
+    state
+        .iter()
+        .zip(config.internal_matrix_diagonal)
+        .map(|(expr, diagonal)| &(expr * diagonal) + &sum)
+        .collect()
+}
@@ -1,6 +1,8 @@
 use crate::helpers::VariableStore;
 use acir::{
+    brillig::{self, RegisterIndex},
     circuit::{
+        brillig::{Brillig, BrilligInputs, BrilligOutputs},
         directives::Directive,
         opcodes::{BlackBoxFuncCall, FunctionInput},
         Opcode,
@@ -173,3 +175,152 @@ pub(crate) fn byte_decomposition(
 
     (new_opcodes, vector, variables.finalize())
 }
+
+/// Returns a witness constrained to equal `input^5`, the S-box of the Poseidon permutations, where `input` must be linear.
+#[cfg(feature = "bn254")]
+pub(crate) fn sbox(
+    input: Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Expression {
+    // Constrain the input to a single witness so that each product has a single multiplication term.
+    let input: Expression = expr_into_witness(&input, variables, new_opcodes).into();
+    let square = mul_into_witness(&input, &input, variables, new_opcodes).into();
+    let fourth_power = mul_into_witness(&square, &square, variables, new_opcodes).into();
+    mul_into_witness(&fourth_power, &input, variables, new_opcodes).into()
+}
+
+/// Returns a witness constrained to equal `lhs * rhs`, where both expressions must be linear.
+pub(crate) fn mul_into_witness(
+    lhs: &Expression,
+    rhs: &Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Witness {
+    let expr = (lhs * rhs).expect("both expressions should be linear");
+    expr_into_witness(&expr, variables, new_opcodes)
+}
+
+/// Returns a witness constrained to equal `expr`.
+pub(crate) fn expr_into_witness(
+    expr: &Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Witness {
+    let witness = variables.new_variable();
+    new_opcodes.push(Opcode::Arithmetic(expr - witness));
+    witness
+}
+
+/// Replaces each expression in the state of a permutation with a witness constrained to equal it,
+/// preventing the expressions from growing as they are combined in each round.
+#[cfg(feature = "bn254")]
+pub(crate) fn materialize(
+    state: &[Expression],
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Vec<Expression> {
+    state.iter().map(|expr| expr_into_witness(expr, variables, new_opcodes).into()).collect()
+}
+
+/// Returns an expression which is one if the scalar whose little-endian bits are `bits` is less than `constant`
+/// and zero otherwise.
+#[cfg(feature = "bn254")]
+pub(crate) fn less_than_constant(
+    bits: &[Witness],
+    constant: FieldElement,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Expression {
+    compare_with_constant(bits, constant, variables, new_opcodes).0
+}
+
+/// Returns an expression which is one if the scalar whose little-endian bits are `bits` is at most `constant`
+/// and zero otherwise.
+#[cfg(feature = "bn254")]
+pub(crate) fn less_than_or_equal_constant(
+    bits: &[Witness],
+    constant: FieldElement,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Expression {
+    let (less, equal) = compare_with_constant(bits, constant, variables, new_opcodes);
+    &less + &equal
+}
+
+/// Returns a pair of expressions which are one if the scalar whose little-endian bits are `bits` is respectively
+/// less than or equal to `constant`, and zero otherwise.
+#[cfg(feature = "bn254")]
+fn compare_with_constant(
+    bits: &[Witness],
+    constant: FieldElement,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> (Expression, Expression) {
+    let constant_bits = constant.bits();
+    let constant_bits = &constant_bits[constant_bits.len() - bits.len()..];
+
+    // Compare the bits from the most significant, tracking whether the prefixes seen so far are equal.
+    let mut less = Expression::zero();
+    let mut equal = Expression::one();
+    for (bit, constant_bit) in bits.iter().rev().zip(constant_bits) {
+        let equal_and_bit: Expression =
+            mul_into_witness(&equal, &Expression::from(*bit), variables, new_opcodes).into();
+        if *constant_bit {
+            less = expr_into_witness(&(&(&less + &equal) - &equal_and_bit), variables, new_opcodes)
+                .into();
+            equal = equal_and_bit;
+        } else {
+            equal = expr_into_witness(&(&equal - &equal_and_bit), variables, new_opcodes).into();
+        }
+    }
+    (less, equal)
+}
+
+/// Returns a witness which is one if `expr` is zero and zero otherwise, where `expr` must be linear.
+pub(crate) fn is_zero(
+    expr: &Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Witness {
+    let inverse = unconstrained_div(&Expression::one(), expr, variables, new_opcodes);
+
+    // is_zero = 1 - expr * inverse
+    let expr_times_inverse =
+        (expr * &Expression::from(inverse)).expect("expression should be linear");
+    let is_zero =
+        expr_into_witness(&(&Expression::one() - &expr_times_inverse), variables, new_opcodes);
+
+    // expr * is_zero = 0
+    let constraint = (expr * &Expression::from(is_zero)).expect("expression should be linear");
+    new_opcodes.push(Opcode::Arithmetic(constraint));
+    is_zero
+}
+
+/// Returns a witness which Brillig assigns `numerator / denominator`, or zero if `denominator` is zero.
+///
+/// The witness is not constrained, so the caller must constrain it to the expected value.
+pub(crate) fn unconstrained_div(
+    numerator: &Expression,
+    denominator: &Expression,
+    variables: &mut VariableStore,
+    new_opcodes: &mut Vec<Opcode>,
+) -> Witness {
+    let quotient = variables.new_variable();
+    new_opcodes.push(Opcode::Brillig(Brillig {
+        inputs: vec![
+            BrilligInputs::Single(numerator.clone()),
+            BrilligInputs::Single(denominator.clone()),
+        ],
+        outputs: vec![BrilligOutputs::Simple(quotient)],
+        foreign_call_results: vec![],
+        bytecode: vec![brillig::Opcode::BinaryFieldOp {
+            op: brillig::BinaryFieldOp::Div,
+            lhs: RegisterIndex::from(0),
+            rhs: RegisterIndex::from(1),
+            destination: RegisterIndex::from(0),
+        }],
+        predicate: None,
+    }));
+    quotient
+}
@@ -0,0 +1,42 @@
+//! Gadgets for verifying Ethereum signatures.
+use crate::helpers::VariableStore;
+use acir::{
+    circuit::{
+        opcodes::{BlackBoxFuncCall, FunctionInput},
+        Opcode,
+    },
+    native_types::{Expression, Witness},
+};
+
+/// The number of bytes in an address.
+const ADDRESS_SIZE: usize = 20;
+
+/// Constrains `address` to be the 20 byte Ethereum address of the public key whose big-endian coordinates are
+/// `public_key_x` and `public_key_y`, such as those written by an `EcdsaSecp256k1Recover` opcode.
+///
+/// The address is the last 20 bytes of the Keccak256 hash of the concatenated coordinates.
+pub fn address_from_public_key(
+    public_key_x: &[Witness],
+    public_key_y: &[Witness],
+    address: &[Witness],
+    mut num_witness: u32,
+) -> (u32, Vec<Opcode>) {
+    assert_eq!(address.len(), ADDRESS_SIZE, "expected an address of {ADDRESS_SIZE} bytes");
+    let mut new_opcodes = Vec::new();
+    let mut variables = VariableStore::new(&mut num_witness);
+
+    let digest: Vec<Witness> = (0..32).map(|_| variables.new_variable()).collect();
+    new_opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall::Keccak256 {
+        inputs: public_key_x
+            .iter()
+            .chain(public_key_y)
+            .map(|witness| FunctionInput { witness: *witness, num_bits: 8 })
+            .collect(),
+        outputs: digest.clone(),
+    }));
+    for (address_byte, digest_byte) in address.iter().zip(&digest[digest.len() - ADDRESS_SIZE..]) {
+        new_opcodes.push(Opcode::Arithmetic(&Expression::from(*address_byte) - *digest_byte));
+    }
+
+    (variables.finalize(), new_opcodes)
+}
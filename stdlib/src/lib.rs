@@ -2,4 +2,5 @@
 #![warn(unreachable_pub)]
 
 pub mod blackbox_fallbacks;
+pub mod ethereum;
 pub mod helpers;